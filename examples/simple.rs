@@ -1,7 +1,7 @@
-#![feature(if_let_guard)]
-
-use crossterm::event::{Event as CTEvent, KeyCode};
-use gland::{forward_handle_event, id, Component, Compositor, Context, Event, Id, LayerId};
+use gland::{
+    forward_handle_event, id, key, ui, Component, Compositor, Context, Event, Id, KeyCode, LayerId,
+    TerminalEvent,
+};
 use ratatui::{
     prelude::{Buffer, CrosstermBackend, Rect},
     style::{Color, Style},
@@ -47,7 +47,7 @@ impl Component<AppState> for MainScreen {
     fn handle_event(&mut self, event: &mut Event, cx: &mut Context<AppState>) {
         forward_handle_event!(event, cx, self.input);
 
-        if let Event::Terminal(CTEvent::Key(ke)) = event {
+        if let Event::Terminal(TerminalEvent::Key(ke)) = event {
             match ke.code {
                 KeyCode::Esc => cx.add_callback(|cc| cc.exit()),
                 KeyCode::Tab => {
@@ -106,13 +106,18 @@ impl<S: Send + 'static> Component<S> for Popup {
     }
 
     fn handle_event(&mut self, event: &mut Event, cx: &mut Context<S>) {
+        if event.consume_if_key(key!("esc")) {
+            let id = id!(S, self);
+            cx.add_callback(move |cc| cc.remove_all(id));
+            return;
+        }
+        if event.consume_if_key(key!("backspace")) {
+            self.text.pop();
+            return;
+        }
+
         match event {
-            Event::Terminal(CTEvent::Key(ke)) if ke.code == KeyCode::Esc => {
-                let id = id!(S, self);
-                cx.add_callback(move |cc| cc.remove_all(id));
-                event.consume();
-            }
-            Event::Terminal(CTEvent::Key(ke)) if let KeyCode::Char(ref c) = ke.code => {
+            Event::Terminal(TerminalEvent::Key(ke)) if let KeyCode::Char(ref c) = ke.code => {
                 self.text.push(*c);
                 // If you completes text to `clear` then we clear the text after 1 second.
 
@@ -132,10 +137,6 @@ impl<S: Send + 'static> Component<S> for Popup {
 
                 event.consume();
             }
-            Event::Terminal(CTEvent::Key(ke)) if matches!(ke.code, KeyCode::Backspace) => {
-                self.text.pop();
-                event.consume();
-            }
             _ => {}
         }
     }
@@ -158,7 +159,7 @@ impl Component<AppState> for Input {
     }
 
     fn handle_event(&mut self, event: &mut Event, cx: &mut Context<AppState>) {
-        if let Event::Terminal(CTEvent::Key(ke)) = event {
+        if let Event::Terminal(TerminalEvent::Key(ke)) = event {
             match ke.code {
                 KeyCode::Char(ch) => {
                     cx.state_mut().text.push(ch);
@@ -176,19 +177,17 @@ impl Component<AppState> for Input {
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let mut comp: Compositor<AppState> = Compositor::with_state(AppState {
-        text: "Write to modify the text, press enter to increment".to_owned(),
-        start: Instant::now(),
-    })
-    .with_event_stream();
-
-    comp.replace_at(
-        LayerId::FOREGROUND,
-        MainScreen {
+    let comp: Compositor<AppState> = ui! {
+        Compositor::with_state(AppState {
+            text: "Write to modify the text, press enter to increment".to_owned(),
+            start: Instant::now(),
+        })
+        .with_event_stream(),
+        FOREGROUND: MainScreen {
             input: Input,
             counter: 0,
         },
-    );
+    };
     comp.run(CrosstermBackend::new(io::stdout())).await?;
 
     Ok(())
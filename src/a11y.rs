@@ -0,0 +1,19 @@
+/// Urgency of an [`Announcement`], mirrors ARIA live regions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Announced when the user is idle, e.g. status updates.
+    #[default]
+    Polite,
+    /// Interrupts the user, e.g. errors.
+    Assertive,
+}
+
+/// Text for screen readers or braille displays, see [`Context::announce`](crate::Context::announce).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Announcement {
+    pub text: String,
+    pub priority: Priority,
+}
+
+/// Receives announcements made by components.
+pub(crate) type Announcer = Box<dyn FnMut(Announcement)>;
@@ -0,0 +1,292 @@
+use crate::{blit_cell, unwritten};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+};
+use std::{any::Any, time::Duration};
+
+/// Curve mapping linear progress from 0 to 1 onto animation progress.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// Starts slow and speeds up.
+    EaseIn,
+    /// Starts fast and slows down, e.g. for things sliding in.
+    EaseOut,
+    /// Starts and ends slow.
+    EaseInOut,
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    /// Maps progress `t` from 0 to 1 onto the curve.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1. - (1. - t).powi(3),
+            Self::EaseInOut if t < 0.5 => 4. * t * t * t,
+            Self::EaseInOut => 1. - (-2. * t + 2.).powi(3) / 2.,
+            Self::Custom(f) => f(t),
+        }
+    }
+}
+
+/// Values that can be interpolated.
+pub trait Lerp {
+    /// Returns the value at `t` between `self` at 0 and `to` at 1.
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t as f64
+    }
+}
+
+impl Lerp for u16 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        (*self as f32).lerp(&(*to as f32), t).round() as u16
+    }
+}
+
+impl Lerp for Rect {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Rect::new(
+            self.x.lerp(&to.x, t),
+            self.y.lerp(&to.y, t),
+            self.width.lerp(&to.width, t),
+            self.height.lerp(&to.height, t),
+        )
+    }
+}
+
+/// RGB colors are blended, other colors switch halfway.
+impl Lerp for Color {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        match (*self, *to) {
+            (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+                let channel = |a: u8, b: u8| (a as f32).lerp(&(b as f32), t).round() as u8;
+                Color::Rgb(channel(r1, r2), channel(g1, g2), channel(b1, b2))
+            }
+            _ if t < 0.5 => *self,
+            _ => *to,
+        }
+    }
+}
+
+/// Value moving toward a target over time, advance it on every [`Event::AnimationFrame`](crate::Event::AnimationFrame).
+///
+/// ```
+/// # use gland::{Animated, Easing};
+/// # use std::time::Duration;
+/// let mut offset = Animated::new(0.);
+/// offset.animate_to(10., Duration::from_millis(100), Easing::Linear);
+///
+/// assert!(offset.advance(Duration::from_millis(50)));
+/// assert_eq!(offset.value(), 5.);
+/// assert!(!offset.advance(Duration::from_millis(50)));
+/// assert_eq!(offset.value(), 10.);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Animated<T> {
+    from: T,
+    to: T,
+    easing: Easing,
+    duration: Duration,
+    elapsed: Duration,
+}
+
+impl<T: Lerp + Clone> Animated<T> {
+    /// Creates value that is not animating.
+    pub fn new(value: T) -> Self {
+        Self::transition(value.clone(), value, Duration::ZERO, Easing::Linear)
+    }
+
+    /// Creates value animating from `from` to `to`.
+    pub fn transition(from: T, to: T, duration: Duration, easing: Easing) -> Self {
+        Self {
+            from,
+            to,
+            easing,
+            duration,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Starts animating from the current value to `to`.
+    pub fn animate_to(&mut self, to: T, duration: Duration, easing: Easing) {
+        *self = Self::transition(self.value(), to, duration, easing);
+    }
+
+    /// Jumps to `value`, stopping the animation.
+    pub fn set(&mut self, value: T) {
+        *self = Self::new(value);
+    }
+
+    /// Moves the animation forward by `elapsed`, returns `true` while it is still running.
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        self.elapsed = self.elapsed.saturating_add(elapsed).min(self.duration);
+        self.is_running()
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> T {
+        self.from.lerp(&self.to, self.easing.apply(self.progress()))
+    }
+
+    /// Returns the value the animation ends at.
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+
+    /// Returns linear progress from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.;
+        }
+        self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.elapsed < self.duration
+    }
+}
+
+/// Edge of the screen components slide from or to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// Visual effect of a [`Transition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Moves the component in from or out to the edge of the screen.
+    Slide(Edge),
+    /// Blends RGB colors of the component into its background, other colors are dimmed.
+    Fade,
+}
+
+/// Animation played when a component is mounted or removed,
+/// see [`Component::enter_transition`](crate::Component::enter_transition).
+#[derive(Debug, Clone, Copy)]
+pub struct Transition {
+    pub effect: Effect,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Transition {
+    pub fn slide(edge: Edge, duration: Duration) -> Self {
+        Self {
+            effect: Effect::Slide(edge),
+            duration,
+            easing: Easing::EaseOut,
+        }
+    }
+
+    pub fn fade(duration: Duration) -> Self {
+        Self {
+            effect: Effect::Fade,
+            duration,
+            easing: Easing::Linear,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Copies cells written to `view` into `buf` with the effect applied,
+    /// `shown` goes from 0 when the component is hidden to 1 when it is fully visible.
+    pub(crate) fn blit(&self, shown: f32, view: &Buffer, buf: &mut Buffer) {
+        let (area, screen) = (view.area, buf.area);
+        let hidden = 1. - shown.clamp(0., 1.);
+        // Distance to move the component by to get it off the screen.
+        let (dx, dy) = match self.effect {
+            Effect::Slide(Edge::Left) => (-(area.right() as f32 - screen.left() as f32), 0.),
+            Effect::Slide(Edge::Right) => (screen.right() as f32 - area.left() as f32, 0.),
+            Effect::Slide(Edge::Top) => (0., -(area.bottom() as f32 - screen.top() as f32)),
+            Effect::Slide(Edge::Bottom) => (0., screen.bottom() as f32 - area.top() as f32),
+            Effect::Fade => (0., 0.),
+        };
+        let (dx, dy) = ((dx * hidden).round() as i32, (dy * hidden).round() as i32);
+
+        let unwritten = unwritten();
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = view.get(x, y);
+                let (tx, ty) = (x as i32 + dx, y as i32 + dy);
+                if *cell == unwritten
+                    || !(screen.left() as i32..screen.right() as i32).contains(&tx)
+                    || !(screen.top() as i32..screen.bottom() as i32).contains(&ty)
+                {
+                    continue;
+                }
+
+                let target = buf.get_mut(tx as u16, ty as u16);
+                blit_cell(cell, target);
+                if self.effect == Effect::Fade && hidden > 0. {
+                    match (cell.fg, cell.bg) {
+                        (fg @ Color::Rgb(..), bg @ Color::Rgb(..)) => {
+                            target.fg = fg.lerp(&bg, hidden);
+                        }
+                        _ => target.modifier.insert(Modifier::DIM),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Message sent to a component on every frame of an animation started with
+/// [`Context::animate`](crate::Context::animate), receive it in [`Component::handle_message`](crate::Component::handle_message).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationStep<T> {
+    pub value: T,
+    /// Whether this is the last step.
+    pub finished: bool,
+}
+
+/// Animation run by the compositor, with the type of its value erased.
+pub(crate) trait Animation {
+    /// Moves the animation forward, returns [`AnimationStep`] to send.
+    fn step(&mut self, elapsed: Duration) -> Box<dyn Any>;
+
+    fn is_running(&self) -> bool;
+
+    /// Returns the value as [`Any`] to check its type.
+    fn value_any(&self) -> &dyn Any;
+}
+
+impl<T: Lerp + Clone + 'static> Animation for Animated<T> {
+    fn step(&mut self, elapsed: Duration) -> Box<dyn Any> {
+        let finished = !self.advance(elapsed);
+        Box::new(AnimationStep {
+            value: self.value(),
+            finished,
+        })
+    }
+
+    fn is_running(&self) -> bool {
+        Animated::is_running(self)
+    }
+
+    fn value_any(&self) -> &dyn Any {
+        &self.to
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    backend::{Backend, WindowSize},
+    buffer::Cell,
+    layout::Size,
+    prelude::Rect,
+    style::{Color, Modifier},
+};
+use std::{fmt::Write as _, io};
+
+/// Output that [`AnsiBackend`] writes escape sequences to.
+pub trait AnsiOutput: io::Write {
+    /// Returns the size of the output in `(columns, rows)`.
+    fn size(&self) -> io::Result<(u16, u16)>;
+}
+
+/// Backend that writes frames as ANSI escape sequences into any [`AnsiOutput`].
+/// Unlike crossterm backend it never touches the local terminal, so it can drive remote or embedded terminals.
+pub struct AnsiBackend<W: AnsiOutput> {
+    output: W,
+    cursor: (u16, u16),
+}
+
+impl<W: AnsiOutput> AnsiBackend<W> {
+    /// Creates new backend writing to `output`.
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            cursor: (0, 0),
+        }
+    }
+
+    /// Returns the output immutably.
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Returns the output mutably.
+    pub fn output_mut(&mut self) -> &mut W {
+        &mut self.output
+    }
+}
+
+impl<W: AnsiOutput> Backend for AnsiBackend<W> {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let mut out = String::new();
+        write_cells(&mut out, content);
+        self.output.write_all(out.as_bytes())
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.output.write_all(b"\x1b[?25l")
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.output.write_all(b"\x1b[?25h")
+    }
+
+    fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+        Ok(self.cursor)
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.cursor = (x, y);
+        write!(self.output, "\x1b[{};{}H", y + 1, x + 1)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.output.write_all(b"\x1b[2J")
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        let (width, height) = self.output.size()?;
+        Ok(Rect::new(0, 0, width, height))
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        let (width, height) = self.output.size()?;
+        Ok(WindowSize {
+            columns_rows: Size { width, height },
+            pixels: Size {
+                width: 0,
+                height: 0,
+            },
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Writes cells positioning the cursor only when they are not adjacent, resets style at the end.
+pub(crate) fn write_cells<'a>(out: &mut String, cells: impl Iterator<Item = (u16, u16, &'a Cell)>) {
+    let mut last_pos: Option<(u16, u16)> = None;
+    let mut last_style = None;
+
+    for (x, y, cell) in cells {
+        if !matches!(last_pos, Some(p) if x == p.0 + 1 && y == p.1) {
+            _ = write!(out, "\x1b[{};{}H", y + 1, x + 1);
+        }
+        last_pos = Some((x, y));
+
+        let style = (cell.fg, cell.bg, cell.modifier);
+        if last_style != Some(style) {
+            write_sgr(out, cell.fg, cell.bg, cell.modifier);
+            last_style = Some(style);
+        }
+        out.push_str(cell.symbol());
+    }
+
+    out.push_str("\x1b[0m");
+}
+
+/// Writes SGR sequence that resets attributes and then applies colors and modifiers.
+pub(crate) fn write_sgr(out: &mut String, fg: Color, bg: Color, modifier: Modifier) {
+    out.push_str("\x1b[0");
+
+    const MODIFIERS: [(Modifier, u8); 9] = [
+        (Modifier::BOLD, 1),
+        (Modifier::DIM, 2),
+        (Modifier::ITALIC, 3),
+        (Modifier::UNDERLINED, 4),
+        (Modifier::SLOW_BLINK, 5),
+        (Modifier::RAPID_BLINK, 6),
+        (Modifier::REVERSED, 7),
+        (Modifier::HIDDEN, 8),
+        (Modifier::CROSSED_OUT, 9),
+    ];
+    for (m, code) in MODIFIERS {
+        if modifier.contains(m) {
+            _ = write!(out, ";{code}");
+        }
+    }
+
+    write_color(out, fg, 30);
+    write_color(out, bg, 40);
+    out.push('m');
+}
+
+fn write_color(out: &mut String, color: Color, base: u8) {
+    let code = match color {
+        Color::Reset => return,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+        Color::Rgb(r, g, b) => {
+            _ = write!(out, ";{};2;{r};{g};{b}", base + 8);
+            return;
+        }
+        Color::Indexed(i) => {
+            _ = write!(out, ";{};5;{i}", base + 8);
+            return;
+        }
+    };
+    _ = write!(out, ";{code}");
+}
+
+/// Parses SGR mouse report `ESC [ < b ; x ; y (M | m)`.
+pub(crate) fn parse_sgr_mouse(seq: &str) -> Option<MouseEvent> {
+    let body = seq.strip_prefix("\x1b[<")?;
+    let pressed = body.ends_with('M');
+    let mut parts = body
+        .strip_suffix(['M', 'm'])?
+        .split(';')
+        .map(|p| p.parse::<u16>().ok());
+
+    let (cb, column, row) = (parts.next()??, parts.next()??, parts.next()??);
+
+    let mut modifiers = KeyModifiers::NONE;
+    modifiers.set(KeyModifiers::SHIFT, cb & 4 != 0);
+    modifiers.set(KeyModifiers::ALT, cb & 8 != 0);
+    modifiers.set(KeyModifiers::CONTROL, cb & 16 != 0);
+
+    let button = match cb & 3 {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        _ => MouseButton::Right,
+    };
+    let kind = match (cb & 0b1110_0011, pressed) {
+        (64, _) => MouseEventKind::ScrollUp,
+        (65, _) => MouseEventKind::ScrollDown,
+        (66, _) => MouseEventKind::ScrollLeft,
+        (67, _) => MouseEventKind::ScrollRight,
+        (35, _) => MouseEventKind::Moved,
+        (32..=34, _) => MouseEventKind::Drag(button),
+        (_, true) => MouseEventKind::Down(button),
+        (_, false) => MouseEventKind::Up(button),
+    };
+
+    Some(MouseEvent {
+        kind,
+        column: column.saturating_sub(1),
+        row: row.saturating_sub(1),
+        modifiers,
+    })
+}
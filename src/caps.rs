@@ -0,0 +1,161 @@
+use std::env;
+
+/// Terminal multiplexer the application runs inside of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Multiplexer {
+    Tmux,
+    Screen,
+    Zellij,
+}
+
+/// Escape sequence used to show desktop notifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationProtocol {
+    /// `OSC 9`, message only.
+    Osc9,
+    /// `OSC 777`, title and body.
+    Osc777,
+}
+
+/// Terminal features detected at startup, higher level features check them to degrade
+/// instead of emitting sequences the terminal doesn't understand.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TerminalCaps {
+    /// Multiplexer between the application and the terminal.
+    pub multiplexer: Option<Multiplexer>,
+    /// Name of the terminal emulator if it could be recognized, e.g. `kitty` or `WezTerm`.
+    pub terminal: Option<String>,
+    /// Synchronized output (mode 2026), frames are drawn atomically without tearing.
+    pub synchronized_output: bool,
+    /// Kitty keyboard protocol, allows to receive key release events and disambiguate keys.
+    pub kitty_keyboard: bool,
+    /// Clipboard access with OSC 52.
+    pub osc52: bool,
+    /// Progress reporting in the tab or taskbar with OSC 9;4.
+    pub progress: bool,
+    /// Mouse pointer shape with OSC 22.
+    pub pointer_shape: bool,
+    /// Desktop notifications, `None` if the terminal doesn't support them.
+    pub notifications: Option<NotificationProtocol>,
+    /// 24-bit colors.
+    pub truecolor: bool,
+}
+
+impl TerminalCaps {
+    /// Detects capabilities from the environment variables of the current process.
+    pub fn detect() -> Self {
+        Self::from_env(|name| env::var(name).ok())
+    }
+
+    /// Detects capabilities from environment variables returned by `var`.
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let multiplexer = if var("TMUX").is_some() {
+            Some(Multiplexer::Tmux)
+        } else if var("ZELLIJ").is_some() {
+            Some(Multiplexer::Zellij)
+        } else if var("STY").is_some() {
+            Some(Multiplexer::Screen)
+        } else {
+            None
+        };
+
+        let term = var("TERM").unwrap_or_default();
+        let terminal = if var("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" {
+            Some("kitty".to_owned())
+        } else if var("WT_SESSION").is_some() {
+            Some("WindowsTerminal".to_owned())
+        } else if term == "alacritty" || var("ALACRITTY_WINDOW_ID").is_some() {
+            Some("alacritty".to_owned())
+        } else if term.starts_with("foot") {
+            Some("foot".to_owned())
+        } else if term == "xterm-ghostty" {
+            Some("ghostty".to_owned())
+        } else if term.starts_with("rxvt") {
+            Some("rxvt".to_owned())
+        } else if var("ConEmuANSI").is_some_and(|v| v == "ON") {
+            Some("ConEmu".to_owned())
+        } else {
+            // Multiplexers overwrite `TERM_PROGRAM` with their own name.
+            var("TERM_PROGRAM").filter(|p| p != "tmux" && p != "screen")
+        };
+
+        let known = |names: &[&str]| {
+            terminal
+                .as_deref()
+                .is_some_and(|t| names.iter().any(|n| t.eq_ignore_ascii_case(n)))
+        };
+        let direct = multiplexer.is_none();
+
+        Self {
+            synchronized_output: direct
+                && known(&[
+                    "kitty",
+                    "WezTerm",
+                    "alacritty",
+                    "foot",
+                    "ghostty",
+                    "iTerm.app",
+                    "WindowsTerminal",
+                    "contour",
+                ]),
+            kitty_keyboard: direct && known(&["kitty", "WezTerm", "alacritty", "foot", "ghostty"]),
+            // Multiplexers forward OSC 52 with passthrough, see `wrap_passthrough`.
+            osc52: multiplexer != Some(Multiplexer::Zellij)
+                && known(&[
+                    "kitty",
+                    "WezTerm",
+                    "alacritty",
+                    "foot",
+                    "ghostty",
+                    "iTerm.app",
+                    "WindowsTerminal",
+                    "vscode",
+                ]),
+            progress: multiplexer != Some(Multiplexer::Zellij)
+                && known(&["WindowsTerminal", "ConEmu", "ghostty", "WezTerm"]),
+            pointer_shape: direct && known(&["kitty", "foot", "ghostty"]),
+            notifications: if multiplexer == Some(Multiplexer::Zellij) {
+                None
+            } else if known(&["foot", "ghostty", "WezTerm", "rxvt"]) {
+                Some(NotificationProtocol::Osc777)
+            } else if known(&["kitty", "iTerm.app"]) {
+                Some(NotificationProtocol::Osc9)
+            } else {
+                None
+            },
+            truecolor: var("COLORTERM").is_some_and(|c| c == "truecolor" || c == "24bit"),
+            multiplexer,
+            terminal,
+        }
+    }
+
+    /// Wraps escape sequence so the multiplexer passes it through to the outer terminal.
+    /// Screen limits the length of passthrough strings, long sequences are split into multiple of them.
+    pub fn wrap_passthrough(&self, seq: &str) -> String {
+        /// Longest passthrough string screen accepts is 768 bytes, stay well below it.
+        const SCREEN_CHUNK: usize = 76;
+
+        match self.multiplexer {
+            Some(Multiplexer::Tmux) => {
+                format!("\x1bPtmux;{}\x1b\\", seq.replace('\x1b', "\x1b\x1b"))
+            }
+            Some(Multiplexer::Screen) => {
+                let mut out = String::with_capacity(seq.len() + seq.len() / SCREEN_CHUNK * 4 + 4);
+                let mut rest = seq;
+                while !rest.is_empty() {
+                    let mut end = rest.len().min(SCREEN_CHUNK);
+                    while !rest.is_char_boundary(end) {
+                        end += 1;
+                    }
+
+                    out.push_str("\x1bP");
+                    out.push_str(&rest[..end]);
+                    out.push_str("\x1b\\");
+                    rest = &rest[end..];
+                }
+                out
+            }
+            _ => seq.to_owned(),
+        }
+    }
+}
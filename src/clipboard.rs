@@ -0,0 +1,45 @@
+use crate::TerminalCaps;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// System clipboard of the terminal accessed with OSC 52, works over SSH and in multiplexers.
+/// Sequences are written to the terminal after the next frame.
+pub struct Clipboard<'cx> {
+    caps: &'cx TerminalCaps,
+    escapes: &'cx mut Vec<u8>,
+}
+
+impl<'cx> Clipboard<'cx> {
+    pub(crate) fn new(caps: &'cx TerminalCaps, escapes: &'cx mut Vec<u8>) -> Self {
+        Self { caps, escapes }
+    }
+
+    /// Copies `text` to the clipboard, returns `false` if the terminal doesn't support OSC 52.
+    pub fn set(&mut self, text: &str) -> bool {
+        self.write(&STANDARD.encode(text))
+    }
+
+    /// Asks the terminal for the clipboard contents, returns `false` if the terminal doesn't support OSC 52.
+    /// Contents are reported as [`TerminalEvent::Clipboard`](crate::TerminalEvent::Clipboard),
+    /// only [`InputParser`](crate::InputParser) recognizes the reply. Many terminals deny reading the clipboard.
+    pub fn request(&mut self) -> bool {
+        self.write("?")
+    }
+
+    fn write(&mut self, payload: &str) -> bool {
+        if !self.caps.osc52 {
+            return false;
+        }
+
+        let seq = self
+            .caps
+            .wrap_passthrough(&format!("\x1b]52;c;{payload}\x07"));
+        self.escapes.extend_from_slice(seq.as_bytes());
+        true
+    }
+}
+
+/// Decodes the payload of OSC 52 reply.
+pub(crate) fn decode_osc52(payload: &str) -> Option<String> {
+    let (_, data) = payload.strip_prefix("52;")?.split_once(';')?;
+    String::from_utf8(STANDARD.decode(data).ok()?).ok()
+}
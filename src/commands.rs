@@ -0,0 +1,239 @@
+use crate::{
+    Action, Component, Context, Event, Id, KeyCode, KeyEventKind, KeyModifiers, TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+/// Named command listed in the [`CommandPalette`], running it passes its action to
+/// [`Component::handle_action`], see [`Compositor::run_command`](crate::Compositor::run_command).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    pub action: Action,
+    pub description: String,
+}
+
+impl Command {
+    pub fn new(action: impl Into<Action>, description: impl Into<String>) -> Self {
+        Self {
+            action: action.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// Popup to search commands by typing parts of their descriptions,
+/// see [`Compositor::show_command_palette`](crate::Compositor::show_command_palette).
+///
+/// `Up` and `Down` select a command, `Enter` runs it and `Esc` dismisses the popup.
+/// Consumes key events while shown.
+pub struct CommandPalette {
+    title: String,
+    /// Commands with the keys bound to them.
+    commands: Vec<(Command, String)>,
+    query: String,
+    /// Indices of commands matching the query, best first.
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl CommandPalette {
+    /// Creates palette listing `commands` under `title`, each with a hint of the keys bound to it.
+    pub fn new(title: impl Into<String>, commands: Vec<(Command, String)>) -> Self {
+        let matches = (0..commands.len()).collect();
+        Self {
+            title: title.into(),
+            commands,
+            query: String::new(),
+            matches,
+            selected: 0,
+        }
+    }
+
+    /// Returns the id every command palette has, there is at most one mounted.
+    pub fn palette_id() -> Id {
+        Id::new("gland-command-palette")
+    }
+
+    /// Returns the command that `Enter` would run.
+    pub fn selected(&self) -> Option<&Command> {
+        let index = self.matches.get(self.selected)?;
+        Some(&self.commands[*index].0)
+    }
+
+    fn search(&mut self) {
+        let mut scored = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (command, _))| {
+                let score = fuzzy_score(&self.query, &command.description)
+                    .max(fuzzy_score(&self.query, command.action.name()))?;
+                Some((score, i))
+            })
+            .collect::<Vec<_>>();
+        // Stable, so equally good matches keep their order.
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+    }
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for CommandPalette {
+    fn id(&self) -> Id {
+        Self::palette_id()
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        let width = area.width.min(60);
+        let rows = self.matches.len().clamp(1, 10) as u16;
+        let height = (rows + 3).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 4,
+            width,
+            height,
+        };
+
+        Clear.render(popup, buf);
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(self.title.as_str());
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        if inner.height == 0 {
+            return;
+        }
+
+        let prompt = Line::from(vec![
+            Span::styled("> ", Style::new().add_modifier(Modifier::BOLD)),
+            Span::raw(self.query.as_str()),
+        ]);
+        buf.set_line(inner.x, inner.y, &prompt, inner.width);
+
+        let list = Rect {
+            y: inner.y + 1,
+            height: inner.height - 1,
+            ..inner
+        };
+        let offset = self
+            .selected
+            .saturating_sub((list.height as usize).saturating_sub(1));
+        for (row, (i, index)) in self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(list.height as usize)
+            .enumerate()
+        {
+            let (command, keys) = &self.commands[*index];
+            let y = list.y + row as u16;
+            let style = if i == self.selected {
+                Style::new().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::new()
+            };
+
+            buf.set_style(
+                Rect {
+                    y,
+                    height: 1,
+                    ..list
+                },
+                style,
+            );
+            buf.set_stringn(list.x, y, &command.description, list.width as usize, style);
+            let keys_width = keys.chars().count() as u16;
+            if keys_width + 1 < list.width {
+                let x = list.right() - keys_width;
+                buf.set_string(x, y, keys, style.add_modifier(Modifier::DIM));
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        match event {
+            Event::Terminal(TerminalEvent::Key(key)) if key.kind != KeyEventKind::Release => {
+                let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                let code = match key.code {
+                    KeyCode::Char('p') if ctrl => KeyCode::Up,
+                    KeyCode::Char('n') if ctrl => KeyCode::Down,
+                    code => code,
+                };
+                match code {
+                    KeyCode::Esc => cx.add_callback(|comp| comp.remove_all(Self::palette_id())),
+                    KeyCode::Enter => {
+                        let action = self.selected().map(|c| c.action.clone());
+                        cx.add_callback(move |comp| {
+                            comp.remove_all(Self::palette_id());
+                            if let Some(action) = action {
+                                comp.run_command(&action);
+                            }
+                        });
+                    }
+                    KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+                    KeyCode::Down => {
+                        self.selected = (self.selected + 1).min(self.matches.len().max(1) - 1);
+                    }
+                    KeyCode::Backspace => {
+                        self.query.pop();
+                        self.search();
+                    }
+                    KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+                        self.query.push(c);
+                        self.search();
+                    }
+                    _ => {}
+                }
+                _ = event.consume();
+            }
+            Event::Terminal(TerminalEvent::Paste(text)) => {
+                self.query.extend(text.chars().filter(|c| !c.is_control()));
+                self.search();
+                _ = event.consume();
+            }
+            Event::Terminal(TerminalEvent::Key(_)) => _ = event.consume(),
+            _ => {}
+        }
+    }
+}
+
+/// Scores how well `text` matches `query` typed by the user, `None` if it doesn't contain the
+/// characters of the query in order. Consecutive characters and starts of words score higher.
+pub(crate) fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous = None;
+    let mut last_match: Option<usize> = None;
+
+    for (i, c) in text.chars().enumerate() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+        let word_start = previous.is_none_or(|p: char| !p.is_alphanumeric());
+        previous = Some(c);
+        if !c.to_lowercase().eq(wanted.to_lowercase()) {
+            continue;
+        }
+
+        query.next();
+        score += 1;
+        if word_start {
+            score += 8;
+        }
+        match last_match {
+            Some(last) if last + 1 == i => score += 5,
+            Some(last) => score -= (i - last - 1).min(3) as i32,
+            None => score -= i.min(3) as i32,
+        }
+        last_match = Some(i);
+    }
+
+    query.peek().is_none().then_some(score)
+}
@@ -1,45 +1,113 @@
-use crate::{Component, Event, Id, Jobs, LayerId};
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
-    execute,
-    terminal::{
-        disable_raw_mode, enable_raw_mode, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
-    },
+#[cfg(feature = "debug")]
+use crate::debug::{ComponentInfo, DebugOverlay, DebugStats, LayerInfo};
+#[cfg(feature = "history")]
+use crate::history::{self, History};
+#[cfg(feature = "persist")]
+use crate::persist::Persistent;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+use crate::runtime::ThreadRuntime;
+#[cfg(feature = "event-stream")]
+use crate::terminal::SuspendableInput;
+#[cfg(feature = "termion")]
+use crate::terminal::TermionInput;
+use crate::{
+    a11y::{Announcement, Announcer, Priority},
+    anim::{Animated, Animation, Easing, Lerp, Transition},
+    caps::TerminalCaps,
+    clipboard::Clipboard,
+    commands::{Command, CommandPalette},
+    dialog::{self, Dialog},
+    diff::FrameDiff,
+    error::{Error, ErrorAction, ErrorHandler, ErrorPopup},
+    export::{self, ExportFormat},
+    help::{HelpPopup, HelpSection},
+    jobs,
+    keymap::normalize,
+    layers::Layers,
+    locale::{DefaultLocalizer, Localizer},
+    memory::Memory,
+    metrics::Metrics,
+    plugin::Plugin,
+    resources::Resources,
+    runtime::default_runtime,
+    status::{StatusBar, StatusSegment},
+    terminal::{Setup, TerminalGuard, TerminalOptions},
+    watch::{self, Watch, Watches},
+    window::{self, CursorStyle, PointerShape, Progress},
+    Action, Component, Event, FrameInfo, Handle, Id, Jobs, KeyCode, KeyEvent, KeyEventKind,
+    KeyMatch, KeyModifiers, KeyboardEnhancementFlags, Keymap, LayerId, Runtime, TerminalEvent,
 };
+use futures_channel::mpsc;
 use futures_util::{
+    future::{self, BoxFuture, Either},
     stream::{self, select_all},
-    Stream, StreamExt,
+    FutureExt, Stream, StreamExt,
 };
 use ratatui::{
-    backend::Backend,
+    backend::{Backend, TestBackend},
+    buffer::Cell,
+    layout::Layout,
     prelude::{Buffer, Rect},
-    widgets::Widget,
+    style::{Color, Style},
     Terminal,
 };
 use std::{
     any::Any,
-    collections::BTreeMap,
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
     future::Future,
-    io,
-    mem::{take, transmute},
+    io::{self, IsTerminal},
+    mem::take,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
     pin::Pin,
-    time::Duration,
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use tokio::{
-    sync::mpsc::{self, Receiver},
-    time::interval,
-};
-use tokio_stream::wrappers::{IntervalStream, ReceiverStream};
 
 /// Job callback
 pub type Callback<S, E> = Box<dyn FnOnce(&mut Compositor<S, E>) + Send + 'static>;
 
+/// Writes escape sequences to the terminal behind a backend, if it isn't the local one.
+pub(crate) type RawWriter<B> = fn(&mut B, &[u8]) -> io::Result<()>;
+
+/// Function seeing events before components, see [`Compositor::with_event_hook`].
+type EventHook<S, E> = Box<dyn FnMut(&mut Event<E>, &mut Context<S, E>)>;
+
+/// Function called with every frame drawn, see [`Compositor::with_after_render`].
+type AfterRender<S> = Box<dyn FnMut(&Buffer, &S)>;
+
 /// Context of the current update.
 pub struct Context<'comp, S = (), E = ()> {
     callbacks: Vec<Callback<S, E>>,
+    pointer: Option<PointerShape>,
+    cursor_style: Option<CursorStyle>,
+    focused: Option<Id>,
+    focus: Option<FocusRequest>,
+    announcements: Vec<Announcement>,
+    messages: Vec<(Id, Box<dyn Any>)>,
+    visibility: Vec<(LayerId, bool)>,
+    redraw: bool,
+    animate: bool,
+    animations: Vec<(Id, Box<dyn Animation>)>,
+    commands: Vec<(Id, Command)>,
+    status: Vec<StatusUpdate>,
+    screens: Vec<ScreenRequest<S, E>>,
+    watches: Vec<(Id, Watch<S>)>,
+    invalidated: Vec<Id>,
+    requests: Vec<Request>,
+    handled: bool,
+    reduced_motion: bool,
+    localizer: &'comp Arc<dyn Localizer>,
     jobs: &'comp Jobs<S, E>,
     size: Rect,
-    state: S,
+    state: &'comp mut S,
+    caps: &'comp TerminalCaps,
+    escapes: &'comp mut Vec<u8>,
+    memory: &'comp mut Memory,
+    resources: &'comp mut Resources,
+    #[cfg(feature = "history")]
+    history: Option<&'comp mut History<S>>,
 }
 
 impl<'comp, S: 'static, E: 'static> Context<'comp, S, E> {
@@ -47,7 +115,38 @@ impl<'comp, S: 'static, E: 'static> Context<'comp, S, E> {
         self.jobs
     }
 
+    /// Runs `f` with the terminal restored after this update, e.g. to open `$EDITOR` or a pager,
+    /// then sets the terminal up again and redraws. Events aren't handled until `f` returns.
+    /// The returned future resolves to the output of `f`, spawn it as a job to handle it,
+    /// see [`Jobs::suspend`].
+    ///
+    /// With [`Compositor::dispatch`] `f` is run by the caller, see [`Compositor::take_suspended`].
+    ///
+    /// ```no_run
+    /// # use gland::{Context, Compositor};
+    /// # use std::process::Command;
+    /// fn edit(cx: &mut Context) {
+    ///     let editor = cx.suspend(|| Command::new("vi").arg("notes.txt").status());
+    ///     cx.jobs().spawn(async move {
+    ///         let failed = !matches!(editor.await, Some(Ok(status)) if status.success());
+    ///         move |comp: &mut Compositor| {
+    ///             if failed {
+    ///                 comp.exit();
+    ///             }
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn suspend<R: Send + 'static>(
+        &mut self,
+        f: impl FnOnce() -> R + Send + 'static,
+    ) -> BoxFuture<'static, Option<R>> {
+        self.jobs.suspend(f)
+    }
+
     /// Adds a callback that will be executed after all components have been drawn in this frame.
+    /// Callbacks added through a [`Lens`](crate::Lens) or [`MapEvent`](crate::MapEvent) are dropped,
+    /// a warning is logged with the `tracing` feature.
     pub fn add_callback(&mut self, func: impl FnOnce(&mut Compositor<S, E>) + Send + 'static) {
         self.callbacks.push(Box::new(func))
     }
@@ -57,32 +156,732 @@ impl<'comp, S: 'static, E: 'static> Context<'comp, S, E> {
         self.size
     }
 
+    /// Returns capabilities of the terminal the compositor runs in.
+    pub fn terminal_caps(&self) -> &'comp TerminalCaps {
+        self.caps
+    }
+
+    /// Returns values components keep outside of the state, they survive components being remounted.
+    pub fn memory(&mut self) -> &mut Memory {
+        self.memory
+    }
+
+    /// Returns the resource of type `T`, see [`Compositor::insert_resource`].
+    pub fn resource<T: 'static>(&self) -> Option<&T> {
+        self.resources.get()
+    }
+
+    /// Returns the resource of type `T` mutably, see [`Compositor::insert_resource`].
+    pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.resources.get_mut()
+    }
+
+    /// Returns the clipboard of the terminal.
+    pub fn clipboard(&mut self) -> Clipboard<'_> {
+        Clipboard::new(self.caps, self.escapes)
+    }
+
+    /// Sets the title of the terminal window, the previous one is restored on exit.
+    pub fn set_title(&mut self, title: &str) {
+        self.escapes
+            .extend_from_slice(window::title_sequence(title).as_bytes());
+    }
+
+    /// Shows progress in the terminal tab or taskbar, returns `false` if the terminal doesn't support it.
+    /// Progress is removed on exit.
+    pub fn set_progress(&mut self, progress: Progress) -> bool {
+        if !self.caps.progress {
+            return false;
+        }
+
+        let seq = self.caps.wrap_passthrough(&progress.sequence());
+        self.escapes.extend_from_slice(seq.as_bytes());
+        true
+    }
+
+    /// Shows a desktop notification, returns `false` if the terminal doesn't support notifications.
+    pub fn notify_desktop(&mut self, title: &str, body: &str) -> bool {
+        window::notification_sequence(self.caps, title, body)
+            .map(|seq| self.escapes.extend_from_slice(seq.as_bytes()))
+            .is_some()
+    }
+
+    /// Requests mouse pointer shape, usually while handling a mouse event over the component.
+    /// Pointer returns to the default shape on the next mouse event unless requested again.
+    pub fn set_pointer_shape(&mut self, shape: PointerShape) {
+        self.pointer = Some(shape);
+    }
+
+    /// Announces `text` to assistive technology with the announcer set by [`Compositor::with_announcer`].
+    /// Without one, assertive announcements are shown as desktop notifications if the terminal supports them.
+    pub fn announce(&mut self, text: impl Into<String>, priority: Priority) {
+        self.announcements.push(Announcement {
+            text: text.into(),
+            priority,
+        });
+    }
+
+    /// Returns `true` if animations should be skipped or shortened, see [`Compositor::with_reduced_motion`].
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    /// Returns the localizer built-in components translate their text with.
+    pub fn localizer(&self) -> &'comp Arc<dyn Localizer> {
+        self.localizer
+    }
+
+    /// Returns id of the focused component.
+    pub fn focused(&self) -> Option<Id> {
+        self.focused
+    }
+
+    /// Runs `f` as if `inner` was focused while `outer` is, for containers forwarding events to children.
+    pub(crate) fn with_inner_focus<T>(
+        &mut self,
+        outer: Id,
+        inner: Option<Id>,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let focused = self.focused;
+        if focused == Some(outer) {
+            self.focused = inner;
+        }
+        let out = f(self);
+        self.focused = focused;
+        out
+    }
+
+    /// Turns a request to focus a child of the container with `outer` id into a request to focus
+    /// the container, children aren't known to the compositor. Returns id of the child.
+    pub(crate) fn redirect_focus(
+        &mut self,
+        outer: Id,
+        is_child: impl Fn(Id) -> bool,
+    ) -> Option<Id> {
+        match self.focus {
+            Some(FocusRequest::Set(id)) if is_child(id) => {
+                self.focus = Some(FocusRequest::Set(outer));
+                Some(id)
+            }
+            _ => None,
+        }
+    }
+
+    /// Runs `f` with a context over the part `T` of the state and user events `E2` emitted through `jobs`,
+    /// see [`Lens`](crate::Lens) and [`MapEvent`](crate::MapEvent). Screens pushed through it are wrapped
+    /// and selectors are projected, callbacks are dropped since there is no compositor for them.
+    pub(crate) fn project<T: 'static, E2: 'static, R>(
+        &mut self,
+        jobs: &Jobs<T, E2>,
+        projection: Projection<'_, S, T, E, E2>,
+        f: impl FnOnce(&mut Context<'_, T, E2>) -> R,
+    ) -> R {
+        let mut cx = Context {
+            callbacks: Vec::new(),
+            pointer: None,
+            cursor_style: None,
+            focused: self.focused,
+            focus: None,
+            announcements: Vec::new(),
+            messages: Vec::new(),
+            visibility: Vec::new(),
+            redraw: false,
+            animate: false,
+            animations: Vec::new(),
+            commands: Vec::new(),
+            status: Vec::new(),
+            screens: Vec::new(),
+            watches: Vec::new(),
+            invalidated: Vec::new(),
+            requests: Vec::new(),
+            handled: false,
+            reduced_motion: self.reduced_motion,
+            localizer: self.localizer,
+            state: (projection.get_mut)(self.state),
+            caps: self.caps,
+            escapes: self.escapes,
+            memory: self.memory,
+            resources: self.resources,
+            #[cfg(feature = "history")]
+            history: None,
+            size: self.size,
+            jobs,
+        };
+        let out = f(&mut cx);
+
+        let output = cx.into_output();
+        self.pointer = output.pointer.or(self.pointer);
+        self.cursor_style = output.cursor_style.or(self.cursor_style);
+        self.focus = output.focus.or(self.focus);
+        self.announcements.extend(output.announcements);
+        self.messages.extend(output.messages);
+        self.visibility.extend(output.visibility);
+        self.redraw |= output.redraw;
+        self.animate |= output.animate;
+        self.animations.extend(output.animations);
+        self.commands.extend(output.commands);
+        self.status.extend(output.status);
+        self.invalidated.extend(output.invalidated);
+        self.requests.extend(output.requests);
+        self.handled |= output.handled;
+
+        let get = projection.get;
+        self.watches.extend(
+            output.watches.into_iter().map(|(id, mut watch)| {
+                (id, Box::new(move |state: &S| watch(get(state))) as Watch<S>)
+            }),
+        );
+        self.screens
+            .extend(output.screens.into_iter().map(|request| match request {
+                ScreenRequest::Push(component) => ScreenRequest::Push((projection.wrap)(component)),
+                ScreenRequest::Pop => ScreenRequest::Pop,
+                ScreenRequest::Replace(component) => {
+                    ScreenRequest::Replace((projection.wrap)(component))
+                }
+            }));
+        if !output.callbacks.is_empty() {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                count = output.callbacks.len(),
+                "callbacks added through a lens or `MapEvent` are dropped"
+            );
+        }
+        out
+    }
+
+    /// Queues `request` for the compositor, it is forwarded from projected contexts.
+    pub(crate) fn request(&mut self, request: Request) {
+        self.requests.push(request);
+    }
+
+    /// Focuses component with `id` after this update, it receives key events first.
+    pub fn focus(&mut self, id: Id) {
+        self.focus = Some(FocusRequest::Set(id));
+    }
+
+    /// Removes focus after this update.
+    pub fn blur(&mut self) {
+        self.focus = Some(FocusRequest::Clear);
+    }
+
+    /// Focuses the next focusable component after this update, see [`Compositor::focus_next`].
+    pub fn focus_next(&mut self) {
+        self.focus = Some(FocusRequest::Next);
+    }
+
+    /// Focuses the previous focusable component after this update, see [`Compositor::focus_prev`].
+    pub fn focus_prev(&mut self) {
+        self.focus = Some(FocusRequest::Prev);
+    }
+
+    /// Sends `message` to the component with `id`, it is passed to [`Component::handle_message`] after this
+    /// update and before the next draw. Messages to components that aren't mounted are dropped.
+    pub fn send_to(&mut self, id: Id, message: impl Any) {
+        self.messages.push((id, Box::new(message)));
+    }
+
+    /// Emits user event `event`, it is passed to components after the current event, see [`Jobs::emit`].
+    pub fn emit(&mut self, event: E) {
+        self.jobs.emit(event);
+    }
+
+    /// Asks the topmost component with `id` for data, `query` is called with it after this update.
+    /// Resolves to `None` if there is no such component or it isn't a `C`, and always through a [`Lens`](crate::Lens)
+    /// or [`MapEvent`](crate::MapEvent), since components over a projection aren't known to the compositor.
+    ///
+    /// Await the answer in a job, e.g. `cx.jobs().spawn(async move { let n = answer.await; ... })`.
+    pub fn query<C, R>(
+        &mut self,
+        id: Id,
+        query: impl FnOnce(&C) -> R + Send + 'static,
+    ) -> impl Future<Output = Option<R>> + Send + 'static
+    where
+        C: Component<S, E>,
+        R: Send + 'static,
+    {
+        let (callback, answer) = jobs::query_callback(id, query);
+        self.callbacks.push(callback);
+        answer
+    }
+
+    /// Asks `message` in a dialog with yes and no buttons on [`LayerId::POPUP`], shown after this update.
+    /// Resolves to `true` if the user confirms, `false` if they decline or close the dialog with `Esc`.
+    ///
+    /// Await the answer in a job, e.g.
+    /// `cx.jobs().spawn(async move { if answer.await { ... } })`.
+    pub fn confirm(
+        &mut self,
+        message: impl Into<String>,
+    ) -> impl Future<Output = bool> + Send + 'static {
+        let kind = dialog::Kind::Confirm {
+            yes: self.localizer.text("gland-dialog-yes", "Yes").into_owned(),
+            no: self.localizer.text("gland-dialog-no", "No").into_owned(),
+            confirmed: true,
+        };
+        let answer = Dialog::open(self, message.into(), kind);
+        async move { matches!(answer.await, Ok(dialog::Answer::Confirm(true))) }
+    }
+
+    /// Asks `message` in a dialog with a text input, see [`Self::confirm`].
+    /// Resolves to the entered text, or `None` if the dialog is closed with `Esc`.
+    pub fn prompt(
+        &mut self,
+        message: impl Into<String>,
+    ) -> impl Future<Output = Option<String>> + Send + 'static {
+        let kind = dialog::Kind::Prompt {
+            input: String::new(),
+            cursor: 0,
+        };
+        let answer = Dialog::open(self, message.into(), kind);
+        async move {
+            match answer.await {
+                Ok(dialog::Answer::Prompt(text)) => text,
+                _ => None,
+            }
+        }
+    }
+
+    /// Asks `message` in a dialog listing `options`, see [`Self::confirm`].
+    /// Resolves to the index of the chosen option, or `None` if the dialog is closed with `Esc`.
+    pub fn select(
+        &mut self,
+        message: impl Into<String>,
+        options: impl IntoIterator<Item = impl Into<String>>,
+    ) -> impl Future<Output = Option<usize>> + Send + 'static {
+        let kind = dialog::Kind::Select {
+            options: options.into_iter().map(Into::into).collect(),
+            selected: 0,
+        };
+        let answer = Dialog::open(self, message.into(), kind);
+        async move {
+            match answer.await {
+                Ok(dialog::Answer::Select(index)) => index,
+                _ => None,
+            }
+        }
+    }
+
+    /// Shows or hides the layer after this update, see [`Compositor::set_layer_visible`].
+    pub fn set_layer_visible(&mut self, layer_id: LayerId, visible: bool) {
+        self.visibility.push((layer_id, visible));
+    }
+
+    /// Sets shape and blinking of the text cursor, e.g. a bar in insert mode and a block in normal mode.
+    /// The style stays until changed, the user's style is restored on exit.
+    /// Components placing the cursor can override it, see [`Component::cursor_style`].
+    pub fn set_cursor_style(&mut self, style: CursorStyle) {
+        self.cursor_style = Some(style);
+    }
+
+    /// Exports the frame after this update to `path`, the format is picked from the extension,
+    /// see [`ExportFormat::from_path`]. Failure to write the file is ignored.
+    pub fn screenshot(&mut self, path: impl Into<PathBuf>) {
+        self.requests.push(Request::Screenshot(path.into()));
+    }
+
+    /// Applies `command` to the state, it can be undone if the compositor records a history,
+    /// see [`Compositor::with_history`].
+    #[cfg(feature = "history")]
+    #[doc(cfg(feature = "history"))]
+    pub fn apply(&mut self, mut command: impl history::Command<S>) {
+        self.redraw = true;
+        match &mut self.history {
+            Some(history) => history.apply(command, self.state),
+            None => command.apply(self.state),
+        }
+    }
+
+    /// Reverts the last step of the history, returns `false` if there is nothing to undo.
+    #[cfg(feature = "history")]
+    #[doc(cfg(feature = "history"))]
+    pub fn undo(&mut self) -> bool {
+        let undone = (self.history.as_mut()).is_some_and(|history| history.undo(self.state));
+        self.redraw |= undone;
+        undone
+    }
+
+    /// Applies the last undone step of the history again, returns `false` if there is nothing to redo.
+    #[cfg(feature = "history")]
+    #[doc(cfg(feature = "history"))]
+    pub fn redo(&mut self) -> bool {
+        let redone = (self.history.as_mut()).is_some_and(|history| history.redo(self.state));
+        self.redraw |= redone;
+        redone
+    }
+
     /// Returns an immutable reference to the compositor state.
     pub fn state(&self) -> &S {
-        &self.state
+        self.state
     }
 
-    /// Returns a mutable reference to the compositor state.
+    /// Returns a mutable reference to the compositor state, the next frame is drawn.
     pub fn state_mut(&mut self) -> &mut S {
-        &mut self.state
+        self.redraw = true;
+        self.state
+    }
+
+    /// Draws the next frame. Frames are only drawn when an event is consumed, the state or the components
+    /// change, so components that change on events without consuming them have to request it.
+    pub fn request_redraw(&mut self) {
+        self.redraw = true;
+    }
+
+    /// Only draws component `id` when the output of `selector` changes, e.g. a panel of a large dashboard
+    /// showing a single value of the state. With multiple selectors the component is drawn when any of them
+    /// changes. Selectors run before every frame and are removed once the component is unmounted,
+    /// so they are usually added in [`Component::on_mount`].
+    ///
+    /// Selectors added by components in a [`Lens`](crate::Lens) or [`MapEvent`](crate::MapEvent) are called
+    /// with the state the wrapper passes to them, e.g. the part of the state a lens projects.
+    pub fn watch<T: PartialEq + 'static>(&mut self, id: Id, selector: impl Fn(&S) -> T + 'static) {
+        self.watches.push((id, watch::selector(selector)));
+    }
+
+    /// Draws component `id` in the next frame even if none of its selectors changed, see [`Self::watch`].
+    pub fn invalidate(&mut self, id: Id) {
+        self.invalidated.push(id);
+        self.redraw = true;
+    }
+
+    /// Emits [`Event::AnimationFrame`] after the animation tick interval, see [`Compositor::with_animation_ticks`].
+    /// Request it again on every frame to keep animating, no frames are emitted while nothing animates.
+    pub fn request_animation_frame(&mut self) {
+        self.animate = true;
+    }
+
+    /// Animates a value from `from` to `to`, sending [`AnimationStep`](crate::AnimationStep) with the current value
+    /// to the component with `id` on every animation frame. Starting an animation of the same type for the same
+    /// component replaces the running one. With reduced motion only the last step is sent.
+    pub fn animate<T: Lerp + Clone + 'static>(
+        &mut self,
+        id: Id,
+        from: T,
+        to: T,
+        duration: Duration,
+        easing: Easing,
+    ) {
+        let animation = Animated::transition(from, to, duration, easing);
+        self.animations.push((id, Box::new(animation)));
+    }
+
+    /// Marks the event as handled without consuming it, so it still reaches components below
+    /// but built-in shortcuts like [`Compositor::with_tab_focus`] ignore it. The next frame is drawn.
+    pub fn mark_handled(&mut self) {
+        self.handled = true;
+    }
+
+    /// Returns `true` if a component marked the event as handled, see [`Self::mark_handled`].
+    pub fn is_handled(&self) -> bool {
+        self.handled
+    }
+
+    /// Registers `command` handled by the component with `id` after this update, see [`Compositor::register_command`].
+    /// The command is removed when the component is unmounted.
+    pub fn register_command(&mut self, id: Id, command: Command) {
+        self.commands.push((id, command));
+    }
+
+    /// Shows `segment` in the [`StatusBar`] under `name` after this update, replacing the segment
+    /// the component with `id` had under it. Segments are removed when the component is unmounted.
+    /// Ignored if the status bar isn't enabled, see [`Compositor::with_status_bar`].
+    pub fn set_status(&mut self, id: Id, name: &'static str, segment: StatusSegment) {
+        self.status.push((id, name, Some(segment)));
+    }
+
+    /// Pushes `component` as the top screen after this update, see [`Compositor::push_screen`].
+    pub fn push_screen(&mut self, component: impl Component<S, E>) {
+        self.screens.push(ScreenRequest::Push(Box::new(component)));
+    }
+
+    /// Removes the top screen after this update, revealing the one below, see [`Compositor::pop_screen`].
+    pub fn pop_screen(&mut self) {
+        self.screens.push(ScreenRequest::Pop);
+    }
+
+    /// Replaces the top screen with `component` after this update, see [`Compositor::replace_screen`].
+    pub fn replace_screen(&mut self, component: impl Component<S, E>) {
+        self.screens
+            .push(ScreenRequest::Replace(Box::new(component)));
+    }
+
+    /// Removes the segment the component with `id` has in the [`StatusBar`] under `name` after this update.
+    pub fn clear_status(&mut self, id: Id, name: &'static str) {
+        self.status.push((id, name, None));
+    }
+}
+
+/// Requests components made through a [`Context`].
+struct ContextOutput<S, E> {
+    callbacks: Vec<Callback<S, E>>,
+    pointer: Option<PointerShape>,
+    cursor_style: Option<CursorStyle>,
+    focus: Option<FocusRequest>,
+    announcements: Vec<Announcement>,
+    messages: Vec<(Id, Box<dyn Any>)>,
+    visibility: Vec<(LayerId, bool)>,
+    redraw: bool,
+    animate: bool,
+    animations: Vec<(Id, Box<dyn Animation>)>,
+    commands: Vec<(Id, Command)>,
+    status: Vec<StatusUpdate>,
+    screens: Vec<ScreenRequest<S, E>>,
+    watches: Vec<(Id, Watch<S>)>,
+    invalidated: Vec<Id>,
+    requests: Vec<Request>,
+    handled: bool,
+}
+
+/// Change of the screen stack requested with [`Context::push_screen`] and related functions.
+enum ScreenRequest<S, E> {
+    Push(Box<dyn Component<S, E>>),
+    Pop,
+    Replace(Box<dyn Component<S, E>>),
+}
+
+/// Wraps a component over `T` and user events `E2` to mount it in a compositor over `S` and `E`.
+type Wrap<'a, S, T, E, E2> = &'a dyn Fn(Box<dyn Component<T, E2>>) -> Box<dyn Component<S, E>>;
+
+/// How a context over `S` and user events `E` is projected to one over `T` and `E2`, see [`Context::project`].
+pub(crate) struct Projection<'a, S, T, E, E2> {
+    pub(crate) get: fn(&S) -> &T,
+    pub(crate) get_mut: fn(&mut S) -> &mut T,
+    /// Wraps screens pushed through the projected context.
+    pub(crate) wrap: Wrap<'a, S, T, E, E2>,
+}
+
+/// Request that doesn't depend on the state or user events, so components over a projection of them
+/// and their jobs can make it, see [`Context::project`].
+pub(crate) enum Request {
+    /// Inserts the dialog on [`LayerId::POPUP`] and focuses it.
+    Dialog(Dialog),
+    Screenshot(PathBuf),
+    Suspend(Box<dyn FnOnce() + Send>),
+    /// Draws the next frame, e.g. once frame diff highlights expire.
+    Redraw,
+}
+
+/// Segment of the status bar set or cleared by the component with the id, see [`Context::set_status`].
+type StatusUpdate = (Id, &'static str, Option<StatusSegment>);
+
+impl<S, E> Context<'_, S, E> {
+    fn into_output(self) -> ContextOutput<S, E> {
+        ContextOutput {
+            callbacks: self.callbacks,
+            pointer: self.pointer,
+            cursor_style: self.cursor_style,
+            focus: self.focus,
+            announcements: self.announcements,
+            messages: self.messages,
+            visibility: self.visibility,
+            redraw: self.redraw,
+            animate: self.animate,
+            animations: self.animations,
+            commands: self.commands,
+            status: self.status,
+            screens: self.screens,
+            watches: self.watches,
+            invalidated: self.invalidated,
+            requests: self.requests,
+            handled: self.handled,
+        }
     }
 }
 
+/// Creates context from fields of the compositor, so its components can be borrowed at the same time.
+macro_rules! context {
+    ($comp:tt, $jobs:expr) => {
+        Context {
+            callbacks: Vec::new(),
+            pointer: None,
+            cursor_style: None,
+            focused: $comp.focused,
+            focus: None,
+            announcements: Vec::new(),
+            messages: Vec::new(),
+            visibility: Vec::new(),
+            redraw: false,
+            animate: false,
+            animations: Vec::new(),
+            commands: Vec::new(),
+            status: Vec::new(),
+            screens: Vec::new(),
+            watches: Vec::new(),
+            invalidated: Vec::new(),
+            requests: Vec::new(),
+            handled: false,
+            reduced_motion: $comp.reduced_motion,
+            localizer: &$comp.localizer,
+            state: &mut $comp.state,
+            caps: &$comp.caps,
+            escapes: &mut $comp.escapes,
+            memory: &mut $comp.memory,
+            resources: &mut $comp.resources,
+            #[cfg(feature = "history")]
+            history: $comp.history.as_mut(),
+            size: $comp.size,
+            jobs: $jobs,
+        }
+    };
+}
+
+/// Lookup made by a job callback, a callback added with [`Context::add_callback`] or an animation step
+/// for a component that was no longer mounted. Animations of unmounted components are stopped.
+///
+/// Only recorded in debug builds, see [`Compositor::dangling_accesses`]. Logged as warnings with the `tracing`
+/// feature, otherwise printed once the terminal is restored after running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DanglingAccess {
+    /// Layer the lookup was made at, `None` if it searched all layers.
+    pub layer_id: Option<LayerId>,
+    /// Id of the missing component.
+    pub component_id: Id,
+}
+
+#[derive(Clone, Copy)]
+enum FocusRequest {
+    Set(Id),
+    Clear,
+    Next,
+    Prev,
+}
+
 pub(crate) enum Resume<S, E> {
     Event(Event<E>),
     JobCallback(Callback<S, E>),
+    Request(Request),
+}
+
+/// Component removed with an exit transition.
+struct Exiting<S, E> {
+    layer_id: LayerId,
+    component: Box<dyn Component<S, E>>,
+    /// Area the component was drawn in when removed.
+    area: Rect,
+    transition: Transition,
+    shown: Animated<f32>,
 }
 
 /// Main interface that draws components and dispatches events.
 #[allow(clippy::type_complexity)]
 pub struct Compositor<S = (), E = ()> {
-    layers: BTreeMap<LayerId, Vec<Box<dyn Component<S, E>>>>,
-    state: S,
+    layers: Layers<S, E>,
+    layouts: BTreeMap<LayerId, Layout>,
+    /// Layers that stop events from reaching layers below while they have components.
+    modal: BTreeSet<LayerId>,
+    /// Layers that are neither drawn nor receive terminal events.
+    hidden: BTreeSet<LayerId>,
+    /// Styles patched over layers below a layer while it has components.
+    backdrops: BTreeMap<LayerId, Style>,
+    pub(crate) state: S,
 
     streams: Vec<Pin<Box<dyn Stream<Item = Resume<S, E>>>>>,
+    /// Streams of terminal events, dropped in headless mode.
+    terminal_input: Vec<Pin<Box<dyn Stream<Item = Resume<S, E>>>>>,
+    headless_fallback: bool,
     timeout: Duration,
+    animation_interval: Duration,
+    /// Whether a component requested an animation frame that hasn't been scheduled yet.
+    animation_requested: bool,
+    /// Whether the next animation frame is scheduled.
+    animation_scheduled: bool,
+    animation_frames: u64,
+    /// Animations started with [`Context::animate`].
+    animations: Vec<(Id, Box<dyn Animation>)>,
+    /// Enter transitions of mounted components, see [`Component::enter_transition`].
+    entering: HashMap<(LayerId, Id), (Transition, Animated<f32>)>,
+    /// Removed components drawn until their exit transitions end.
+    exiting: Vec<Exiting<S, E>>,
+    /// Maximum number of ready events dispatched before drawing a frame.
+    batch_size: usize,
+    /// Minimum time between frames, see [`Self::with_max_fps`].
+    frame_interval: Option<Duration>,
+    frame_diff: Option<FrameDiff>,
+    pub(crate) setup: Setup,
+    terminal_options: TerminalOptions,
+    pub(crate) runtime: Option<Arc<dyn Runtime>>,
+    pub(crate) caps: TerminalCaps,
+    pub(crate) detect_caps: bool,
+    /// Escape sequences written to the terminal after the next frame.
+    escapes: Vec<u8>,
+    memory: Memory,
+    resources: Resources,
+    /// Commands applied with [`Context::apply`], see [`Self::with_history`].
+    #[cfg(feature = "history")]
+    history: Option<History<S>>,
+    /// Component types saved in snapshots, see [`Self::with_persistent`].
+    #[cfg(feature = "persist")]
+    pub(crate) persistent: Vec<Persistent<S, E>>,
+    /// Snapshot restored on start and saved on exit, see [`Self::with_session_file`].
+    #[cfg(feature = "persist")]
+    pub(crate) session_file: Option<PathBuf>,
+    /// Whether the next frame has to be drawn, see [`Context::request_redraw`].
+    redraw: bool,
+    pointer: PointerShape,
+    /// Style set with [`Context::set_cursor_style`].
+    cursor_style: CursorStyle,
+    /// Style last written to the terminal.
+    shown_cursor_style: CursorStyle,
+    focused: Option<Id>,
+    tab_focus: bool,
+    keymap: Keymap,
+    help_key: Option<KeyEvent>,
+    /// Pops the top screen, see [`Self::with_back_key`].
+    back_key: Option<KeyEvent>,
+    /// Id of the top screen on [`LayerId::MIDDLE`], see [`Self::push_screen`].
+    screen: Option<Id>,
+    /// Screens covered by the top screen, taken out of their layer without being unmounted.
+    covered_screens: Vec<Box<dyn Component<S, E>>>,
+    /// Registered commands with the components handling them.
+    commands: Vec<(Option<Id>, Command)>,
+    palette_key: Option<KeyEvent>,
+    /// Whether the last event was handled or consumed.
+    handled: bool,
+    announcer: Option<Announcer>,
+    error_handler: Option<ErrorHandler>,
+    event_hooks: Vec<EventHook<S, E>>,
+    after_render: Vec<AfterRender<S>>,
+    plugins: Vec<Box<dyn Plugin<S, E>>>,
+    /// Timings of components, see [`Self::with_metrics`].
+    metrics: Option<Metrics>,
+    status_bar: Option<StatusBar>,
+    /// Statistics shown in the debug overlay, collected once it is enabled.
+    #[cfg(feature = "debug")]
+    debug: Option<DebugStats>,
+    /// Error that made the error handler stop the compositor.
+    failure: Option<Error>,
+    isolation: bool,
+    /// Components that have panicked with isolation enabled, removed after the update or frame.
+    faulted: RefCell<Vec<(LayerId, Id, String)>>,
+    reduced_motion: bool,
+    localizer: Arc<dyn Localizer>,
+    /// Size of the first terminal during the last update.
+    size: Rect,
+
+    /// Handle to spawn jobs, set once the compositor starts running.
+    pub(crate) jobs: Option<Jobs<S, E>>,
+    /// Closures passed to [`Context::suspend`], run with the terminal restored.
+    suspended: Vec<Box<dyn FnOnce()>>,
+    /// Terminal input read with [`Self::with_event_stream`], paused while suspended.
+    #[cfg(feature = "event-stream")]
+    input: Option<SuspendableInput>,
+    /// Terminal input read with [`Self::with_termion`], paused while suspended.
+    #[cfg(feature = "termion")]
+    termion_input: Option<TermionInput>,
+    /// Job callbacks when driven by [`Compositor::dispatch`] and [`Compositor::render`].
+    embedded_callbacks: Option<mpsc::UnboundedReceiver<Resume<S, E>>>,
+    /// Components inserted before the compositor started running.
+    pending_mounts: Vec<(LayerId, Id)>,
+    /// Set while job, context callbacks and animation steps run, see [`DanglingAccess`].
+    in_callback: bool,
+    dangling: RefCell<Vec<DanglingAccess>>,
+    /// Last output of components that have skipped an update, see [`Component::should_update`].
+    views: RefCell<HashMap<(LayerId, Id), Buffer>>,
+    /// Selectors of components, see [`Context::watch`].
+    watches: RefCell<Watches<S>>,
 
     exit: bool,
+    exit_requested: bool,
 }
 
 impl<E: 'static> Compositor<(), E> {
@@ -95,43 +894,203 @@ impl<E: 'static> Compositor<(), E> {
 /// Non-builder functions
 impl<S: 'static, E: 'static> Compositor<S, E> {
     /// Inserts a new component at a layer, if already exists returns it.
+    /// [`Component::on_mount`] is called once the compositor is running.
+    /// Returns a handle to access the component without downcasting, see [`Self::get_handle`].
     pub fn insert_at<C: Component<S, E>>(
         &mut self,
         layer_id: LayerId,
         component: C,
-    ) -> Result<(), C> {
-        let layer = self.layers.entry(layer_id).or_default();
+    ) -> Result<Handle<C>, C> {
+        let index = self.layers.len(layer_id);
+        self.insert_at_index(layer_id, index, component)
+    }
+
+    /// Same as [`Self::insert_at`] but puts the component at `index` in the layer, components later
+    /// in a layer are drawn on top of earlier ones. Index past the end inserts at the end.
+    pub fn insert_at_index<C: Component<S, E>>(
+        &mut self,
+        layer_id: LayerId,
+        index: usize,
+        component: C,
+    ) -> Result<Handle<C>, C> {
+        let component_id = component.id();
 
-        if layer.iter().any(|c| c.id() == component.id()) {
+        if self.layers.contains(layer_id, component_id) {
             Err(component)
         } else {
-            layer.push(Box::new(component));
-            Ok(())
+            self.layers.insert(layer_id, index, Box::new(component));
+            self.mount(layer_id, component_id);
+            Ok(Handle::new(layer_id, component_id))
+        }
+    }
+
+    /// Inserts a component right below the component with `sibling` id in the layer.
+    /// Returns the component if it already exists or there is no such sibling.
+    pub fn insert_before<C: Component<S, E>>(
+        &mut self,
+        layer_id: LayerId,
+        sibling: Id,
+        component: C,
+    ) -> Result<Handle<C>, C> {
+        match self.position(layer_id, sibling) {
+            Some(index) => self.insert_at_index(layer_id, index, component),
+            None => Err(component),
+        }
+    }
+
+    /// Inserts a component right above the component with `sibling` id in the layer.
+    /// Returns the component if it already exists or there is no such sibling.
+    pub fn insert_after<C: Component<S, E>>(
+        &mut self,
+        layer_id: LayerId,
+        sibling: Id,
+        component: C,
+    ) -> Result<Handle<C>, C> {
+        match self.position(layer_id, sibling) {
+            Some(index) => self.insert_at_index(layer_id, index + 1, component),
+            None => Err(component),
+        }
+    }
+
+    /// Moves the component to the end of its layer, so it is drawn on top of the others.
+    /// Returns `false` if there is no such component.
+    pub fn move_to_front(&mut self, layer_id: LayerId, component_id: Id) -> bool {
+        self.move_within(layer_id, component_id, usize::MAX)
+    }
+
+    /// Moves the component to the start of its layer, so it is drawn below the others.
+    /// Returns `false` if there is no such component.
+    pub fn move_to_back(&mut self, layer_id: LayerId, component_id: Id) -> bool {
+        self.move_within(layer_id, component_id, 0)
+    }
+
+    fn position(&self, layer_id: LayerId, component_id: Id) -> Option<usize> {
+        self.layers.position(layer_id, component_id)
+    }
+
+    /// Moves the component to `index` in its layer without unmounting it.
+    fn move_within(&mut self, layer_id: LayerId, component_id: Id, index: usize) -> bool {
+        let moved = self.layers.move_to(layer_id, component_id, index);
+        if moved {
+            self.redraw = true;
+        } else {
+            self.note_dangling(Some(layer_id), component_id);
         }
+        moved
     }
 
     /// Replaces component or adds new one at some layer.
-    pub fn replace_at<C: Component<S, E>>(&mut self, layer_id: LayerId, component: C) {
-        let layer = self.layers.entry(layer_id).or_default();
-        layer.retain(|c| c.id() != component.id());
-        layer.push(Box::new(component));
+    pub fn replace_at<C: Component<S, E>>(&mut self, layer_id: LayerId, component: C) -> Handle<C> {
+        let component_id = component.id();
+        self.detach(Some(layer_id), component_id);
+
+        self.layers
+            .insert(layer_id, usize::MAX, Box::new(component));
+        self.mount(layer_id, component_id);
+        Handle::new(layer_id, component_id)
     }
 
     /// Removes all components with `component_id` on all layers.
     pub fn remove_all(&mut self, component_id: Id) {
-        self.layers
-            .values_mut()
-            .for_each(|l| l.retain(|c| c.id() != component_id));
+        if !self.detach(None, component_id) {
+            self.note_dangling(None, component_id);
+        }
+    }
+
+    /// Removes components with `component_id` at `layer_id` or on all layers, calling [`Component::on_unmount`].
+    fn detach(&mut self, layer_id: Option<LayerId>, component_id: Id) -> bool {
+        let layer_ids = match layer_id {
+            Some(layer_id) => vec![layer_id],
+            None => self.layers.iter().map(|(id, _)| id).collect(),
+        };
+        let mut removed = Vec::new();
+        for layer_id in layer_ids {
+            let exit = self.exit_transition(layer_id, component_id);
+            if let Some(component) = self.layers.remove(layer_id, component_id) {
+                removed.push((layer_id, component, exit));
+            }
+        }
+
+        let any = !removed.is_empty();
+        for (layer_id, mut component, exit) in removed {
+            self.unmount(layer_id, &mut *component);
+            if let Some((transition, area)) = exit {
+                let shown = Animated::transition(1., 0., transition.duration, transition.easing);
+                self.exiting.push(Exiting {
+                    layer_id,
+                    component,
+                    area,
+                    transition,
+                    shown,
+                });
+                self.animation_requested = true;
+            }
+        }
+        any
+    }
+
+    /// Returns exit transition of a running component and the area it is drawn in, if it has one.
+    fn exit_transition(&self, layer_id: LayerId, component_id: Id) -> Option<(Transition, Rect)> {
+        if self.jobs.is_none() || self.reduced_motion {
+            return None;
+        }
+        let transition = self.layers.get(layer_id, component_id)?.exit_transition()?;
+        let (_, _, area) = self
+            .laid_out(self.size)
+            .find(|(l, c, _)| *l == layer_id && c.id() == component_id)?;
+        Some((transition, area))
     }
 
     /// Downcasts mounted component and returns a reference to it.
     pub fn get_at<C: Component<S, E>>(&self, layer_id: LayerId, component_id: Id) -> Option<&C> {
-        let dyncomp = &**self
-            .layers
-            .get(&layer_id)?
-            .iter()
-            .find(|c| c.id() == component_id)? as &dyn Any;
-        dyncomp.downcast_ref::<C>()
+        let Some(component) = self.layers.get(layer_id, component_id) else {
+            self.note_dangling(Some(layer_id), component_id);
+            return None;
+        };
+
+        (component as &dyn Any).downcast_ref::<C>()
+    }
+
+    /// Returns the component `handle` refers to, `None` if it was removed.
+    pub fn get_handle<C: Component<S, E>>(&self, handle: &Handle<C>) -> Option<&C> {
+        self.get_at(handle.layer_id(), handle.id())
+    }
+
+    /// Same as [`Self::get_handle`] but returns a mutable reference.
+    pub fn get_handle_mut<C: Component<S, E>>(&mut self, handle: &Handle<C>) -> Option<&mut C> {
+        self.get_mut_at(handle.layer_id(), handle.id())
+    }
+
+    /// Downcasts the topmost mounted component with `component_id` on any layer, see [`Self::get_at`].
+    pub fn get<C: Component<S, E>>(&self, component_id: Id) -> Option<&C> {
+        let Some(layer_id) = self.find_layer(component_id) else {
+            self.note_dangling(None, component_id);
+            return None;
+        };
+        self.get_at(layer_id, component_id)
+    }
+
+    /// Same as [`Self::get`] but returns a mutable reference.
+    pub fn get_mut<C: Component<S, E>>(&mut self, component_id: Id) -> Option<&mut C> {
+        let Some(layer_id) = self.find_layer(component_id) else {
+            self.note_dangling(None, component_id);
+            return None;
+        };
+        self.get_mut_at(layer_id, component_id)
+    }
+
+    /// Unmounts the topmost component with `component_id` on any layer and downcasts it, see [`Self::take_at`].
+    pub fn take<C: Component<S, E>>(&mut self, component_id: Id) -> Option<Box<C>> {
+        let Some(layer_id) = self.find_layer(component_id) else {
+            self.note_dangling(None, component_id);
+            return None;
+        };
+        self.take_at(layer_id, component_id)
+    }
+
+    /// Returns the topmost layer with a component with `component_id`.
+    pub fn find_layer(&self, component_id: Id) -> Option<LayerId> {
+        self.layers.find(component_id, |_| true)
     }
 
     /// Downcasts mounted component and returns a mutable reference to it.
@@ -140,11 +1099,13 @@ impl<S: 'static, E: 'static> Compositor<S, E> {
         layer_id: LayerId,
         component_id: Id,
     ) -> Option<&mut C> {
-        let dyncomp = &mut **self
-            .layers
-            .get_mut(&layer_id)?
-            .iter_mut()
-            .find(|c| c.id() == component_id)? as &mut dyn Any;
+        if !self.layers.contains(layer_id, component_id) {
+            self.note_dangling(Some(layer_id), component_id);
+            return None;
+        }
+
+        self.redraw = true;
+        let dyncomp = &mut **self.layers.get_mut(layer_id, component_id)? as &mut dyn Any;
         dyncomp.downcast_mut::<C>()
     }
 
@@ -154,29 +1115,134 @@ impl<S: 'static, E: 'static> Compositor<S, E> {
         layer_id: LayerId,
         component_id: Id,
     ) -> Option<Box<C>> {
-        let layer = self.layers.get_mut(&layer_id)?;
-        let position = layer.iter().position(|c| c.id() == component_id)?;
+        let Some(component) = self.layers.get(layer_id, component_id) else {
+            self.note_dangling(Some(layer_id), component_id);
+            return None;
+        };
 
-        let dyncomp = layer.swap_remove(position) as Box<dyn Any>;
-        match dyncomp.downcast::<C>() {
-            Ok(comp) => Some(comp),
-            Err(other) => {
-                // SAFETY: It's the same component we casted above.
-                let dyncomp = unsafe { transmute::<Box<dyn Any>, Box<dyn Component<S, E>>>(other) };
-                layer.push(dyncomp);
+        if !(component as &dyn Any).is::<C>() {
+            return None;
+        }
+        let mut component = self.layers.remove(layer_id, component_id)?;
+        self.unmount(layer_id, &mut *component);
 
-                None
-            }
+        (component as Box<dyn Any>).downcast::<C>().ok()
+    }
+
+    /// Downcasts component with `component_id` on any layer, returning it together with the state.
+    #[cfg_attr(not(feature = "rhai"), allow(dead_code))]
+    pub(crate) fn find_mut<C: Component<S, E>>(
+        &mut self,
+        component_id: Id,
+    ) -> Option<(&mut C, &mut S)> {
+        let layer_id = self.layers.find(component_id, |_| true)?;
+        let component = self.layers.get_mut(layer_id, component_id)?;
+        self.redraw = true;
+
+        let dyncomp = &mut **component as &mut dyn Any;
+        Some((dyncomp.downcast_mut::<C>()?, &mut self.state))
+    }
+
+    /// Returns layers and ids of all mounted components from bottom to top.
+    pub(crate) fn component_ids(&self) -> impl Iterator<Item = (LayerId, Id)> + '_ {
+        self.iter().map(|(layer_id, id, _)| (layer_id, id))
+    }
+
+    /// Iterates over all mounted components from the bottom layer up, in the order they are drawn.
+    pub fn iter(&self) -> impl Iterator<Item = (LayerId, Id, &dyn Component<S, E>)> + '_ {
+        self.layers
+            .iter()
+            .flat_map(|(layer_id, l)| l.iter().map(move |c| (layer_id, c.id(), &**c)))
+    }
+
+    /// Same as [`Self::iter`] but with mutable references.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (LayerId, Id, &mut dyn Component<S, E>)> + '_ {
+        self.redraw = true;
+        self.layers.iter_mut().flat_map(|(layer_id, l)| {
+            l.iter_mut()
+                .map(move |c| (layer_id, c.id(), &mut **c as &mut dyn Component<S, E>))
+        })
+    }
+
+    /// Calls `visitor` with every mounted component from the bottom layer up,
+    /// downcast them with [`Any`] to access their fields.
+    pub fn visit(&mut self, mut visitor: impl FnMut(LayerId, &mut dyn Component<S, E>)) {
+        for (layer_id, _, component) in self.iter_mut() {
+            visitor(layer_id, component);
         }
     }
 
     /// Removes component at a layer, returning `true` if the component was removed.
     pub fn remove_at(&mut self, layer_id: LayerId, component_id: Id) -> bool {
-        self.layers
-            .get_mut(&layer_id)
-            .unwrap()
-            .retain(|c| c.id() != component_id);
-        true
+        let removed = self.detach(Some(layer_id), component_id);
+
+        if !removed {
+            self.note_dangling(Some(layer_id), component_id);
+        }
+        removed
+    }
+
+    /// Returns values components keep outside of the state, see [`Context::memory`].
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    /// Returns values components keep outside of the state mutably, see [`Context::memory`].
+    pub fn memory_mut(&mut self) -> &mut Memory {
+        &mut self.memory
+    }
+
+    /// Adds a service shared by all components, e.g. a database handle, replacing the previous
+    /// resource of type `T`, see [`Resources`].
+    pub fn insert_resource<T: 'static>(&mut self, resource: T) -> Option<T> {
+        self.resources.insert(resource)
+    }
+
+    /// Removes the resource of type `T`.
+    pub fn remove_resource<T: 'static>(&mut self) -> Option<T> {
+        self.resources.remove()
+    }
+
+    /// Returns the resource of type `T`, see [`Self::insert_resource`].
+    pub fn resource<T: 'static>(&self) -> Option<&T> {
+        self.resources.get()
+    }
+
+    /// Returns the resource of type `T` mutably, see [`Self::insert_resource`].
+    pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.resources.get_mut()
+    }
+
+    /// Returns all resources, see [`Self::insert_resource`].
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    /// Returns the history of commands applied with [`Context::apply`], see [`Self::with_history`].
+    #[cfg(feature = "history")]
+    #[doc(cfg(feature = "history"))]
+    pub fn history(&self) -> Option<&History<S>> {
+        self.history.as_ref()
+    }
+
+    /// Reverts the last step of the history, see [`Context::undo`].
+    #[cfg(feature = "history")]
+    #[doc(cfg(feature = "history"))]
+    pub fn undo(&mut self) -> bool {
+        let undone = (self.history.as_mut()).is_some_and(|history| history.undo(&mut self.state));
+        self.redraw |= undone;
+        undone
+    }
+
+    /// Applies the last undone step of the history again, see [`Context::redo`].
+    #[cfg(feature = "history")]
+    #[doc(cfg(feature = "history"))]
+    pub fn redo(&mut self) -> bool {
+        let redone = (self.history.as_mut()).is_some_and(|history| history.redo(&mut self.state));
+        self.redraw |= redone;
+        redone
     }
 
     /// Returns state of the compositor immutably.
@@ -186,13 +1252,536 @@ impl<S: 'static, E: 'static> Compositor<S, E> {
 
     /// Returns state of the compositor mutably.
     pub fn state_mut(&mut self) -> &mut S {
+        self.redraw = true;
         &mut self.state
     }
 
-    /// Exit the compositor.
-    pub fn exit(&mut self) {
+    /// Draws the next frame, see [`Context::request_redraw`].
+    pub fn request_redraw(&mut self) {
+        self.redraw = true;
+    }
+
+    /// Returns capabilities of the terminal, detected when the compositor starts running.
+    pub fn terminal_caps(&self) -> &TerminalCaps {
+        &self.caps
+    }
+
+    /// Shows a desktop notification, returns `false` if the terminal doesn't support notifications.
+    /// Useful in job callbacks to alert users that switched away from the terminal.
+    pub fn notify_desktop(&mut self, title: &str, body: &str) -> bool {
+        window::notification_sequence(&self.caps, title, body)
+            .map(|seq| self.escapes.extend_from_slice(seq.as_bytes()))
+            .is_some()
+    }
+
+    /// Returns the last known terminal size, it is queried on start and updated on [`TerminalEvent::Resize`]
+    /// and whenever a frame is drawn.
+    pub fn size(&self) -> Rect {
+        self.size
+    }
+
+    /// Renders all components at the last known terminal size and exports the frame,
+    /// e.g. for documentation screenshots and bug reports.
+    pub fn export_frame(&self, format: ExportFormat) -> String {
+        export::export(&self.render_to_buffer(self.size), format)
+    }
+
+    /// Draws all components into a new buffer covering `area`, without a terminal.
+    /// Useful for previews and tests, the area doesn't have to match the terminal's size.
+    pub fn render_to_buffer(&self, area: Rect) -> Buffer {
+        let mut buf = Buffer::empty(area);
+        self.compose(&mut buf);
+        buf
+    }
+
+    /// Splits the screen between components of the layer in the order they were mounted,
+    /// components without a matching chunk get the whole screen. Pass `None` to remove the layout.
+    /// Components can adjust their area further with [`Component::layout`].
+    pub fn set_layout(&mut self, layer_id: LayerId, layout: impl Into<Option<Layout>>) {
+        match layout.into() {
+            Some(layout) => self.layouts.insert(layer_id, layout),
+            None => self.layouts.remove(&layer_id),
+        };
+        self.redraw = true;
+    }
+
+    /// Makes the layer modal: while it has components mounted, terminal events never reach layers below it,
+    /// including the focused component, e.g. for dialogs at [`LayerId::POPUP`]. Other events are not affected.
+    pub fn set_modal(&mut self, layer_id: LayerId, modal: bool) {
+        if modal {
+            self.modal.insert(layer_id);
+        } else {
+            self.modal.remove(&layer_id);
+        }
+    }
+
+    /// Hides or shows the layer without unmounting its components, e.g. a debug overlay.
+    /// Components of hidden layers are not drawn, don't receive terminal events and can't be focused,
+    /// other events are still delivered to them.
+    pub fn set_layer_visible(&mut self, layer_id: LayerId, visible: bool) {
+        if visible {
+            self.hidden.remove(&layer_id);
+        } else {
+            self.hidden.insert(layer_id);
+        }
+        self.redraw = true;
+    }
+
+    /// Returns `false` if the layer is hidden.
+    pub fn is_layer_visible(&self, layer_id: LayerId) -> bool {
+        !self.hidden.contains(&layer_id)
+    }
+
+    /// Sets a style applied to everything drawn by layers below the layer while it has components,
+    /// e.g. `Style::new().add_modifier(Modifier::DIM)` to dim the ui behind popups.
+    /// Pass `None` to remove it.
+    pub fn set_backdrop(&mut self, layer_id: LayerId, style: impl Into<Option<Style>>) {
+        match style.into() {
+            Some(style) => self.backdrops.insert(layer_id, style),
+            None => self.backdrops.remove(&layer_id),
+        };
+        self.redraw = true;
+    }
+
+    /// Returns areas of mounted components from the bottom layer up, as they were last drawn.
+    pub fn areas(&self) -> impl Iterator<Item = (LayerId, Id, Rect)> + '_ {
+        self.laid_out(self.size)
+            .map(|(layer_id, c, area)| (layer_id, c.id(), area))
+    }
+
+    /// Returns id of the focused component.
+    pub fn focused(&self) -> Option<Id> {
+        self.focused
+    }
+
+    /// Returns timings of components, `None` unless enabled with [`Self::with_metrics`].
+    pub fn metrics(&self) -> Option<&Metrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Returns the status bar, `None` unless enabled with [`Self::with_status_bar`].
+    pub fn status_bar(&self) -> Option<&StatusBar> {
+        self.status_bar.as_ref()
+    }
+
+    /// Shows `segment` in the [`StatusBar`] under `name`, replacing the segment set under it
+    /// with this function. Ignored if the status bar isn't enabled.
+    pub fn set_status(&mut self, name: &'static str, segment: StatusSegment) {
+        if let Some(bar) = self.status_bar.as_mut() {
+            bar.set(None, name, segment);
+            self.redraw = true;
+        }
+    }
+
+    /// Removes the segment set under `name` with [`Self::set_status`].
+    pub fn clear_status(&mut self, name: &'static str) {
+        if let Some(bar) = self.status_bar.as_mut() {
+            bar.clear(None, name);
+            self.redraw = true;
+        }
+    }
+
+    /// Focuses component with `component_id`, key events are delivered to it first and then to the rest
+    /// of components in the usual order. Returns `false` if the component isn't mounted.
+    pub fn set_focus(&mut self, component_id: Id) -> bool {
+        let mounted = self.component_ids().any(|(_, id)| id == component_id);
+        if mounted {
+            self.focused = Some(component_id);
+            self.redraw = true;
+        }
+        mounted
+    }
+
+    /// Removes focus.
+    pub fn clear_focus(&mut self) {
+        self.focused = None;
+        self.redraw = true;
+    }
+
+    /// Focuses the next focusable component from the bottom layer up, wrapping around.
+    /// Returns id of the newly focused component, `None` if there are no focusable components.
+    pub fn focus_next(&mut self) -> Option<Id> {
+        self.cycle_focus(false)
+    }
+
+    /// Focuses the previous focusable component, see [`Self::focus_next`].
+    pub fn focus_prev(&mut self) -> Option<Id> {
+        self.cycle_focus(true)
+    }
+
+    /// Returns the keymap, see [`Self::with_keymap`].
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// Returns the keymap to change bindings at runtime, e.g. from a job callback.
+    pub fn keymap_mut(&mut self) -> &mut Keymap {
+        &mut self.keymap
+    }
+
+    /// Shows a [`HelpPopup`] on [`LayerId::OVERLAY`] listing bindings of the actions components
+    /// in visible layers above the topmost modal one handle, see [`Component::actions`].
+    /// Actions are grouped by [`Component::title`], or by layer for components without one.
+    /// Bound actions no component lists are shown as general ones. Hides the popup if it is shown.
+    pub fn show_help(&mut self) {
+        if self.detach(Some(LayerId::OVERLAY), HelpPopup::popup_id()) {
+            return;
+        }
+
+        let floor = self.modal_floor();
+        let mut sections = Vec::new();
+        for (layer_id, layer) in self.layers.iter().rev() {
+            if floor.is_some_and(|floor| layer_id < floor) {
+                break;
+            }
+            if self.hidden.contains(&layer_id) {
+                continue;
+            }
+
+            let title = self
+                .localizer
+                .translate("gland-help-layer", &[("layer", layer_id.0.into())])
+                .unwrap_or_else(|| format!("Layer {}", layer_id.0));
+            let mut untitled = HelpSection {
+                title,
+                bindings: Vec::new(),
+            };
+            for component in layer.iter().rev() {
+                let bindings = self.help_bindings(component.actions().iter());
+                match component.title() {
+                    _ if bindings.is_empty() => {}
+                    Some(title) => sections.push(HelpSection {
+                        title: title.to_owned(),
+                        bindings,
+                    }),
+                    None => untitled.bindings.extend(bindings),
+                }
+            }
+            if !untitled.bindings.is_empty() {
+                sections.push(untitled);
+            }
+        }
+
+        let listed = self
+            .layers
+            .iter()
+            .flat_map(|(_, layer)| layer.iter().flat_map(|c| c.actions()))
+            .collect::<BTreeSet<_>>();
+        let general = self
+            .keymap
+            .bindings()
+            .iter()
+            .map(|b| &b.action)
+            .filter(|action| !listed.contains(action))
+            .collect::<BTreeSet<_>>();
+        let bindings = self.help_bindings(general.into_iter());
+        if !bindings.is_empty() {
+            let title = self.localizer.text("gland-help-general", "General");
+            sections.push(HelpSection {
+                title: title.into_owned(),
+                bindings,
+            });
+        }
+
+        let title = self.localizer.text("gland-help-title", "Help").into_owned();
+        let mut popup = HelpPopup::new(title, sections);
+        if let Some(key) = self.help_key {
+            popup = popup.with_close_key(key);
+        }
+        _ = self.insert_at(LayerId::OVERLAY, popup);
+    }
+
+    /// Registers `command` listed in the [`CommandPalette`], replacing the command with the same action.
+    /// Running it passes its action to components like a bound key, see [`Component::handle_action`].
+    pub fn register_command(&mut self, command: Command) {
+        self.add_command(None, command);
+    }
+
+    /// Removes the command with `action`, returns `false` if there is none.
+    pub fn unregister_command(&mut self, action: &Action) -> bool {
+        let len = self.commands.len();
+        self.commands.retain(|(_, c)| c.action != *action);
+        self.commands.len() != len
+    }
+
+    /// Returns registered commands.
+    pub fn commands(&self) -> impl Iterator<Item = &Command> + '_ {
+        self.commands.iter().map(|(_, c)| c)
+    }
+
+    /// Passes `action` to the component that registered its command first, see [`Context::register_command`],
+    /// then to components like a bound key. Returns `true` if a component handled it.
+    /// Returns `false` if the compositor isn't running yet.
+    pub fn run_command(&mut self, action: &Action) -> bool {
+        let Some(jobs) = self.jobs.as_ref() else {
+            return false;
+        };
+        let owner = self
+            .commands
+            .iter()
+            .find(|(_, c)| c.action == *action)
+            .and_then(|(owner, _)| *owner);
+        let floor = self.modal_floor();
+        let order = owner
+            .and_then(|id| Some((self.layers.find(id, |_| true)?, id)))
+            .into_iter()
+            .chain(
+                action_order(&self.layers, &self.hidden, self.focused, floor)
+                    .into_iter()
+                    .filter(|(_, id)| owner != Some(*id)),
+            )
+            .collect::<Vec<_>>();
+
+        let mut cx = context!(self, jobs);
+        let handled = order.into_iter().any(|(layer_id, id)| {
+            self.layers
+                .get_mut(layer_id, id)
+                .is_some_and(|c| c.handle_action(action, &mut cx))
+        });
+        let output = cx.into_output();
+        self.redraw |= handled;
+        self.apply(output);
+        handled
+    }
+
+    /// Shows a [`CommandPalette`] on [`LayerId::OVERLAY`] with commands registered by components
+    /// in visible layers above the topmost modal one and commands not registered by a component.
+    /// Hides the palette if it is shown.
+    pub fn show_command_palette(&mut self) {
+        if self.detach(Some(LayerId::OVERLAY), CommandPalette::palette_id()) {
+            return;
+        }
+
+        let floor = self.modal_floor();
+        let active = |id| {
+            self.layers.find(id, |layer_id| {
+                !floor.is_some_and(|floor| layer_id < floor) && !self.hidden.contains(&layer_id)
+            })
+        };
+        let commands = self
+            .commands
+            .iter()
+            .filter(|(owner, _)| owner.is_none_or(|id| active(id).is_some()))
+            .map(|(_, command)| {
+                let keys = self
+                    .keymap
+                    .keys_for(&command.action)
+                    .map(|keys| {
+                        keys.iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<_>>();
+                (command.clone(), keys.join(", "))
+            })
+            .collect();
+
+        let title = self
+            .localizer
+            .text("gland-command-palette-title", "Commands")
+            .into_owned();
+        _ = self.insert_at(LayerId::OVERLAY, CommandPalette::new(title, commands));
+    }
+
+    /// Shows the [`DebugOverlay`] on [`LayerId::TOPMOST`], or hides it if it is shown.
+    /// Statistics and [metrics](Self::metrics) are collected from the first time it is shown.
+    #[cfg(feature = "debug")]
+    #[doc(cfg(feature = "debug"))]
+    pub fn toggle_debug_overlay(&mut self) {
+        if !self.detach(Some(LayerId::TOPMOST), DebugOverlay::overlay_id()) {
+            self.debug.get_or_insert_with(DebugStats::default);
+            self.metrics.get_or_insert_with(Metrics::default);
+            _ = self.insert_at(LayerId::TOPMOST, DebugOverlay::new());
+        }
+    }
+
+    /// Sends `message` to the topmost component with `id` right away, see [`Context::send_to`].
+    /// Returns `false` if there is no such component or the compositor isn't running yet.
+    pub fn send_to(&mut self, id: Id, message: impl Any) -> bool {
+        self.deliver(id, Box::new(message))
+    }
+
+    /// Pushes `component` as the top screen on [`LayerId::MIDDLE`], covering the current top screen.
+    /// Covered screens are neither drawn nor receive events, but stay mounted so they keep their state
+    /// until they are revealed by [`Self::pop_screen`]. Returns the component if a screen with its id exists.
+    pub fn push_screen<C: Component<S, E>>(&mut self, component: C) -> Result<Handle<C>, C> {
+        let id = component.id();
+        if self.has_screen(id) {
+            return Err(component);
+        }
+        _ = self.push_screen_boxed(Box::new(component));
+        Ok(Handle::new(LayerId::MIDDLE, id))
+    }
+
+    /// Removes the top screen and reveals the one below it.
+    /// Returns `false` if there is no screen below the top one, so the last screen is never popped.
+    pub fn pop_screen(&mut self) -> bool {
+        let Some(covered) = self.covered_screens.pop() else {
+            return false;
+        };
+        if let Some(top) = self.screen.take() {
+            self.screen_hook(top, false);
+            self.detach(Some(LayerId::MIDDLE), top);
+        }
+
+        let id = covered.id();
+        let index = self.layers.len(LayerId::MIDDLE);
+        self.layers.insert(LayerId::MIDDLE, index, covered);
+        self.screen = Some(id);
+        self.redraw = true;
+        self.screen_hook(id, true);
+        true
+    }
+
+    /// Replaces the top screen with `component`, the replaced screen is removed.
+    /// Returns the component if a screen with its id exists.
+    pub fn replace_screen<C: Component<S, E>>(&mut self, component: C) -> Result<Handle<C>, C> {
+        let id = component.id();
+        if self.has_screen(id) && self.screen != Some(id) {
+            return Err(component);
+        }
+        _ = self.replace_screen_boxed(Box::new(component));
+        Ok(Handle::new(LayerId::MIDDLE, id))
+    }
+
+    /// Returns the number of screens, including the covered ones.
+    pub fn screen_depth(&self) -> usize {
+        self.covered_screens.len() + self.screen.is_some() as usize
+    }
+
+    /// Announces text to assistive technology, see [`Context::announce`].
+    pub fn announce(&mut self, announcement: Announcement) {
+        match self.announcer.as_mut() {
+            Some(announcer) => announcer(announcement),
+            None if announcement.priority == Priority::Assertive => {
+                self.notify_desktop("", &announcement.text);
+            }
+            None => {}
+        }
+    }
+
+    /// Returns `true` if animations should be skipped or shortened.
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    /// Returns the localizer built-in components translate their text with.
+    pub fn localizer(&self) -> &Arc<dyn Localizer> {
+        &self.localizer
+    }
+
+    /// Requests to exit the compositor after the current update. [`Event::Exit`] is dispatched to components
+    /// first, consuming it cancels the exit, e.g. to ask to save changes.
+    pub fn exit(&mut self) {
+        self.exit_requested = true;
+    }
+
+    /// Exits the compositor after the current update without asking components.
+    pub fn force_exit(&mut self) {
         self.exit = true;
     }
+
+    /// Returns lookups that job callbacks, context callbacks and animations made for components
+    /// that were no longer mounted, see [`DanglingAccess`]. Always empty in release builds.
+    pub fn dangling_accesses(&self) -> Vec<DanglingAccess> {
+        self.dangling.borrow().clone()
+    }
+
+    fn note_dangling(&self, layer_id: Option<LayerId>, component_id: Id) {
+        if cfg!(debug_assertions) && self.in_callback {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(component = ?component_id, layer = ?layer_id, "callback looked up an unmounted component");
+            self.dangling.borrow_mut().push(DanglingAccess {
+                layer_id,
+                component_id,
+            });
+        }
+    }
+}
+
+/// Embedding functions
+impl<S: 'static, E: 'static> Compositor<S, E> {
+    /// Dispatches `event` to components, for driving the compositor from an existing loop
+    /// instead of [`Self::run`]. Callbacks of finished jobs are run first.
+    ///
+    /// The terminal is not set up, streams are not polled, ticks are not emitted and closures passed to
+    /// [`Context::suspend`] are not run, that is left to the caller, see [`Self::take_suspended`].
+    /// Jobs use the runtime set with [`Self::with_runtime`], tokio if called inside of a tokio runtime,
+    /// smol, or a thread pool if there is none and the `blocking` feature is enabled.
+    ///
+    /// Returns `true` if a component consumed the event or marked it as handled, see [`Context::mark_handled`].
+    pub fn dispatch(&mut self, event: Event<E>) -> bool {
+        let jobs = self.embed();
+        self.run_job_callbacks(&jobs);
+        self.handled = false;
+        if !self.exit {
+            self.handle(Resume::Event(event), &jobs);
+        }
+        self.handled
+    }
+
+    /// Draws components to `terminal`, see [`Self::dispatch`]. Callbacks of finished jobs are run first.
+    /// Escape sequences like the window title are written to stdout.
+    pub fn render<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        let jobs = self.embed();
+        self.size = terminal.size()?;
+        self.run_job_callbacks(&jobs);
+        self.redraw = false;
+        self.draw(std::slice::from_mut(terminal), None)
+    }
+
+    /// Returns closures passed to [`Context::suspend`] since the last call, callbacks of finished jobs are run first.
+    /// [`Self::dispatch`] and [`Self::render`] don't run them, the caller restores the terminal, calls them in order,
+    /// then sets the terminal up again and redraws in full.
+    pub fn take_suspended(&mut self) -> Vec<Box<dyn FnOnce()>> {
+        let jobs = self.embed();
+        self.run_job_callbacks(&jobs);
+        take(&mut self.suspended)
+    }
+
+    /// Returns `true` if something changed since the last frame, see [`Context::request_redraw`].
+    pub fn needs_redraw(&self) -> bool {
+        self.redraw
+    }
+
+    /// Returns `true` once the compositor is exiting, see [`Self::exit`].
+    pub fn should_exit(&self) -> bool {
+        self.exit
+    }
+
+    /// Starts jobs and mounts pending components on the first call.
+    fn embed(&mut self) -> Jobs<S, E> {
+        if let Some(jobs) = &self.jobs {
+            return jobs.clone();
+        }
+
+        let runtime = self.runtime.take().or_else(default_runtime);
+        #[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+        let runtime = runtime.or_else(|| {
+            let runtime = ThreadRuntime::new().expect("failed to start job threads");
+            Some(Arc::new(runtime) as Arc<dyn Runtime>)
+        });
+        let runtime = runtime.expect("no async runtime, set it with `with_runtime`");
+
+        let (sender, rx) = mpsc::unbounded();
+        let jobs = Jobs::new(sender, runtime);
+        self.embedded_callbacks = Some(rx);
+        self.jobs = Some(jobs.clone());
+        self.start();
+        jobs
+    }
+
+    fn run_job_callbacks(&mut self, jobs: &Jobs<S, E>) {
+        while let Some(Ok(Some(callback))) =
+            self.embedded_callbacks.as_mut().map(|rx| rx.try_next())
+        {
+            self.handle(callback, jobs);
+            if self.exit {
+                break;
+            }
+        }
+    }
 }
 
 /// Builder functions
@@ -201,9 +1790,81 @@ impl<S: 'static, E: 'static> Compositor<S, E> {
     pub fn with_state(state: S) -> Self {
         Self {
             timeout: Duration::from_secs(3),
-            layers: BTreeMap::new(),
+            animation_interval: Duration::from_secs(1) / 60,
+            animation_requested: false,
+            animation_scheduled: false,
+            animation_frames: 0,
+            animations: Vec::new(),
+            entering: HashMap::new(),
+            exiting: Vec::new(),
+            batch_size: 64,
+            frame_interval: None,
+            frame_diff: None,
+            setup: Setup::default(),
+            terminal_options: TerminalOptions::default(),
+            runtime: None,
+            caps: TerminalCaps::default(),
+            detect_caps: true,
+            escapes: Vec::new(),
+            memory: Memory::default(),
+            resources: Resources::default(),
+            #[cfg(feature = "history")]
+            history: None,
+            #[cfg(feature = "persist")]
+            persistent: Vec::new(),
+            #[cfg(feature = "persist")]
+            session_file: None,
+            redraw: true,
+            pointer: PointerShape::Default,
+            cursor_style: CursorStyle::Default,
+            shown_cursor_style: CursorStyle::Default,
+            focused: None,
+            tab_focus: false,
+            keymap: Keymap::default(),
+            help_key: None,
+            back_key: None,
+            screen: None,
+            covered_screens: Vec::new(),
+            commands: Vec::new(),
+            palette_key: None,
+            handled: false,
+            announcer: None,
+            error_handler: None,
+            event_hooks: Vec::new(),
+            after_render: Vec::new(),
+            plugins: Vec::new(),
+            metrics: None,
+            status_bar: None,
+            #[cfg(feature = "debug")]
+            debug: None,
+            failure: None,
+            isolation: false,
+            faulted: RefCell::default(),
+            reduced_motion: false,
+            localizer: Arc::new(DefaultLocalizer),
+            size: Rect::default(),
+            layers: Layers::default(),
+            layouts: BTreeMap::new(),
+            modal: BTreeSet::new(),
+            backdrops: BTreeMap::new(),
+            hidden: BTreeSet::new(),
             streams: Vec::new(),
+            terminal_input: Vec::new(),
+            headless_fallback: false,
+            jobs: None,
+            suspended: Vec::new(),
+            #[cfg(feature = "event-stream")]
+            input: None,
+            #[cfg(feature = "termion")]
+            termion_input: None,
+            embedded_callbacks: None,
+            pending_mounts: Vec::new(),
+            in_callback: false,
+            dangling: RefCell::new(Vec::new()),
+            views: RefCell::default(),
+            watches: RefCell::default(),
             exit: false,
+            exit_requested: false,
             state,
         }
     }
@@ -215,166 +1876,2060 @@ impl<S: 'static, E: 'static> Compositor<S, E> {
         self
     }
 
-    /// Adds new stream of events, UI is re-rendered when event is received.
-    pub fn with_stream(mut self, stream: impl Stream<Item = Event<E>> + 'static) -> Self {
-        self.streams.push(Box::pin(stream.map(Resume::Event)));
+    /// Sets the interval of [`Event::AnimationFrame`], they are only emitted while components request them
+    /// with [`Context::request_animation_frame`], unlike ticks. Default is 1/60 of a second.
+    pub fn with_animation_ticks(mut self, interval: Duration) -> Self {
+        self.animation_interval = interval;
         self
     }
 
-    /// Adds new stream that emits user events built from the receiver.
-    pub fn with_receiver_stream(self, recv: Receiver<E>) -> Self {
-        self.with_stream(ReceiverStream::new(recv).map(Event::User))
+    /// Sets how many events that are already available are dispatched before drawing a frame,
+    /// so bursts like mouse drags, pastes and resizes are drawn once. Default is 64, 1 draws after every event.
+    pub fn with_event_batch(mut self, size: usize) -> Self {
+        self.batch_size = size.max(1);
+        self
     }
 
-    /// Adds new stream created from terminal event.
-    #[cfg(feature = "event-stream")]
-    #[doc(cfg(feature = "event-stream"))]
-    pub fn with_event_stream(self) -> Self {
-        use crossterm::event::EventStream;
+    /// Draws at most `fps` frames per second, events are still dispatched as they arrive
+    /// and changes are drawn together in the next frame. Not limited by default.
+    pub fn with_max_fps(mut self, fps: u32) -> Self {
+        self.frame_interval = Some(Duration::from_secs(1) / fps.max(1));
+        self
+    }
 
-        let stream = EventStream::new()
-            .map(|x| x.expect("failed to receive a terminal event"))
-            .map(Event::Terminal);
-        self.with_stream(stream)
+    /// Enables debug rendering mode that inverts cells changed since the previous frame for `highlight` duration.
+    /// Useful to spot components that redraw more than they should. The frame is drawn again once highlights expire.
+    pub fn with_frame_diff(mut self, highlight: Duration) -> Self {
+        self.frame_diff = Some(FrameDiff::new(highlight));
+        self
     }
 
-    /// Exit the compositor when this future resolves
-    pub fn with_shutdown(self, shutdown: impl Future + 'static) -> Self {
-        self.with_stream(stream::once(shutdown).map(|_| Event::Exit))
+    /// Overrides terminal capabilities instead of detecting them from the environment on start.
+    pub fn with_terminal_caps(mut self, caps: TerminalCaps) -> Self {
+        self.caps = caps;
+        self.detect_caps = false;
+        self
     }
 
-    /// Begin polling events and draw ui. Exit after [`Event::Exit`] is emitted or [`Self::exit`] is called.
-    pub async fn run<B: Backend>(mut self, backend: B) -> io::Result<()> {
-        let _guard = TerminalGuard::new()?;
+    /// Cycles focus with Tab and Shift-Tab if no component consumes them.
+    pub fn with_tab_focus(mut self) -> Self {
+        self.tab_focus = true;
+        self
+    }
 
-        if !self.timeout.is_zero() {
-            self.streams.push(Box::pin(
-                IntervalStream::new(interval(self.timeout))
-                    .map(|_| Event::Tick)
-                    .map(Resume::Event),
-            ));
-        }
+    /// Turns keys bound in `keymap` into actions passed to [`Component::handle_action`].
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
 
-        // Tick once at the start to draw initial ui.
-        self = self.with_stream(stream::iter([Event::Tick]));
+    /// Shows the [`HelpPopup`] when `key` is pressed and no component consumes it, e.g. `?`.
+    pub fn with_help_key(mut self, key: KeyEvent) -> Self {
+        self.help_key = Some(normalize(key));
+        self
+    }
 
-        let (sender, rx) = mpsc::channel(12);
-        self.streams.push(Box::pin(ReceiverStream::new(rx)));
+    /// Pops the top screen when `key` is pressed and no component consumes it, e.g. `esc`,
+    /// see [`Self::pop_screen`].
+    pub fn with_back_key(mut self, key: KeyEvent) -> Self {
+        self.back_key = Some(normalize(key));
+        self
+    }
 
-        let jobs = Jobs::new(sender);
+    /// Shows the [`CommandPalette`] when `key` is pressed and no component consumes it, e.g. `ctrl-p`.
+    pub fn with_palette_key(mut self, key: KeyEvent) -> Self {
+        self.palette_key = Some(normalize(key));
+        self
+    }
 
-        let mut flux = select_all(take(&mut self.streams));
-        let mut terminal = Terminal::new(backend)?;
-
-        while let Some(event) = flux.next().await {
-            let mut event = match event {
-                Resume::Event(e) => e,
-                Resume::JobCallback(callback) => {
-                    callback(&mut self);
-                    Event::None
-                }
-            };
-            assert!(
-                !matches!(event, Event::None),
-                "`None` event is not allowed to be emitted"
-            );
+    /// Registers `command` handled by any component, see [`Self::register_command`].
+    pub fn with_command(mut self, command: Command) -> Self {
+        self.register_command(command);
+        self
+    }
 
-            // Pass event to all components.
-            let mut cx: Context<S, E> = Context {
-                callbacks: Vec::with_capacity(8),
-                size: terminal.size()?,
-                state: self.state,
-                jobs: &jobs,
-            };
+    /// Sends announcements made by components to `announcer`, e.g. a speech synthesizer,
+    /// a channel to a braille driver or an append-only log file a screen reader follows.
+    pub fn with_announcer(mut self, announcer: impl FnMut(Announcement) + 'static) -> Self {
+        self.announcer = Some(Box::new(announcer));
+        self
+    }
 
-            // Iterate from top to bottom, break if event is consumed.
-            'outer: for layer in self.layers.values_mut().rev() {
-                for component in layer.iter_mut() {
-                    component.handle_event(&mut event, &mut cx);
+    /// Adds a function that sees every event before components do, e.g. for global shortcuts,
+    /// logging or remapping keys. It can change the event, consume it to stop it from reaching
+    /// components and later hooks, or mark it as handled. Hooks run in the order they are added,
+    /// requests made through the context are applied before components get the event.
+    pub fn with_event_hook(
+        mut self,
+        hook: impl FnMut(&mut Event<E>, &mut Context<S, E>) + 'static,
+    ) -> Self {
+        self.event_hooks.push(Box::new(hook));
+        self
+    }
 
-                    if matches!(event, Event::None) {
-                        break 'outer;
-                    }
-                }
-            }
+    /// Adds a function called with the frame and the state after every frame is drawn, e.g. to
+    /// capture frames or mirror them to another display. With multiple terminals it gets the frame
+    /// of the first one. Hooks run in the order they are added.
+    pub fn with_after_render(mut self, hook: impl FnMut(&Buffer, &S) + 'static) -> Self {
+        self.after_render.push(Box::new(hook));
+        self
+    }
 
-            let Context {
-                callbacks,
-                state,
-                size: _,
-                jobs: _,
-            } = cx;
-            self.state = state;
-            callbacks.into_iter().for_each(|cc| cc(&mut self));
+    /// Records commands applied with [`Context::apply`] so they can be undone, see [`history`](crate::history).
+    #[cfg(feature = "history")]
+    #[doc(cfg(feature = "history"))]
+    pub fn with_history(mut self, history: History<S>) -> Self {
+        self.history = Some(history);
+        self
+    }
 
-            if self.exit {
-                break;
-            }
+    /// Adds a service shared by all components, see [`Self::insert_resource`].
+    pub fn with_resource<T: 'static>(mut self, resource: T) -> Self {
+        self.resources.insert(resource);
+        self
+    }
 
-            terminal
-                .draw(|f| {
-                    self.layers.values().flat_map(|l| l.iter()).for_each(|c| {
-                        f.render_widget(
-                            ComponentWidget {
-                                component: &**c,
-                                state: &self.state,
-                            },
-                            f.size(),
-                        )
-                    });
-                })
-                .unwrap();
-        }
+    /// Adds a plugin, its hooks run before event hooks and hooks added with [`Self::with_after_render`].
+    /// Plugins run in the order they are added.
+    pub fn with_plugin(mut self, plugin: impl Plugin<S, E>) -> Self {
+        self.plugins.push(Box::new(plugin));
+        self
+    }
 
-        Ok(())
+    /// Reserves the bottom row of the screen for `bar`, layers are laid out above it.
+    pub fn with_status_bar(mut self, bar: StatusBar) -> Self {
+        self.status_bar = Some(bar);
+        self
     }
-}
 
-impl<S: 'static + Default, E: 'static> Default for Compositor<S, E> {
-    #[inline]
-    fn default() -> Self {
-        Self::with_state(S::default())
+    /// Records how long components spend drawing and handling events, see [`Self::metrics`].
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = Some(Metrics::default());
+        self
     }
-}
 
-struct ComponentWidget<'r, S, E> {
-    component: &'r dyn Component<S, E>,
-    state: &'r S,
-}
+    /// Toggles the [`DebugOverlay`] with `key` if no component consumes or handles it, e.g. `f12`.
+    #[cfg(feature = "debug")]
+    #[doc(cfg(feature = "debug"))]
+    pub fn with_debug_overlay(mut self, key: KeyEvent) -> Self {
+        self.debug.get_or_insert_with(DebugStats::default).key = Some(normalize(key));
+        self
+    }
 
-impl<'r, S: 'static, E: 'static> Widget for ComponentWidget<'r, S, E> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        self.component.view(area, buf, self.state);
+    /// Sets a function deciding what happens when drawing a frame or a component fails, e.g. to log
+    /// the error and continue. Without one the compositor stops and `run` returns drawing errors,
+    /// component errors are shown in an [`ErrorPopup`]. [`ErrorAction::Retry`] only applies to drawing.
+    pub fn with_error_handler(
+        mut self,
+        handler: impl FnMut(&Error) -> ErrorAction + 'static,
+    ) -> Self {
+        self.error_handler = Some(Box::new(handler));
+        self
     }
-}
 
-struct TerminalGuard;
-impl TerminalGuard {
-    fn new() -> io::Result<Self> {
-        enable_raw_mode()?;
-        execute!(
-            io::stdout(),
-            EnterAlternateScreen,
-            EnableMouseCapture,
-            // PushKeyboardEnhancementFlags(
-            //     KeyboardEnhancementFlags::REPORT_EVENT_TYPES
-            //         | KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
-            // ),
-            crossterm::terminal::Clear(ClearType::All)
-        )?;
-
-        Ok(Self)
+    /// Catches panics of components in [`Component::view`] and [`Component::try_handle_event`], the faulting
+    /// component is removed and the panic is reported as [`Error::Panicked`], see [`Self::with_error_handler`].
+    /// The panic hook still runs, the screen is cleared after it.
+    pub fn with_component_isolation(mut self) -> Self {
+        self.isolation = true;
+        self
     }
-}
 
-impl Drop for TerminalGuard {
-    fn drop(&mut self) {
-        _ = execute!(
-            io::stdout(),
-            // PopKeyboardEnhancementFlags,
-            DisableMouseCapture,
-            LeaveAlternateScreen,
-        );
-        _ = disable_raw_mode();
+    /// Makes animations and built-in components skip transitions and motion.
+    pub fn with_reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.reduced_motion = reduced_motion;
+        self
+    }
+
+    /// Splits the screen between components of the layer, see [`Self::set_layout`].
+    pub fn with_layout(mut self, layer_id: LayerId, layout: Layout) -> Self {
+        self.set_layout(layer_id, layout);
+        self
+    }
+
+    /// Makes the layer modal, see [`Self::set_modal`].
+    pub fn with_modal(mut self, layer_id: LayerId) -> Self {
+        self.set_modal(layer_id, true);
+        self
+    }
+
+    /// Sets a style applied below the layer while it has components, see [`Self::set_backdrop`].
+    pub fn with_backdrop(mut self, layer_id: LayerId, style: Style) -> Self {
+        self.set_backdrop(layer_id, style);
+        self
+    }
+
+    /// Sets the localizer for built-in components, they show English text by default.
+    pub fn with_localizer(mut self, localizer: impl Localizer) -> Self {
+        self.localizer = Arc::new(localizer);
+        self
+    }
+
+    /// Adds new stream of events, UI is re-rendered when event is received.
+    pub fn with_stream(mut self, stream: impl Stream<Item = Event<E>> + 'static) -> Self {
+        self.streams.push(Box::pin(stream.map(Resume::Event)));
+        self
+    }
+
+    /// Adds stream of terminal events, it is ignored in headless mode.
+    #[cfg_attr(not(feature = "event-stream"), allow(dead_code))]
+    fn with_terminal_input(mut self, stream: impl Stream<Item = Event<E>> + 'static) -> Self {
+        self.terminal_input
+            .push(Box::pin(stream.map(Resume::Event)));
+        self
+    }
+
+    #[cfg_attr(not(feature = "control"), allow(dead_code))]
+    pub(crate) fn with_resume_stream(
+        mut self,
+        stream: impl Stream<Item = Resume<S, E>> + 'static,
+    ) -> Self {
+        self.streams.push(Box::pin(stream));
+        self
+    }
+
+    /// Adds new stream that emits user events built from the receiver.
+    #[cfg(feature = "tokio")]
+    #[doc(cfg(feature = "tokio"))]
+    pub fn with_receiver_stream(self, recv: tokio::sync::mpsc::Receiver<E>) -> Self {
+        use tokio_stream::wrappers::ReceiverStream;
+
+        self.with_stream(ReceiverStream::new(recv).map(Event::User))
+    }
+
+    /// Sets the async runtime used to spawn jobs and drive timers.
+    /// Defaults to tokio if `tokio` feature is enabled, otherwise to smol if `smol` feature is enabled.
+    pub fn with_runtime(mut self, runtime: impl Runtime) -> Self {
+        self.runtime = Some(Arc::new(runtime));
+        self
+    }
+
+    /// Adds new stream created from terminal event.
+    #[cfg(feature = "event-stream")]
+    #[doc(cfg(feature = "event-stream"))]
+    pub fn with_event_stream(mut self) -> Self {
+        use crate::quirks::{crossterm_event, WindowsQuirks};
+
+        let mut quirks = WindowsQuirks::default();
+        let input = SuspendableInput::default();
+        self.input = Some(input.clone());
+        let stream = input
+            .map(|x| x.expect("failed to receive a terminal event"))
+            .filter_map(move |e| std::future::ready(crossterm_event(&mut quirks, e)))
+            .map(Event::Terminal);
+        self.with_terminal_input(stream)
+    }
+
+    /// Uses termion instead of crossterm to set up the terminal and adds a stream of terminal events read by a thread.
+    /// Use it together with ratatui's `TermionBackend`.
+    ///
+    /// Failing to read the terminal stops the compositor, the error is returned from [`Self::run`]
+    /// as [`Error::Io`].
+    #[cfg(feature = "termion")]
+    #[doc(cfg(feature = "termion"))]
+    pub fn with_termion(mut self) -> Self {
+        let input = TermionInput::default();
+        self.termion_input = Some(input.clone());
+        // Reading starts on first poll, there is no terminal to read from in headless mode.
+        let stream = stream::once(async move { input.spawn() })
+            .flatten()
+            .filter_map(|event| {
+                std::future::ready(match event {
+                    Ok(event) => Some(Resume::Event(Event::Terminal(event))),
+                    Err(error) => Some(Resume::JobCallback(Box::new(
+                        move |comp: &mut Compositor<S, E>| {
+                            comp.failure.get_or_insert(Error::Io(error));
+                            comp.force_exit();
+                        },
+                    ))),
+                })
+            });
+
+        self.setup = Setup::Termion;
+        self.terminal_input.push(Box::pin(stream));
+        self
+    }
+
+    /// Enables mouse reporting on start, enabled by default.
+    pub fn with_mouse_capture(mut self, enabled: bool) -> Self {
+        self.terminal_options.mouse_capture = enabled;
+        self
+    }
+
+    /// Draws on the alternate screen, leaving the shell's scrollback untouched. Enabled by default,
+    /// disable it for inline tools that leave their last frame on the screen.
+    pub fn with_alternate_screen(mut self, enabled: bool) -> Self {
+        self.terminal_options.alternate_screen = enabled;
+        self
+    }
+
+    /// Enables kitty keyboard protocol enhancements if the terminal supports them.
+    /// Key release events are dropped unless [`KeyboardEnhancementFlags::REPORT_EVENT_TYPES`] is set.
+    pub fn with_keyboard_enhancements(mut self, flags: KeyboardEnhancementFlags) -> Self {
+        self.terminal_options.keyboard = flags;
+        self
+    }
+
+    /// Clears the screen on start, enabled by default.
+    pub fn with_clear_on_start(mut self, enabled: bool) -> Self {
+        self.terminal_options.clear_on_start = enabled;
+        self
+    }
+
+    /// Runs without terminal IO if stdout is not a terminal instead of failing with [`Error::NotATty`],
+    /// e.g. when piped or in CI smoke tests. Events are still dispatched and frames are drawn off-screen,
+    /// terminal input is ignored. The off-screen size is read from `COLUMNS` and `LINES`, 80x24 by default.
+    pub fn with_headless_fallback(mut self) -> Self {
+        self.headless_fallback = true;
+        self
+    }
+
+    /// Exit the compositor when this future resolves
+    pub fn with_shutdown(self, shutdown: impl Future + 'static) -> Self {
+        self.with_stream(stream::once(shutdown).map(|_| Event::Exit))
+    }
+
+    /// Begin polling events and draw ui. Exit after [`Event::Exit`] is emitted or [`Self::exit`] is called.
+    /// Returns the final state, e.g. with what the user has selected.
+    ///
+    /// Fails with [`Error::NotATty`] if stdout is not a terminal, see [`Self::with_headless_fallback`].
+    pub async fn run<B: Backend>(self, backend: B) -> Result<S, Error> {
+        self.run_mirrored([backend]).await
+    }
+
+    /// Same as [`Self::run`] but draws every frame to all `backends`, mirroring the ui between them.
+    /// [`Context::size`] reports the size of the first backend.
+    pub async fn run_mirrored<B: Backend>(
+        mut self,
+        backends: impl IntoIterator<Item = B>,
+    ) -> Result<S, Error> {
+        if self.check_tty()? {
+            return self.run_with([headless_backend()], None).await;
+        }
+        self.run_with(backends, None).await
+    }
+
+    pub(crate) async fn run_with<B: Backend>(
+        mut self,
+        backends: impl IntoIterator<Item = B>,
+        write_raw: Option<RawWriter<B>>,
+    ) -> Result<S, Error> {
+        let runtime = self.runtime.take().or_else(default_runtime).expect(
+            "no async runtime, enable `tokio` or `smol` feature or set it with `with_runtime`",
+        );
+        self.detect_caps();
+        let mut guard =
+            TerminalGuard::new(self.setup, self.terminal_options, self.caps.kitty_keyboard)?;
+        let input = take(&mut self.terminal_input);
+        self.streams.extend(input);
+
+        if !self.timeout.is_zero() {
+            self.streams.push(Box::pin(
+                runtime
+                    .interval(self.timeout)
+                    .map(|_| Event::Tick)
+                    .map(Resume::Event),
+            ));
+        }
+
+        // Tick once at the start to draw initial ui.
+        self = self.with_stream(stream::iter([Event::Tick]));
+
+        let (sender, rx) = mpsc::unbounded();
+        self.streams.push(Box::pin(rx));
+
+        let jobs = Jobs::new(sender, runtime.clone());
+
+        let mut flux = select_all(take(&mut self.streams));
+        let mut terminals = backends
+            .into_iter()
+            .map(Terminal::new)
+            .collect::<io::Result<Vec<_>>>()?;
+        assert!(!terminals.is_empty(), "at least one backend is required");
+
+        self.jobs = Some(jobs.clone());
+        self.size = terminals[0].size()?;
+        self.start();
+
+        // Local jobs are driven together with the event loop.
+        let mut result = Ok(());
+        let mut last_frame = None;
+        let mut frame_due = None;
+        runtime
+            .run_local(Box::pin(async {
+                loop {
+                    // Frame diff highlights are cleared even if nothing else is drawn.
+                    if let Some(at) = self
+                        .frame_diff
+                        .as_mut()
+                        .and_then(FrameDiff::schedule_wakeup)
+                    {
+                        let wakeup = runtime.sleep(at.saturating_duration_since(Instant::now()));
+                        flux.push(Box::pin(
+                            stream::once(wakeup).map(|_| Resume::Request(Request::Redraw)),
+                        ));
+                    }
+
+                    // Frame delayed by the frame rate limit is drawn once due, unless events arrive first.
+                    let event = match frame_due.as_mut() {
+                        Some(due) => match future::select(flux.next(), due).await {
+                            Either::Left((event, _)) => event,
+                            Either::Right(_) => {
+                                frame_due = None;
+                                if let Err(e) =
+                                    self.present_limited(&mut terminals, write_raw, &mut last_frame)
+                                {
+                                    result = Err(e);
+                                    break;
+                                }
+                                continue;
+                            }
+                        },
+                        None => flux.next().await,
+                    };
+                    let Some(event) = event else {
+                        break;
+                    };
+                    self.handle(event, &jobs);
+
+                    // Events that are already available are dispatched before drawing once.
+                    let mut batch = 1;
+                    while batch < self.batch_size && !self.exit {
+                        let Some(Some(event)) = flux.next().now_or_never() else {
+                            break;
+                        };
+                        self.handle(event, &jobs);
+                        batch += 1;
+                    }
+                    if let Err(e) = self.resume_suspended(&mut guard, &mut terminals) {
+                        result = Err(e.into());
+                        break;
+                    }
+
+                    // Animation frames are scheduled lazily, so there are no wakeups while idle.
+                    if take(&mut self.animation_requested) && !self.animation_scheduled {
+                        self.animation_scheduled = true;
+                        let info = self.next_animation_frame();
+                        let frame = runtime.sleep(self.animation_interval);
+                        flux.push(Box::pin(
+                            stream::once(frame)
+                                .map(move |_| Resume::Event(Event::AnimationFrame(info()))),
+                        ));
+                    }
+
+                    if self.exit {
+                        break;
+                    }
+                    if frame_due.is_some() {
+                        continue;
+                    }
+
+                    let delay = self.frame_delay(last_frame);
+                    if !delay.is_zero() {
+                        frame_due = Some(runtime.sleep(delay));
+                        continue;
+                    }
+                    if let Err(e) = self.present_limited(&mut terminals, write_raw, &mut last_frame)
+                    {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }))
+            .await;
+        self.stop();
+        result?;
+
+        self.clear_progress();
+        self.set_pointer_shape(PointerShape::Default);
+        self.write_escapes(&mut terminals, write_raw);
+        drop(guard);
+        #[cfg(not(feature = "tracing"))]
+        self.report_dangling();
+
+        match self.failure.take() {
+            Some(error) => Err(error),
+            None => Ok(self.state),
+        }
+    }
+
+    /// Same as [`Self::run`] but without an async runtime: terminal events are read with crossterm's blocking
+    /// `poll`/`read` and jobs are executed on a thread pool.
+    /// Streams added with [`Self::with_stream`] are polled without blocking between terminal events,
+    /// [`Self::with_event_stream`] must not be used as this function reads terminal events itself.
+    #[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+    #[doc(cfg(feature = "blocking"))]
+    pub fn run_blocking<B: Backend>(mut self, backend: B) -> Result<S, Error> {
+        use crate::quirks::{crossterm_event, WindowsQuirks};
+        use crossterm::event::{poll, read};
+
+        /// How often job callbacks and streams are checked while waiting for terminal events.
+        const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+        if self.check_tty()? {
+            return self.run_blocking(headless_backend());
+        }
+        self.detect_caps();
+        let mut guard =
+            TerminalGuard::new(self.setup, self.terminal_options, self.caps.kitty_keyboard)?;
+        let input = take(&mut self.terminal_input);
+        self.streams.extend(input);
+
+        let (sender, mut rx) = mpsc::unbounded();
+        let jobs = Jobs::new(sender, Arc::new(ThreadRuntime::new()?));
+
+        let mut flux = select_all(take(&mut self.streams));
+        let mut terminals = [Terminal::new(backend)?];
+
+        self.jobs = Some(jobs.clone());
+        self.size = terminals[0].size()?;
+        self.start();
+
+        // Tick once at the start to draw initial ui.
+        self.handle(Resume::Event(Event::Tick), &jobs);
+        self.present(&mut terminals, None)?;
+
+        let mut quirks = WindowsQuirks::default();
+        let mut last_tick = Instant::now();
+        let mut last_frame = None;
+        let mut next_animation = None;
+        let mut diff_wakeup = None;
+        while !self.exit {
+            // Frame diff highlights are cleared even if nothing else is drawn.
+            if let Some(at) = self
+                .frame_diff
+                .as_mut()
+                .and_then(FrameDiff::schedule_wakeup)
+            {
+                diff_wakeup = Some(at);
+            }
+            if take(&mut self.animation_requested) && next_animation.is_none() {
+                let at = Instant::now() + self.animation_interval;
+                next_animation = Some((at, self.next_animation_frame()));
+            }
+
+            let mut pending = Vec::new();
+            while let Ok(Some(callback)) = rx.try_next() {
+                pending.push(callback);
+            }
+            while pending.len() < self.batch_size {
+                let Some(Some(event)) = flux.next().now_or_never() else {
+                    break;
+                };
+                pending.push(event);
+            }
+
+            let wait = if self.timeout.is_zero() {
+                POLL_INTERVAL
+            } else {
+                POLL_INTERVAL.min(self.timeout.saturating_sub(last_tick.elapsed()))
+            };
+            let delay = self.frame_delay(last_frame);
+            let wait = if delay.is_zero() {
+                wait
+            } else {
+                wait.min(delay)
+            };
+            let wait = match &next_animation {
+                Some((at, _)) => wait.min(at.saturating_duration_since(Instant::now())),
+                None => wait,
+            };
+            let wait = match diff_wakeup {
+                Some(at) => wait.min(at.saturating_duration_since(Instant::now())),
+                None => wait,
+            };
+            if pending.is_empty() {
+                if self.setup == Setup::None {
+                    std::thread::sleep(wait);
+                } else if poll(wait)? {
+                    if let Some(event) = crossterm_event(&mut quirks, read()?) {
+                        pending.push(Resume::Event(Event::Terminal(event)));
+                    }
+                }
+            }
+            if !self.timeout.is_zero() && last_tick.elapsed() >= self.timeout {
+                pending.push(Resume::Event(Event::Tick));
+                last_tick = Instant::now();
+            }
+            if let Some((_, info)) = next_animation.take_if(|(at, _)| Instant::now() >= *at) {
+                pending.push(Resume::Event(Event::AnimationFrame(info())));
+            }
+            if diff_wakeup.take_if(|at| Instant::now() >= *at).is_some() {
+                pending.push(Resume::Request(Request::Redraw));
+            }
+
+            for event in pending {
+                self.handle(event, &jobs);
+
+                if self.exit {
+                    break;
+                }
+            }
+            self.resume_suspended(&mut guard, &mut terminals)?;
+
+            if !self.exit && self.frame_delay(last_frame).is_zero() {
+                self.present_limited(&mut terminals, None, &mut last_frame)?;
+            }
+        }
+        self.stop();
+
+        self.clear_progress();
+        self.set_pointer_shape(PointerShape::Default);
+        self.write_escapes(&mut terminals, None);
+        drop(guard);
+        #[cfg(not(feature = "tracing"))]
+        self.report_dangling();
+
+        match self.failure.take() {
+            Some(error) => Err(error),
+            None => Ok(self.state),
+        }
+    }
+
+    /// Runs job callback or dispatches event to all components, then runs context callbacks.
+    fn handle(&mut self, resume: Resume<S, E>, jobs: &Jobs<S, E>) {
+        let mut event = match resume {
+            Resume::Event(e) => {
+                assert!(
+                    !matches!(e, Event::None),
+                    "`None` event is not allowed to be emitted"
+                );
+                e
+            }
+            Resume::JobCallback(callback) => {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::debug_span!("job_callback").entered();
+                let outer = std::mem::replace(&mut self.in_callback, true);
+                callback(self);
+                self.in_callback = outer;
+
+                Event::None
+            }
+            Resume::Request(request) => {
+                self.request(request);
+                Event::None
+            }
+        };
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("event", kind = event.kind()).entered();
+        // Size is cached to avoid querying the terminal for every event.
+        if let Event::Terminal(TerminalEvent::Resize(width, height)) = event {
+            self.size = Rect::new(0, 0, width, height);
+            self.redraw = true;
+        }
+        // Ticks redraw, so components can show time passing.
+        if matches!(event, Event::Tick | Event::AnimationFrame(_)) {
+            self.redraw = true;
+        }
+        // Components animating request the next frame while handling this one.
+        if let Event::AnimationFrame(info) = event {
+            self.animation_scheduled = false;
+            self.animation_requested = false;
+            self.step_animations(info.elapsed);
+        }
+
+        // Windows consoles report releases without opting in.
+        if let Event::Terminal(TerminalEvent::Key(key)) = &event {
+            let report = KeyboardEnhancementFlags::REPORT_EVENT_TYPES;
+            if key.kind == KeyEventKind::Release && !self.terminal_options.keyboard.contains(report)
+            {
+                return;
+            }
+        }
+
+        #[cfg(feature = "debug")]
+        if let Some(debug) = &mut self.debug {
+            debug.record(&event);
+        }
+
+        let mut hooked = false;
+        if !(self.plugins.is_empty() && self.event_hooks.is_empty()) && !event.is_consumed() {
+            let mut cx = context!(self, jobs);
+            for plugin in &mut self.plugins {
+                plugin.on_event(&mut event, &mut cx);
+                if event.is_consumed() {
+                    break;
+                }
+            }
+            for hook in &mut self.event_hooks {
+                if event.is_consumed() {
+                    break;
+                }
+                hook(&mut event, &mut cx);
+            }
+            let output = cx.into_output();
+            hooked = output.handled;
+            self.apply(output);
+        }
+        let is_mouse = matches!(event, Event::Terminal(TerminalEvent::Mouse(_)));
+
+        // Mouse events are only delivered to components under the cursor.
+        let hits = match &event {
+            Event::Terminal(TerminalEvent::Mouse(mouse)) => Some(
+                self.laid_out(self.size)
+                    .map(|(_, _, area)| contains(area, mouse.column, mouse.row))
+                    .collect::<Vec<_>>(),
+            ),
+            _ => None,
+        };
+
+        // Terminal events don't reach layers below the topmost modal layer with components.
+        let floor = self
+            .modal_floor()
+            .filter(|_| matches!(event, Event::Terminal(_)));
+
+        // Pass event to all components.
+        let mut cx = context!(self, jobs);
+        cx.handled = hooked;
+        let mut errors = Vec::new();
+
+        // Keyboard input goes to the focused component first.
+        let focused = self.focused.filter(|_| {
+            matches!(
+                event,
+                Event::Terminal(TerminalEvent::Key(_) | TerminalEvent::Paste(_))
+            )
+        });
+        // Bound keys are turned into actions, keys of unfinished chords don't reach components.
+        if let Event::Terminal(TerminalEvent::Key(key)) = &event {
+            match self.keymap.feed(key) {
+                KeyMatch::Action(action) => {
+                    let order = action_order(&self.layers, &self.hidden, focused, floor);
+                    let handler = order.into_iter().find(|(layer_id, id)| {
+                        self.layers
+                            .get_mut(*layer_id, *id)
+                            .is_some_and(|c| c.handle_action(&action, &mut cx))
+                    });
+                    if let Some((_, _id)) = handler {
+                        #[cfg(feature = "debug")]
+                        if let Some(debug) = &mut self.debug {
+                            debug.reached(_id, true);
+                        }
+                        _ = event.consume();
+                    }
+                }
+                KeyMatch::Pending => _ = event.consume(),
+                KeyMatch::None => {}
+            }
+        }
+
+        if let Some(id) = focused.filter(|_| !event.is_consumed()) {
+            let layer_id = self.layers.find(id, |layer_id| {
+                !floor.is_some_and(|floor| layer_id < floor) && !self.hidden.contains(&layer_id)
+            });
+            let component = layer_id.and_then(|l| Some((l, self.layers.get_mut(l, id)?)));
+            if let Some((layer_id, component)) = component {
+                let faulted = self.faulted.get_mut();
+                if let Some(error) = dispatch(
+                    self.isolation,
+                    layer_id,
+                    &mut **component,
+                    &mut event,
+                    &mut cx,
+                    faulted,
+                    self.metrics.as_ref(),
+                ) {
+                    errors.push(error);
+                }
+                #[cfg(feature = "debug")]
+                if let Some(debug) = &mut self.debug {
+                    debug.reached(component.id(), event.is_consumed());
+                }
+            }
+        }
+
+        // Iterate from top to bottom, break if event is consumed.
+        let mut end = hits.as_ref().map_or(0, Vec::len);
+        let terminal = matches!(event, Event::Terminal(_));
+        'outer: for (layer_id, layer) in self.layers.iter_mut().rev() {
+            if event.is_consumed() || floor.is_some_and(|floor| layer_id < floor) {
+                break;
+            }
+            if terminal && self.hidden.contains(&layer_id) {
+                continue;
+            }
+
+            let start = end - hits.as_ref().map_or(0, |_| layer.len());
+            for (i, component) in layer.iter_mut().enumerate() {
+                if focused.is_some_and(|id| id == component.id()) {
+                    continue;
+                }
+                if hits.as_ref().is_some_and(|hits| !hits[start + i]) {
+                    continue;
+                }
+                let faulted = self.faulted.get_mut();
+                if let Some(error) = dispatch(
+                    self.isolation,
+                    layer_id,
+                    &mut **component,
+                    &mut event,
+                    &mut cx,
+                    faulted,
+                    self.metrics.as_ref(),
+                ) {
+                    errors.push(error);
+                }
+                #[cfg(feature = "debug")]
+                if let Some(debug) = &mut self.debug {
+                    debug.reached(component.id(), event.is_consumed());
+                }
+
+                if matches!(event, Event::None) {
+                    break 'outer;
+                }
+            }
+            end = start;
+        }
+
+        let exiting = matches!(event, Event::Exit);
+        let mut output = cx.into_output();
+        self.handled = event.is_consumed() || output.handled;
+        self.redraw |= self.handled;
+        // Every mouse event is a hit test, pointer resets unless the component under it asks again.
+        if is_mouse {
+            output.pointer.get_or_insert(PointerShape::Default);
+        }
+        // Handled keys don't trigger built-in shortcuts.
+        if let (Event::Terminal(TerminalEvent::Key(key)), false) = (&event, output.handled) {
+            if self.tab_focus && key.kind != KeyEventKind::Release {
+                match key.code {
+                    KeyCode::Tab if !key.modifiers.contains(KeyModifiers::SHIFT) => {
+                        self.focus_next();
+                    }
+                    KeyCode::Tab | KeyCode::BackTab => _ = self.focus_prev(),
+                    _ => {}
+                }
+            }
+            if key.kind != KeyEventKind::Release {
+                let key = Some(normalize(*key));
+                if self.help_key == key {
+                    self.show_help();
+                } else if self.palette_key == key {
+                    self.show_command_palette();
+                } else if self.back_key == key {
+                    self.pop_screen();
+                }
+                #[cfg(feature = "debug")]
+                if self
+                    .debug
+                    .as_ref()
+                    .is_some_and(|d| d.key.is_some() && d.key == key)
+                {
+                    self.toggle_debug_overlay();
+                }
+            }
+        }
+        self.apply(output);
+        errors.into_iter().for_each(|e| self.component_failed(e));
+        self.remove_faulted();
+
+        if exiting {
+            self.exit = true;
+            // Requests made while handling the exit are dropped.
+            self.exit_requested = false;
+        } else if take(&mut self.exit_requested) && !self.exit {
+            let handled = self.handled;
+            self.handle(Resume::Event(Event::Exit), jobs);
+            self.handled = handled;
+        }
+    }
+
+    /// Returns `true` if the top screen or a covered one has `id`.
+    fn has_screen(&self, id: Id) -> bool {
+        self.layers.contains(LayerId::MIDDLE, id)
+            || self.covered_screens.iter().any(|s| s.id() == id)
+    }
+
+    fn push_screen_boxed(&mut self, component: Box<dyn Component<S, E>>) -> bool {
+        let id = component.id();
+        if self.has_screen(id) {
+            return false;
+        }
+        if let Some(top) = self.screen.take() {
+            self.screen_hook(top, false);
+            if let Some(top) = self.layers.remove(LayerId::MIDDLE, top) {
+                self.covered_screens.push(top);
+            }
+        }
+        self.show_screen(component);
+        true
+    }
+
+    fn replace_screen_boxed(&mut self, component: Box<dyn Component<S, E>>) -> bool {
+        let id = component.id();
+        if self.has_screen(id) && self.screen != Some(id) {
+            return false;
+        }
+        if let Some(top) = self.screen.take() {
+            self.screen_hook(top, false);
+            self.detach(Some(LayerId::MIDDLE), top);
+        }
+        self.show_screen(component);
+        true
+    }
+
+    /// Inserts `component` as the top screen and mounts it.
+    fn show_screen(&mut self, component: Box<dyn Component<S, E>>) {
+        let id = component.id();
+        let index = self.layers.len(LayerId::MIDDLE);
+        self.layers.insert(LayerId::MIDDLE, index, component);
+        self.screen = Some(id);
+        self.mount(LayerId::MIDDLE, id);
+        self.screen_hook(id, true);
+    }
+
+    /// Calls [`Component::on_enter`] or [`Component::on_leave`] of the top screen with `id`.
+    /// Skipped until the compositor starts running, the top screen enters then.
+    fn screen_hook(&mut self, id: Id, enter: bool) {
+        let Some(jobs) = self.jobs.as_ref() else {
+            return;
+        };
+        let Some(component) = self.layers.get_mut(LayerId::MIDDLE, id) else {
+            return;
+        };
+        let mut cx = context!(self, jobs);
+        if enter {
+            component.on_enter(&mut cx);
+        } else {
+            component.on_leave(&mut cx);
+        }
+        let output = cx.into_output();
+        self.apply(output);
+    }
+
+    /// Returns the topmost visible modal layer with components.
+    fn modal_floor(&self) -> Option<LayerId> {
+        self.layers
+            .iter()
+            .rev()
+            .find(|(id, l)| !l.is_empty() && self.modal.contains(id) && !self.hidden.contains(id))
+            .map(|(id, _)| id)
+    }
+
+    fn add_command(&mut self, owner: Option<Id>, command: Command) {
+        self.commands.retain(|(_, c)| c.action != command.action);
+        self.commands.push((owner, command));
+    }
+
+    /// Returns keys bound to `actions` with their descriptions, skipping unbound actions.
+    fn help_bindings<'a>(
+        &self,
+        actions: impl Iterator<Item = &'a Action>,
+    ) -> Vec<(String, String)> {
+        let mut bindings = Vec::new();
+        let mut seen = BTreeSet::new();
+        for action in actions {
+            let keys = self
+                .keymap
+                .keys_for(action)
+                .map(|keys| {
+                    keys.iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>();
+            if keys.is_empty() || !seen.insert(action) {
+                continue;
+            }
+            let description = self.keymap.description(action).unwrap_or(action.name());
+            bindings.push((keys.join(", "), description.to_owned()));
+        }
+        bindings
+    }
+
+    /// Passes component error to the error handler or shows it in a popup if there is none.
+    fn component_failed(&mut self, error: Error) {
+        let Some(handler) = self.error_handler.as_mut() else {
+            let title = self
+                .localizer
+                .text("gland-error-title", "Error")
+                .into_owned();
+            let popup = ErrorPopup::new(title, error.to_string());
+            _ = self.detach(Some(LayerId::OVERLAY), ErrorPopup::popup_id());
+            _ = self.insert_at(LayerId::OVERLAY, popup);
+            return;
+        };
+
+        if handler(&error) == ErrorAction::Exit {
+            self.failure.get_or_insert(error);
+            self.force_exit();
+        }
+    }
+
+    /// Applies requests components made through a context, then runs its callbacks.
+    fn apply(&mut self, output: ContextOutput<S, E>) {
+        self.redraw |= output.redraw;
+        let watches = self.watches.get_mut();
+        for (id, watch) in output.watches {
+            watches.add(id, watch);
+        }
+        for id in output.invalidated {
+            watches.invalidate(id);
+        }
+        self.animation_requested |= output.animate;
+        for (id, animation) in output.animations {
+            self.start_animation(id, animation);
+        }
+        for (id, command) in output.commands {
+            self.add_command(Some(id), command);
+        }
+        for request in output.screens {
+            match request {
+                ScreenRequest::Push(component) => _ = self.push_screen_boxed(component),
+                ScreenRequest::Pop => _ = self.pop_screen(),
+                ScreenRequest::Replace(component) => _ = self.replace_screen_boxed(component),
+            }
+        }
+        if let Some(bar) = self.status_bar.as_mut() {
+            for (id, name, segment) in output.status {
+                match segment {
+                    Some(segment) => bar.set(Some(id), name, segment),
+                    None => bar.clear(Some(id), name),
+                }
+                self.redraw = true;
+            }
+        }
+        match output.focus {
+            Some(FocusRequest::Set(id)) => _ = self.set_focus(id),
+            Some(FocusRequest::Clear) => self.clear_focus(),
+            Some(FocusRequest::Next) => _ = self.focus_next(),
+            Some(FocusRequest::Prev) => _ = self.focus_prev(),
+            None => {}
+        }
+        // Written when drawing, the focused component may override it.
+        if let Some(style) = output.cursor_style.filter(|s| *s != self.cursor_style) {
+            self.cursor_style = style;
+            self.redraw = true;
+        }
+        output
+            .announcements
+            .into_iter()
+            .for_each(|a| self.announce(a));
+        if let Some(shape) = output.pointer {
+            self.set_pointer_shape(shape);
+        }
+        for (layer_id, visible) in output.visibility {
+            self.set_layer_visible(layer_id, visible);
+        }
+        for (id, message) in output.messages {
+            self.deliver(id, message);
+        }
+        output.requests.into_iter().for_each(|r| self.request(r));
+        let outer = std::mem::replace(&mut self.in_callback, true);
+        output.callbacks.into_iter().for_each(|cc| cc(self));
+        self.in_callback = outer;
+    }
+
+    fn request(&mut self, request: Request) {
+        match request {
+            Request::Dialog(dialog) => {
+                let id = <Dialog as Component<S, E>>::id(&dialog);
+                if self.insert_at(LayerId::POPUP, dialog).is_ok() {
+                    _ = self.set_focus(id);
+                }
+            }
+            Request::Screenshot(path) => {
+                let frame = self.export_frame(ExportFormat::from_path(&path));
+                _ = std::fs::write(&path, frame);
+            }
+            Request::Suspend(f) => self.suspend(f),
+            Request::Redraw => self.redraw = true,
+        }
+    }
+
+    fn start_animation(&mut self, id: Id, mut animation: Box<dyn Animation>) {
+        let ty = animation.value_any().type_id();
+        self.animations
+            .retain(|(i, a)| *i != id || a.value_any().type_id() != ty);
+
+        if self.reduced_motion {
+            let step = animation.step(Duration::MAX);
+            self.deliver(id, step);
+        } else {
+            self.animations.push((id, animation));
+            self.animation_requested = true;
+        }
+    }
+
+    /// Moves animations forward and sends their steps, animations started meanwhile are kept.
+    fn step_animations(&mut self, elapsed: Duration) {
+        let steps = self
+            .animations
+            .iter_mut()
+            .map(|(id, animation)| (*id, animation.step(elapsed)))
+            .collect::<Vec<_>>();
+        self.animations.retain(|(_, a)| a.is_running());
+
+        for (_, shown) in self.entering.values_mut() {
+            shown.advance(elapsed);
+        }
+        self.entering.retain(|_, (_, shown)| shown.is_running());
+        for exiting in &mut self.exiting {
+            exiting.shown.advance(elapsed);
+        }
+        self.exiting.retain(|e| e.shown.is_running());
+
+        self.animation_requested |=
+            !self.animations.is_empty() || !self.entering.is_empty() || !self.exiting.is_empty();
+
+        let outer = std::mem::replace(&mut self.in_callback, true);
+        for (id, step) in steps {
+            if !self.deliver(id, step) {
+                self.note_dangling(None, id);
+                // Nothing receives the steps anymore.
+                self.animations.retain(|(i, _)| *i != id);
+            }
+        }
+        self.in_callback = outer;
+    }
+
+    /// Passes message to the topmost component with `id`, returns `false` if there is none.
+    fn deliver(&mut self, id: Id, message: Box<dyn Any>) -> bool {
+        let Some(jobs) = self.jobs.as_ref() else {
+            return false;
+        };
+        let Some(component) = self
+            .layers
+            .find(id, |_| true)
+            .and_then(|layer_id| self.layers.get_mut(layer_id, id))
+        else {
+            return false;
+        };
+
+        let mut cx = context!(self, jobs);
+        component.handle_message(message, &mut cx);
+        let output = cx.into_output();
+        // Messages may change their recipients.
+        self.redraw = true;
+        self.apply(output);
+        true
+    }
+
+    /// Calls [`Component::on_mount`], or defers it until the compositor starts running.
+    fn mount(&mut self, layer_id: LayerId, component_id: Id) {
+        self.redraw = true;
+        let Some(jobs) = self.jobs.as_ref() else {
+            self.pending_mounts.push((layer_id, component_id));
+            return;
+        };
+        let Some(component) = self.layers.get_mut(layer_id, component_id) else {
+            return;
+        };
+        if let Some(transition) = component
+            .enter_transition()
+            .filter(|_| !self.reduced_motion)
+        {
+            let shown = Animated::transition(0., 1., transition.duration, transition.easing);
+            self.entering
+                .insert((layer_id, component_id), (transition, shown));
+            self.animation_requested = true;
+        }
+
+        let mut cx = context!(self, jobs);
+        component.on_mount(&mut cx);
+        let output = cx.into_output();
+        self.apply(output);
+    }
+
+    /// Calls [`Component::on_unmount`] on a removed component if it was mounted and aborts jobs it owns.
+    fn unmount(&mut self, layer_id: LayerId, component: &mut dyn Component<S, E>) {
+        self.redraw = true;
+        let key = (layer_id, component.id());
+        self.entering.remove(&key);
+        if let Some(metrics) = &self.metrics {
+            metrics.remove(layer_id, key.1);
+        }
+        if let Some(i) = self.pending_mounts.iter().position(|p| *p == key) {
+            self.pending_mounts.remove(i);
+            return;
+        }
+        let Some(jobs) = self.jobs.as_ref() else {
+            return;
+        };
+
+        let mut cx = context!(self, jobs);
+        component.on_unmount(&mut cx);
+        let output = cx.into_output();
+        jobs.abort_owned(key.1);
+        self.apply(output);
+        if self.layers.find(key.1, |_| true).is_none() {
+            self.commands.retain(|(owner, _)| *owner != Some(key.1));
+            self.watches.get_mut().remove(key.1);
+            if let Some(bar) = self.status_bar.as_mut() {
+                bar.remove_owned(key.1);
+            }
+        }
+    }
+
+    /// Mounts components inserted before the compositor started running, then starts plugins.
+    fn start(&mut self) {
+        for (layer_id, component_id) in take(&mut self.pending_mounts) {
+            self.mount(layer_id, component_id);
+        }
+        if let Some(screen) = self.screen {
+            self.screen_hook(screen, true);
+        }
+
+        let Some(jobs) = self.jobs.as_ref() else {
+            return;
+        };
+        let mut cx = context!(self, jobs);
+        for plugin in &mut self.plugins {
+            plugin.on_start(&mut cx);
+        }
+        let output = cx.into_output();
+        self.apply(output);
+    }
+
+    /// Passes current statistics to the debug overlay if it is shown.
+    #[cfg(feature = "debug")]
+    fn update_debug_overlay(&mut self) {
+        let overlay_id = DebugOverlay::overlay_id();
+        if !self.layers.contains(LayerId::TOPMOST, overlay_id) {
+            return;
+        }
+        let areas = self
+            .laid_out(self.size)
+            .map(|(layer_id, c, area)| ((layer_id, c.id()), area))
+            .collect::<HashMap<_, _>>();
+        let Some(debug) = self.debug.as_mut() else {
+            return;
+        };
+        let metrics = self.metrics.as_ref();
+        let layers = self
+            .layers
+            .iter()
+            .map(|(layer_id, layer)| LayerInfo {
+                id: layer_id,
+                hidden: self.hidden.contains(&layer_id),
+                modal: self.modal.contains(&layer_id),
+                components: layer
+                    .iter()
+                    .filter(|c| c.id() != overlay_id)
+                    .map(|c| ComponentInfo {
+                        id: c.id(),
+                        title: c.title().map(str::to_owned),
+                        area: areas.get(&(layer_id, c.id())).copied().unwrap_or_default(),
+                        focused: self.focused == Some(c.id()),
+                        metrics: metrics.and_then(|m| m.component(c.id())),
+                    })
+                    .collect(),
+            })
+            .filter(|l| !l.components.is_empty())
+            .collect();
+
+        let jobs = self.jobs.as_ref().map_or(0, Jobs::running);
+        let snapshot = debug.snapshot(layers, jobs);
+        let overlay = self.layers.get_mut(LayerId::TOPMOST, overlay_id);
+        if let Some(overlay) =
+            overlay.and_then(|c| (&mut **c as &mut dyn Any).downcast_mut::<DebugOverlay>())
+        {
+            overlay.set_snapshot(snapshot);
+        }
+    }
+
+    /// Queues `f` to run with the terminal restored, see [`Context::suspend`].
+    pub(crate) fn suspend(&mut self, f: Box<dyn FnOnce()>) {
+        self.suspended.push(f);
+    }
+
+    /// Restores the terminal, runs closures passed to [`Context::suspend`] and sets the terminal up again.
+    fn resume_suspended<B: Backend>(
+        &mut self,
+        guard: &mut TerminalGuard,
+        terminals: &mut [Terminal<B>],
+    ) -> io::Result<()> {
+        if self.suspended.is_empty() {
+            return Ok(());
+        }
+        #[cfg(feature = "event-stream")]
+        if let Some(input) = &self.input {
+            input.suspend();
+        }
+        #[cfg(feature = "termion")]
+        if let Some(input) = &self.termion_input {
+            input.suspend();
+        }
+
+        // Dropping the guard restores the terminal.
+        *guard = TerminalGuard::None;
+        take(&mut self.suspended).into_iter().for_each(|f| f());
+        *guard = TerminalGuard::new(self.setup, self.terminal_options, self.caps.kitty_keyboard)?;
+        self.shown_cursor_style = CursorStyle::Default;
+
+        #[cfg(feature = "event-stream")]
+        if let Some(input) = &self.input {
+            input.resume();
+        }
+        #[cfg(feature = "termion")]
+        if let Some(input) = &self.termion_input {
+            input.resume();
+        }
+        // The program may have drawn anything, so the next frame is drawn in full.
+        for terminal in terminals {
+            terminal.clear()?;
+        }
+        self.redraw = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        for plugin in &mut self.plugins {
+            plugin.on_exit(&self.state);
+        }
+        #[cfg(feature = "persist")]
+        if let Err(error) = self.save_session() {
+            self.failure.get_or_insert(error.into());
+        }
+    }
+
+    fn cycle_focus(&mut self, reverse: bool) -> Option<Id> {
+        let mut focusable = self
+            .layers
+            .iter()
+            .filter(|(id, _)| !self.hidden.contains(id))
+            .flat_map(|(_, l)| l.iter())
+            .filter(|c| c.focusable())
+            .map(|c| c.id())
+            .collect::<Vec<_>>();
+        if reverse {
+            focusable.reverse();
+        }
+
+        let current = self
+            .focused
+            .and_then(|id| focusable.iter().position(|f| *f == id));
+        let next = match current {
+            Some(i) => focusable[(i + 1) % focusable.len()],
+            None => *focusable.first()?,
+        };
+        self.focused = Some(next);
+        self.redraw = true;
+        Some(next)
+    }
+
+    /// Checks that stdout is a terminal if the compositor sets one up.
+    /// Returns `true` if it isn't and the compositor should run headless.
+    fn check_tty(&mut self) -> Result<bool, Error> {
+        if self.setup == Setup::None || io::stdout().is_terminal() {
+            return Ok(false);
+        }
+        if !self.headless_fallback {
+            return Err(Error::NotATty);
+        }
+
+        self.setup = Setup::None;
+        self.terminal_input.clear();
+        Ok(true)
+    }
+
+    /// Writes pointer shape if it changed and the terminal supports it.
+    fn set_pointer_shape(&mut self, shape: PointerShape) {
+        if self.caps.pointer_shape && self.pointer != shape {
+            self.pointer = shape;
+            self.escapes.extend_from_slice(shape.sequence().as_bytes());
+        }
+    }
+
+    /// Draws a frame to all terminals, then writes pending escape sequences.
+    fn draw<B: Backend>(
+        &mut self,
+        terminals: &mut [Terminal<B>],
+        write_raw: Option<RawWriter<B>>,
+    ) -> io::Result<()> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("draw", terminals = terminals.len()).entered();
+        for plugin in &mut self.plugins {
+            plugin.before_render(&self.state);
+        }
+        #[cfg(feature = "debug")]
+        self.update_debug_overlay();
+        let cursor_style = self
+            .focused_component(self.size)
+            .filter(|(c, area)| c.cursor(*area, &self.state).is_some())
+            .and_then(|(c, _)| c.cursor_style(&self.state))
+            .unwrap_or(self.cursor_style);
+        if cursor_style != self.shown_cursor_style {
+            self.shown_cursor_style = cursor_style;
+            self.escapes
+                .extend_from_slice(cursor_style.sequence().as_bytes());
+        }
+        let synchronized = self.caps.synchronized_output;
+        if synchronized {
+            self.setup.synchronized_update(true);
+        }
+
+        // Terminals clear their buffer after drawing, it is kept for hooks.
+        let mut frame = None;
+        let result = terminals
+            .iter_mut()
+            .enumerate()
+            .try_for_each(|(i, terminal)| {
+                terminal
+                    .draw(|f| {
+                        // Not every input stream reports resizes, the terminal is resized before drawing.
+                        if i == 0 {
+                            self.size = f.size();
+                        }
+                        #[cfg(feature = "debug")]
+                        let start = Instant::now();
+                        self.compose(f.buffer_mut());
+                        #[cfg(feature = "debug")]
+                        if let Some(debug) = self.debug.as_ref().filter(|_| i == 0) {
+                            debug.frame_time.set(start.elapsed());
+                        }
+
+                        // Diff is only tracked for the first terminal, sizes of others may differ.
+                        if let Some(diff) = self.frame_diff.as_mut().filter(|_| i == 0) {
+                            diff.apply(f.buffer_mut());
+                        }
+                        if i == 0 && !(self.plugins.is_empty() && self.after_render.is_empty()) {
+                            frame = Some(f.buffer_mut().clone());
+                        }
+                        // Cursor is hidden after drawing unless set.
+                        if let Some((x, y)) = self.cursor(f.size()) {
+                            f.set_cursor(x, y);
+                        }
+                    })
+                    .map(drop)
+            });
+
+        if synchronized {
+            self.setup.synchronized_update(false);
+        }
+        result?;
+
+        // Panic messages are printed over the ui, it is redrawn without the faulted components.
+        if self.remove_faulted() {
+            terminals.iter_mut().try_for_each(Terminal::clear)?;
+            return self.draw(terminals, write_raw);
+        }
+
+        if let Some(frame) = frame {
+            for plugin in &mut self.plugins {
+                plugin.after_render(&frame, &self.state);
+            }
+            for hook in &mut self.after_render {
+                hook(&frame, &self.state);
+            }
+        }
+        self.write_escapes(terminals, write_raw);
+        Ok(())
+    }
+
+    /// Draws a frame, letting the error handler decide what to do if it fails.
+    fn present<B: Backend>(
+        &mut self,
+        terminals: &mut [Terminal<B>],
+        write_raw: Option<RawWriter<B>>,
+    ) -> Result<(), Error> {
+        // Escape sequences are written even if nothing has to be drawn.
+        if !take(&mut self.redraw) {
+            self.write_escapes(terminals, write_raw);
+            return Ok(());
+        }
+
+        loop {
+            let Err(error) = self.draw(terminals, write_raw) else {
+                return Ok(());
+            };
+            let error = Error::from(error);
+            match self
+                .error_handler
+                .as_mut()
+                .map_or(ErrorAction::Exit, |h| h(&error))
+            {
+                ErrorAction::Continue => return Ok(()),
+                ErrorAction::Retry => continue,
+                ErrorAction::Exit => return Err(error),
+            }
+        }
+    }
+
+    /// Numbers the next animation frame, returns a function that times it once it is due.
+    fn next_animation_frame(&mut self) -> impl Fn() -> FrameInfo + Copy + Send + 'static {
+        self.animation_frames += 1;
+        let frame = self.animation_frames;
+        let requested = Instant::now();
+        move || FrameInfo {
+            frame,
+            elapsed: requested.elapsed(),
+        }
+    }
+
+    /// Returns how long drawing the next frame has to wait to not exceed the maximum frame rate.
+    fn frame_delay(&self, last_frame: Option<Instant>) -> Duration {
+        match (self.frame_interval, last_frame) {
+            (Some(interval), Some(last)) if self.redraw => interval.saturating_sub(last.elapsed()),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Same as [`Self::present`] but remembers when the frame was drawn if the frame rate is limited.
+    fn present_limited<B: Backend>(
+        &mut self,
+        terminals: &mut [Terminal<B>],
+        write_raw: Option<RawWriter<B>>,
+        last_frame: &mut Option<Instant>,
+    ) -> Result<(), Error> {
+        if self.redraw && self.frame_interval.is_some() {
+            *last_frame = Some(Instant::now());
+        }
+        self.present(terminals, write_raw)
+    }
+
+    /// Returns components from the bottom layer up with their areas on a `screen`.
+    fn laid_out(
+        &self,
+        screen: Rect,
+    ) -> impl Iterator<Item = (LayerId, &dyn Component<S, E>, Rect)> + '_ {
+        let screen = self.layers_area(screen);
+        let visible = self
+            .layers
+            .iter()
+            .filter(|(id, _)| !self.hidden.contains(id));
+        visible.flat_map(move |(layer_id, layer)| {
+            let chunks = self.layouts.get(&layer_id).map(|l| l.split(screen));
+            layer.iter().enumerate().map(move |(i, c)| {
+                let area = chunks
+                    .as_ref()
+                    .and_then(|chunks| chunks.get(i).copied())
+                    .unwrap_or(screen);
+                (layer_id, &**c, c.layout(area).intersection(screen))
+            })
+        })
+    }
+
+    /// Returns the part of `screen` layers are laid out in, above the status bar if it is enabled.
+    fn layers_area(&self, screen: Rect) -> Rect {
+        match &self.status_bar {
+            Some(_) => Rect {
+                height: screen.height.saturating_sub(1),
+                ..screen
+            },
+            None => screen,
+        }
+    }
+
+    /// Draws the status bar in the bottom row of `buf` with the built-in segments it shows.
+    fn view_status_bar(&self, buf: &mut Buffer) {
+        let Some(bar) = &self.status_bar else {
+            return;
+        };
+        let screen = buf.area;
+        if screen.height == 0 {
+            return;
+        }
+
+        let mut extra = Vec::new();
+        if bar.key_hints() {
+            let bindings = self
+                .focused_component(screen)
+                .map(|(c, _)| self.help_bindings(c.actions().iter()))
+                .unwrap_or_default();
+            if !bindings.is_empty() {
+                let hints = bindings
+                    .into_iter()
+                    .map(|(keys, description)| format!("{keys} {description}"))
+                    .collect::<Vec<_>>();
+                extra.push(StatusSegment::center(hints.join("  ")).with_priority(i32::MAX));
+            }
+        }
+        let running = self.jobs.as_ref().map_or(0, Jobs::running);
+        if bar.job_progress() && running > 0 {
+            let text = self
+                .localizer
+                .translate("gland-status-jobs", &[("count", running.into())])
+                .unwrap_or_else(|| format!("{running} running"));
+            extra.push(StatusSegment::right(text).with_priority(i32::MIN));
+        }
+
+        let row = Rect {
+            y: screen.bottom() - 1,
+            height: 1,
+            ..screen
+        };
+        bar.render(row, buf, &extra);
+    }
+
+    /// Returns the focused component with its area if it is shown.
+    fn focused_component(&self, screen: Rect) -> Option<(&dyn Component<S, E>, Rect)> {
+        let focused = self.focused?;
+        self.laid_out(screen)
+            .filter(|(_, c, _)| c.id() == focused)
+            .last()
+            .map(|(_, c, area)| (c, area))
+    }
+
+    /// Returns position of the cursor requested by the focused component, if it is inside `screen`.
+    fn cursor(&self, screen: Rect) -> Option<(u16, u16)> {
+        let (c, area) = self.focused_component(screen)?;
+        let (x, y) = c.cursor(area, &self.state)?;
+        let inside = (screen.left()..screen.right()).contains(&x)
+            && (screen.top()..screen.bottom()).contains(&y);
+        inside.then_some((x, y))
+    }
+
+    /// Draws components from the bottom layer up in their areas.
+    fn compose(&self, buf: &mut Buffer) {
+        let mut views = self.views.borrow_mut();
+        let mut watches = self.watches.borrow_mut();
+        let mut mounted = Vec::with_capacity(views.len());
+
+        // Exiting components are drawn on top of what is left of their layers.
+        let mut exiting = self
+            .exiting
+            .iter()
+            .filter(|e| !self.hidden.contains(&e.layer_id))
+            .collect::<Vec<_>>();
+        exiting.sort_by_key(|e| e.layer_id);
+        let mut exiting = exiting.into_iter().peekable();
+
+        let mut last_layer = None;
+        for (layer_id, c, area) in self.laid_out(buf.area) {
+            while let Some(e) = exiting.next_if(|e| e.layer_id < layer_id) {
+                self.view_transition(
+                    e.layer_id,
+                    &*e.component,
+                    e.area,
+                    &e.transition,
+                    &e.shown,
+                    buf,
+                );
+            }
+            if last_layer.replace(layer_id) != Some(layer_id) {
+                if let Some(style) = self.backdrops.get(&layer_id) {
+                    buf.set_style(buf.area, *style);
+                }
+            }
+
+            let key = (layer_id, c.id());
+            if let Some((transition, shown)) = self.entering.get(&key) {
+                views.remove(&key);
+                self.view_transition(layer_id, c, area, transition, shown, buf);
+                continue;
+            }
+            let update = c.should_update(&self.state) && watches.changed(c.id(), &self.state);
+
+            match views.get_mut(&key) {
+                None if update => self.view(layer_id, c, area, buf),
+                Some(view) if !update && view.area == area => blit(view, buf),
+                view => {
+                    // Unwritten cells keep the marker, so only the component's own output is copied.
+                    let view = match view {
+                        Some(view) => view,
+                        None => views.entry(key).or_default(),
+                    };
+                    *view = Buffer::filled(area, &unwritten());
+                    self.view(layer_id, c, area, view);
+                    blit(view, buf);
+                }
+            }
+            mounted.push(key);
+        }
+
+        for e in exiting {
+            self.view_transition(
+                e.layer_id,
+                &*e.component,
+                e.area,
+                &e.transition,
+                &e.shown,
+                buf,
+            );
+        }
+
+        if !views.is_empty() {
+            views.retain(|key, _| mounted.contains(key));
+        }
+        self.view_status_bar(buf);
+    }
+
+    /// Draws component into a separate buffer and copies it to `buf` with the transition applied.
+    fn view_transition(
+        &self,
+        layer_id: LayerId,
+        c: &dyn Component<S, E>,
+        area: Rect,
+        transition: &Transition,
+        shown: &Animated<f32>,
+        buf: &mut Buffer,
+    ) {
+        let mut view = Buffer::filled(area, &unwritten());
+        self.view(layer_id, c, area, &mut view);
+        transition.blit(shown.value(), &view, buf);
+    }
+
+    /// Draws component, catching panics if isolation is enabled.
+    fn view(&self, layer_id: LayerId, c: &dyn Component<S, E>, area: Rect, buf: &mut Buffer) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("view", id = c.id().0.get(), layer = layer_id.0).entered();
+        if let Some(metrics) = &self.metrics {
+            let start = Instant::now();
+            self.view_isolated(layer_id, c, area, buf);
+            metrics.record_view(layer_id, c.id(), start.elapsed());
+            return;
+        }
+        self.view_isolated(layer_id, c, area, buf);
+    }
+
+    /// Draws component, catching panics if isolation is enabled.
+    fn view_isolated(
+        &self,
+        layer_id: LayerId,
+        c: &dyn Component<S, E>,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        if !self.isolation {
+            return c.view(area, buf, &self.state);
+        }
+        if let Err(payload) =
+            panic::catch_unwind(AssertUnwindSafe(|| c.view(area, buf, &self.state)))
+        {
+            self.faulted
+                .borrow_mut()
+                .push((layer_id, c.id(), panic_message(&*payload)));
+        }
+    }
+
+    /// Removes components that have panicked and reports them, returns `true` if there were any.
+    fn remove_faulted(&mut self) -> bool {
+        let faulted = take(self.faulted.get_mut());
+        let any = !faulted.is_empty();
+        for (layer_id, id, message) in faulted {
+            _ = self.detach(Some(layer_id), id);
+            // Exiting components are dropped right away, including the one just removed.
+            self.exiting
+                .retain(|e| e.layer_id != layer_id || e.component.id() != id);
+            self.component_failed(Error::Panicked { id, message });
+        }
+        any
+    }
+
+    /// Writes pending escape sequences with `write_raw` or to the local terminal if it is `None`.
+    fn write_escapes<B: Backend>(
+        &mut self,
+        terminals: &mut [Terminal<B>],
+        write_raw: Option<RawWriter<B>>,
+    ) {
+        if !self.escapes.is_empty() {
+            // Escape sequences are best effort, the frame has been drawn regardless.
+            match write_raw {
+                Some(write_raw) => terminals.iter_mut().for_each(|t| {
+                    _ = write_raw(t.backend_mut(), &self.escapes);
+                }),
+                None => _ = self.setup.write_raw(&self.escapes),
+            }
+            self.escapes.clear();
+        }
+    }
+
+    /// Removes progress indicator that may have been left by [`Context::set_progress`].
+    fn clear_progress(&mut self) {
+        if self.caps.progress {
+            let seq = self.caps.wrap_passthrough(&Progress::None.sequence());
+            self.escapes.extend_from_slice(seq.as_bytes());
+        }
+    }
+
+    /// Detects terminal capabilities unless they were set explicitly.
+    /// Environment of a terminal managed elsewhere is unknown, conservative defaults are kept for it.
+    fn detect_caps(&mut self) {
+        if self.detect_caps && self.setup != Setup::None {
+            self.caps = TerminalCaps::detect();
+        }
+    }
+
+    /// Prints dangling accesses after the terminal has been restored.
+    #[cfg(not(feature = "tracing"))]
+    fn report_dangling(&mut self) {
+        let dangling = self.dangling.get_mut();
+        if !dangling.is_empty() {
+            eprintln!(
+                "gland: {} callback lookup(s) targeted unmounted components:",
+                dangling.len()
+            );
+            dangling
+                .iter()
+                .for_each(|d| eprintln!("  {:?} at {:?}", d.component_id, d.layer_id));
+        }
+    }
+}
+
+impl<S: 'static + Default, E: 'static> Default for Compositor<S, E> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_state(S::default())
+    }
+}
+
+/// Off-screen backend used in headless mode.
+fn headless_backend() -> TestBackend {
+    let var = |name: &str, default| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(default)
+    };
+    TestBackend::new(var("COLUMNS", 80), var("LINES", 24))
+}
+
+fn contains(area: Rect, x: u16, y: u16) -> bool {
+    (area.left()..area.right()).contains(&x) && (area.top()..area.bottom()).contains(&y)
+}
+
+/// Marks cells a component has not written to, cells it has only styled keep the symbol.
+pub(crate) fn unwritten() -> Cell {
+    let mut cell = Cell::default();
+    cell.set_symbol("\0");
+    cell
+}
+
+/// Copies `cell` of a component's view written by it over `target`. Cells the component has only styled
+/// keep the symbol of `target` and patch its style, resetting colors or removing modifiers is lost.
+pub(crate) fn blit_cell(cell: &Cell, target: &mut Cell) {
+    if cell.symbol() != "\0" {
+        *target = cell.clone();
+        return;
+    }
+
+    let mut style = Style::new().add_modifier(cell.modifier);
+    if cell.fg != Color::Reset {
+        style = style.fg(cell.fg);
+    }
+    if cell.bg != Color::Reset {
+        style = style.bg(cell.bg);
+    }
+    target.set_style(style);
+}
+
+/// Copies cells written by a component from its `view` to `buf`.
+fn blit(view: &Buffer, buf: &mut Buffer) {
+    let unwritten = unwritten();
+    let area = view.area.intersection(buf.area);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            let cell = view.get(x, y);
+            if *cell != unwritten {
+                blit_cell(cell, buf.get_mut(x, y));
+            }
+        }
+    }
+}
+
+/// Returns components actions are passed to, the focused one first, then from the top layer down.
+fn action_order<S: 'static, E: 'static>(
+    layers: &Layers<S, E>,
+    hidden: &BTreeSet<LayerId>,
+    focused: Option<Id>,
+    floor: Option<LayerId>,
+) -> Vec<(LayerId, Id)> {
+    let active =
+        |layer_id| !floor.is_some_and(|floor| layer_id < floor) && !hidden.contains(&layer_id);
+    let mut order = focused
+        .and_then(|id| Some((layers.find(id, active)?, id)))
+        .into_iter()
+        .collect::<Vec<_>>();
+    for (layer_id, layer) in layers.iter().rev().filter(|(id, _)| active(*id)) {
+        let ids = layer.iter().map(|c| c.id());
+        order.extend(
+            ids.filter(|id| focused != Some(*id))
+                .map(|id| (layer_id, id)),
+        );
+    }
+    order
+}
+
+/// Calls [`Component::try_handle_event`], catching panics if `isolation` is enabled.
+fn dispatch<S: 'static, E: 'static>(
+    isolation: bool,
+    layer_id: LayerId,
+    component: &mut dyn Component<S, E>,
+    event: &mut Event<E>,
+    cx: &mut Context<S, E>,
+    faulted: &mut Vec<(LayerId, Id, String)>,
+    metrics: Option<&Metrics>,
+) -> Option<Error> {
+    let id = component.id();
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!(
+        "handle_event",
+        id = id.0.get(),
+        layer = layer_id.0,
+        kind = event.kind(),
+        consumed = tracing::field::Empty,
+    )
+    .entered();
+    let start = metrics.map(|_| Instant::now());
+    let result = if isolation {
+        match panic::catch_unwind(AssertUnwindSafe(|| component.try_handle_event(event, cx))) {
+            Ok(result) => result,
+            Err(payload) => {
+                faulted.push((layer_id, id, panic_message(&*payload)));
+                Ok(())
+            }
+        }
+    } else {
+        component.try_handle_event(event, cx)
+    };
+    if let Some((metrics, start)) = metrics.zip(start) {
+        metrics.record_event(layer_id, id, start.elapsed());
+    }
+    #[cfg(feature = "tracing")]
+    _span.record("consumed", event.is_consumed());
+
+    result.err().map(|error| Error::Component { id, error })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "component panicked".to_owned())
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use std::{thread, time::Duration};
+
+    /// Spawns a job incrementing the state on the first event.
+    struct Spawner {
+        spawned: bool,
+    }
+
+    impl Component<usize> for Spawner {
+        fn id(&self) -> Id {
+            Id::new("spawner")
+        }
+
+        fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &usize) {}
+
+        fn handle_event(&mut self, _event: &mut Event, cx: &mut Context<usize>) {
+            if !std::mem::replace(&mut self.spawned, true) {
+                cx.jobs()
+                    .spawn(async { |comp: &mut Compositor<usize>| *comp.state_mut() += 1 });
+            }
+        }
+    }
+
+    /// Highlights the first row without writing symbols, only drawn once.
+    struct Highlight;
+
+    impl Component<usize> for Highlight {
+        fn id(&self) -> Id {
+            Id::new("highlight")
+        }
+
+        fn view(&self, area: Rect, buf: &mut Buffer, _state: &usize) {
+            buf.set_style(Rect { height: 1, ..area }, Style::new().bg(Color::Blue));
+        }
+
+        fn should_update(&self, _state: &usize) -> bool {
+            false
+        }
+    }
+
+    /// Writes the state over the whole area.
+    struct Text;
+
+    impl Component<usize> for Text {
+        fn id(&self) -> Id {
+            Id::new("text")
+        }
+
+        fn view(&self, area: Rect, buf: &mut Buffer, state: &usize) {
+            buf.set_string(area.x, area.y, format!("{state}{state}"), Style::new());
+        }
+    }
+
+    #[test]
+    fn reused_views_keep_styles() {
+        let mut comp = Compositor::with_state(1);
+        _ = comp.insert_at(LayerId::BACKGROUND, Text);
+        _ = comp.insert_at(LayerId::MIDDLE, Highlight);
+        let mut terminal = Terminal::new(TestBackend::new(2, 2)).unwrap();
+
+        comp.render(&mut terminal).unwrap();
+        *comp.state_mut() = 2;
+        comp.render(&mut terminal).unwrap();
+
+        let mut expected = Buffer::with_lines(vec!["22", "  "]);
+        expected.set_style(Rect::new(0, 0, 2, 1), Style::new().bg(Color::Blue));
+        terminal.backend().assert_buffer(&expected);
+    }
+
+    #[test]
+    fn dispatch_spawns_jobs_without_runtime() {
+        let mut comp = Compositor::with_state(0);
+        _ = comp.insert_at(LayerId::MIDDLE, Spawner { spawned: false });
+
+        comp.dispatch(Event::Tick);
+        for _ in 0..100 {
+            if *comp.state() == 1 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+            comp.dispatch(Event::Tick);
+        }
+        assert_eq!(*comp.state(), 1);
+    }
+
+    #[test]
+    fn draws_track_resizes_without_events() {
+        let mut comp = Compositor::with_state(0);
+        _ = comp.insert_at(LayerId::MIDDLE, Text);
+        let mut terminals = [Terminal::new(TestBackend::new(2, 2)).unwrap()];
+        comp.draw(&mut terminals, None).unwrap();
+        assert_eq!(comp.size(), Rect::new(0, 0, 2, 2));
+
+        terminals[0].backend_mut().resize(4, 3);
+        comp.draw(&mut terminals, None).unwrap();
+        assert_eq!(comp.size(), Rect::new(0, 0, 4, 3));
+    }
+
+    /// Starts an animation and removes itself from a context callback on the first event.
+    struct Fader;
+
+    impl Component<usize> for Fader {
+        fn id(&self) -> Id {
+            Id::new("fader")
+        }
+
+        fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &usize) {}
+
+        fn handle_event(&mut self, _event: &mut Event, cx: &mut Context<usize>) {
+            let id = self.id();
+            cx.animate(id, 0.0f32, 1.0, Duration::from_secs(1), Easing::Linear);
+            cx.add_callback(move |comp| {
+                comp.remove_all(id);
+                _ = comp.get::<Fader>(id);
+            });
+        }
+    }
+
+    #[test]
+    fn callbacks_and_animations_report_dangling() {
+        let mut comp = Compositor::with_state(0);
+        _ = comp.insert_at(LayerId::MIDDLE, Fader);
+        comp.dispatch(Event::Tick);
+        let info = FrameInfo {
+            frame: 1,
+            elapsed: Duration::from_millis(16),
+        };
+        comp.dispatch(Event::AnimationFrame(info));
+        comp.dispatch(Event::AnimationFrame(info));
+
+        let dangling = DanglingAccess {
+            layer_id: None,
+            component_id: Id::new("fader"),
+        };
+        // Lookup in the callback and the first undelivered step, the animation is stopped after it.
+        assert_eq!(comp.dangling_accesses(), [dangling; 2]);
     }
 }
@@ -0,0 +1,341 @@
+use crate::{
+    Action, Component, Context, CursorStyle, Event, Id, MouseButton, MouseEventKind, TerminalEvent,
+};
+use ratatui::{buffer::Buffer, layout::Rect};
+use std::{any::Any, cell::Cell};
+
+/// Maps the area of the container to the area of a child.
+type AreaMap = Box<dyn Fn(Rect) -> Rect>;
+
+struct Child<S, E> {
+    component: Box<dyn Component<S, E>>,
+    map: Option<AreaMap>,
+}
+
+impl<S: 'static, E: 'static> Child<S, E> {
+    fn area(&self, area: Rect) -> Rect {
+        let mapped = self.map.as_ref().map_or(area, |map| map(area));
+        self.component.layout(mapped).intersection(area)
+    }
+}
+
+/// Child components of a component, kept as its field and driven from its own [`Component`] functions.
+///
+/// Children are drawn in order, later ones on top, each in the area of the container unless
+/// it is mapped with [`Self::with_child_in`]. Mouse events go to the topmost child under the cursor,
+/// other events go to the focused child first, then to the others from the top until consumed.
+/// Children are mounted and unmounted along with the container.
+///
+/// While the owner is focused, the child clicked last or that requested focus sees itself as focused,
+/// the topmost focusable child by default.
+///
+/// ```
+/// # use gland::{Component, Container, Context, Event, Id};
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// struct Sidebar {
+///     id: Id,
+///     children: Container,
+/// }
+///
+/// impl Component for Sidebar {
+///     fn id(&self) -> Id {
+///         self.id
+///     }
+///
+///     fn on_mount(&mut self, cx: &mut Context) {
+///         self.children.on_mount(cx);
+///     }
+///
+///     fn on_unmount(&mut self, cx: &mut Context) {
+///         self.children.on_unmount(cx);
+///     }
+///
+///     fn view(&self, area: Rect, buf: &mut Buffer, state: &()) {
+///         self.children.view(area, buf, state);
+///     }
+///
+///     fn handle_event(&mut self, event: &mut Event, cx: &mut Context) {
+///         self.children.handle_event(event, cx);
+///     }
+/// }
+/// ```
+pub struct Container<S = (), E = ()> {
+    owner: Id,
+    children: Vec<Child<S, E>>,
+    focused: Option<Id>,
+    mounted: bool,
+    /// Area the container was drawn in last.
+    area: Cell<Rect>,
+}
+
+/// Builder functions
+impl<S, E> Container<S, E> {
+    /// Creates container for the component with `owner` id.
+    pub fn new(owner: Id) -> Self {
+        Self {
+            owner,
+            children: Vec::new(),
+            focused: None,
+            mounted: false,
+            area: Cell::new(Rect::default()),
+        }
+    }
+
+    /// Adds `child` on top of the others.
+    pub fn with_child(mut self, child: impl Component<S, E>) -> Self {
+        self.children.push(Child {
+            component: Box::new(child),
+            map: None,
+        });
+        self
+    }
+
+    /// Adds `child` on top of the others, drawn in the area returned by `map` for the area of the container.
+    pub fn with_child_in(
+        mut self,
+        child: impl Component<S, E>,
+        map: impl Fn(Rect) -> Rect + 'static,
+    ) -> Self {
+        self.children.push(Child {
+            component: Box::new(child),
+            map: Some(Box::new(map)),
+        });
+        self
+    }
+}
+
+/// Non-builder functions
+impl<S: 'static, E: 'static> Container<S, E> {
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn contains(&self, id: Id) -> bool {
+        self.children.iter().any(|c| c.component.id() == id)
+    }
+
+    /// Returns ids of children from the bottom to the top.
+    pub fn ids(&self) -> impl Iterator<Item = Id> + '_ {
+        self.children.iter().map(|c| c.component.id())
+    }
+
+    /// Returns the child with `id` if it is a `C`.
+    pub fn get<C: Component<S, E>>(&self, id: Id) -> Option<&C> {
+        let child = self.children.iter().find(|c| c.component.id() == id)?;
+        (&*child.component as &dyn Any).downcast_ref()
+    }
+
+    /// Returns the child with `id` if it is a `C`.
+    pub fn get_mut<C: Component<S, E>>(&mut self, id: Id) -> Option<&mut C> {
+        let child = self.children.iter_mut().find(|c| c.component.id() == id)?;
+        (&mut *child.component as &mut dyn Any).downcast_mut()
+    }
+
+    /// Returns the area the child with `id` was drawn in last.
+    pub fn area_of(&self, id: Id) -> Option<Rect> {
+        let child = self.children.iter().find(|c| c.component.id() == id)?;
+        Some(child.area(self.area.get()))
+    }
+
+    /// Adds `child` on top of the others, it is mounted if the container is.
+    /// Replaces the child with the same id if there is one.
+    pub fn insert(&mut self, child: impl Component<S, E>, cx: &mut Context<S, E>) {
+        self.insert_boxed(Box::new(child), None, cx);
+    }
+
+    /// Same as [`Self::insert`] but draws the child in the area returned by `map`.
+    pub fn insert_in(
+        &mut self,
+        child: impl Component<S, E>,
+        map: impl Fn(Rect) -> Rect + 'static,
+        cx: &mut Context<S, E>,
+    ) {
+        self.insert_boxed(Box::new(child), Some(Box::new(map)), cx);
+    }
+
+    /// Removes the child with `id`, it is unmounted if the container is mounted.
+    pub fn remove(&mut self, id: Id, cx: &mut Context<S, E>) -> Option<Box<dyn Component<S, E>>> {
+        let index = self.children.iter().position(|c| c.component.id() == id)?;
+        let mut child = self.children.remove(index);
+        if self.mounted {
+            child.component.on_unmount(cx);
+        }
+        if self.focused == Some(id) {
+            self.focused = None;
+        }
+        cx.request_redraw();
+        Some(child.component)
+    }
+
+    /// Returns the id of the child that sees itself as focused while the owner is focused.
+    pub fn focused(&self) -> Option<Id> {
+        self.focused.filter(|&id| self.contains(id)).or_else(|| {
+            let child = self
+                .children
+                .iter()
+                .rev()
+                .find(|c| c.component.focusable())?;
+            Some(child.component.id())
+        })
+    }
+
+    /// Makes the child with `id` see itself as focused while the owner is focused.
+    pub fn set_focused(&mut self, id: Id) -> bool {
+        let found = self.contains(id);
+        if found {
+            self.focused = Some(id);
+        }
+        found
+    }
+
+    pub fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        self.mounted = true;
+        for child in &mut self.children {
+            child.component.on_mount(cx);
+        }
+    }
+
+    pub fn on_unmount(&mut self, cx: &mut Context<S, E>) {
+        self.mounted = false;
+        for child in &mut self.children {
+            child.component.on_unmount(cx);
+        }
+    }
+
+    pub fn on_enter(&mut self, cx: &mut Context<S, E>) {
+        for child in &mut self.children {
+            child.component.on_enter(cx);
+        }
+    }
+
+    pub fn on_leave(&mut self, cx: &mut Context<S, E>) {
+        for child in &mut self.children {
+            child.component.on_leave(cx);
+        }
+    }
+
+    /// Draws children in order within `area`.
+    pub fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        self.area.set(area);
+        for child in &self.children {
+            child.component.view(child.area(area), buf, state);
+        }
+    }
+
+    /// Returns the cursor of the focused child, see [`Component::cursor`].
+    pub fn cursor(&self, state: &S) -> Option<(u16, u16)> {
+        let child = self.focused_child()?;
+        child.component.cursor(child.area(self.area.get()), state)
+    }
+
+    /// Returns the cursor style of the focused child, see [`Component::cursor_style`].
+    pub fn cursor_style(&self, state: &S) -> Option<CursorStyle> {
+        self.focused_child()?.component.cursor_style(state)
+    }
+
+    /// Passes `action` to the focused child first, then to the others from the top until it is handled.
+    pub fn handle_action(&mut self, action: &Action, cx: &mut Context<S, E>) -> bool {
+        let focused = self.focused();
+        let handled = self.order(focused).into_iter().any(|index| {
+            let child = &mut self.children[index].component;
+            cx.with_inner_focus(self.owner, focused, |cx| child.handle_action(action, cx))
+        });
+        self.redirect_focus(cx);
+        handled
+    }
+
+    /// Same as [`Self::try_handle_event`] but ignores errors of children.
+    pub fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        _ = self.try_handle_event(event, cx);
+    }
+
+    /// Passes `event` to children until it is consumed, stopping at the first error.
+    pub fn try_handle_event(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut focused = self.focused();
+        let order = match event {
+            Event::Terminal(TerminalEvent::Mouse(mouse)) => {
+                let area = self.area.get();
+                let point = Rect::new(mouse.column, mouse.row, 1, 1);
+                let Some(index) = self
+                    .children
+                    .iter()
+                    .rposition(|c| c.area(area).intersects(point))
+                else {
+                    return Ok(());
+                };
+                let child = &self.children[index].component;
+                if mouse.kind == MouseEventKind::Down(MouseButton::Left) && child.focusable() {
+                    self.focused = Some(child.id());
+                    focused = self.focused;
+                    cx.focus(self.owner);
+                }
+                vec![index]
+            }
+            _ => self.order(focused),
+        };
+
+        let mut result = Ok(());
+        for index in order {
+            let child = &mut self.children[index].component;
+            result =
+                cx.with_inner_focus(self.owner, focused, |cx| child.try_handle_event(event, cx));
+            if result.is_err() || event.is_consumed() {
+                break;
+            }
+        }
+        self.redirect_focus(cx);
+        result
+    }
+
+    fn insert_boxed(
+        &mut self,
+        mut component: Box<dyn Component<S, E>>,
+        map: Option<AreaMap>,
+        cx: &mut Context<S, E>,
+    ) {
+        self.remove(component.id(), cx);
+        if self.mounted {
+            component.on_mount(cx);
+        }
+        self.children.push(Child { component, map });
+        cx.request_redraw();
+    }
+
+    fn focused_child(&self) -> Option<&Child<S, E>> {
+        let focused = self.focused()?;
+        self.children.iter().find(|c| c.component.id() == focused)
+    }
+
+    /// Returns indices of children with the `focused` one first, then the others from the top.
+    fn order(&self, focused: Option<Id>) -> Vec<usize> {
+        let focused = self
+            .children
+            .iter()
+            .position(|c| Some(c.component.id()) == focused);
+        focused
+            .into_iter()
+            .chain(
+                (0..self.children.len())
+                    .rev()
+                    .filter(|&i| Some(i) != focused),
+            )
+            .collect()
+    }
+
+    /// Remembers a child that requested focus, the owner is focused instead.
+    fn redirect_focus(&mut self, cx: &mut Context<S, E>) {
+        let children = &self.children;
+        let is_child = |id| children.iter().any(|c| c.component.id() == id);
+        if let Some(id) = cx.redirect_focus(self.owner, is_child) {
+            self.focused = Some(id);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{Compositor, Event, ExportFormat, KeyEvent, Resume, TerminalEvent};
+use futures_channel::mpsc;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, ToSocketAddrs},
+    path::PathBuf,
+    sync::mpsc as std_mpsc,
+    thread,
+};
+
+/// Request sent to the control server, one JSON object per line.
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case", bound = "E: DeserializeOwned")]
+enum Request<E> {
+    /// Dispatches [`Event::User`].
+    Event { event: E },
+    /// Dispatches key press parsed like `ctrl-s`.
+    Key { key: String },
+    /// Lists mounted components.
+    Components,
+    /// Returns serialized state.
+    State,
+    /// Exports the current frame, writes it to `path` if set.
+    Screenshot {
+        #[serde(default)]
+        format: Option<String>,
+        #[serde(default)]
+        path: Option<PathBuf>,
+    },
+    /// Exits the compositor.
+    Exit,
+}
+
+/// Control server functions
+impl<S, E> Compositor<S, E>
+where
+    S: Serialize + 'static,
+    E: DeserializeOwned + Send + 'static,
+{
+    /// Starts a control server on a TCP address for end-to-end tests and external automation.
+    ///
+    /// The protocol is line delimited JSON, every request is answered with `{"ok": true, "result": ...}`
+    /// or `{"ok": false, "error": "..."}`. Requests are objects with a `cmd` field:
+    /// - `{"cmd": "event", "event": ...}` dispatches a user event.
+    /// - `{"cmd": "key", "key": "ctrl-s"}` dispatches a key press.
+    /// - `{"cmd": "components"}` lists mounted components as `{"layer": ..., "id": ...}`.
+    /// - `{"cmd": "state"}` returns the state.
+    /// - `{"cmd": "screenshot", "format": "ansi" | "html" | "svg", "path": ...}` exports the frame,
+    ///   returning it or writing to `path`.
+    /// - `{"cmd": "exit"}` exits the compositor.
+    ///
+    /// The server has no authentication, bind it to a local address only.
+    #[doc(cfg(feature = "control"))]
+    pub fn with_control_tcp(self, addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let (tx, rx) = mpsc::unbounded();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (tx, reader) = (tx.clone(), stream.try_clone());
+                thread::spawn(move || serve(BufReader::new(reader?), stream, tx));
+            }
+            io::Result::Ok(())
+        });
+
+        Ok(self.with_resume_stream(rx))
+    }
+
+    /// Same as [`Self::with_control_tcp`] but listens on a unix socket at `path`.
+    #[cfg(unix)]
+    #[doc(cfg(all(feature = "control", unix)))]
+    pub fn with_control_unix(self, path: impl Into<PathBuf>) -> io::Result<Self> {
+        use std::os::unix::net::UnixListener;
+
+        let listener = UnixListener::bind(path.into())?;
+        let (tx, rx) = mpsc::unbounded();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (tx, reader) = (tx.clone(), stream.try_clone());
+                thread::spawn(move || serve(BufReader::new(reader?), stream, tx));
+            }
+            io::Result::Ok(())
+        });
+
+        Ok(self.with_resume_stream(rx))
+    }
+}
+
+/// Answers requests of a single connection until it is closed or the compositor exits.
+fn serve<S, E>(
+    reader: impl BufRead,
+    mut writer: impl Write,
+    tx: mpsc::UnboundedSender<Resume<S, E>>,
+) -> io::Result<()>
+where
+    S: Serialize + 'static,
+    E: DeserializeOwned + Send + 'static,
+{
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request<E>>(&line) {
+            Ok(request) => match execute(request, &tx) {
+                Some(Ok(result)) => json!({ "ok": true, "result": result }),
+                Some(Err(error)) => json!({ "ok": false, "error": error }),
+                // Compositor is gone.
+                None => return Ok(()),
+            },
+            Err(e) => json!({ "ok": false, "error": e.to_string() }),
+        };
+        writeln!(writer, "{response}")?;
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+fn execute<S, E>(
+    request: Request<E>,
+    tx: &mpsc::UnboundedSender<Resume<S, E>>,
+) -> Option<Result<Value, String>>
+where
+    S: Serialize + 'static,
+    E: DeserializeOwned + Send + 'static,
+{
+    let event = |event| {
+        tx.unbounded_send(Resume::Event(event))
+            .ok()
+            .map(|_| Ok(Value::Null))
+    };
+
+    match request {
+        Request::Event { event: e } => event(Event::User(e)),
+        Request::Key { key } => match key.parse::<KeyEvent>() {
+            Ok(key) => event(Event::Terminal(TerminalEvent::Key(key))),
+            Err(e) => Some(Err(e.to_string())),
+        },
+        Request::Components => query(tx, |comp| {
+            let components = comp
+                .component_ids()
+                .map(|(layer, id)| json!({ "layer": layer.0, "id": id.0.get() }))
+                .collect();
+            Ok(Value::Array(components))
+        }),
+        Request::State => query(tx, |comp| {
+            serde_json::to_value(comp.state()).map_err(|e| e.to_string())
+        }),
+        Request::Screenshot { format, path } => {
+            let format = match (format.as_deref(), &path) {
+                (Some("ansi"), _) => ExportFormat::Ansi,
+                (Some("html"), _) => ExportFormat::Html,
+                (Some("svg"), _) => ExportFormat::Svg,
+                (Some("snapshot"), _) => ExportFormat::Snapshot,
+                (Some(other), _) => return Some(Err(format!("unknown format: {other}"))),
+                (None, Some(path)) => ExportFormat::from_path(path),
+                (None, None) => ExportFormat::Ansi,
+            };
+            query(tx, move |comp| {
+                let frame = comp.export_frame(format);
+                match path {
+                    Some(path) => std::fs::write(&path, frame)
+                        .map(|_| json!(path))
+                        .map_err(|e| e.to_string()),
+                    None => Ok(Value::String(frame)),
+                }
+            })
+        }
+        Request::Exit => query(tx, |comp| {
+            comp.exit();
+            Ok(Value::Null)
+        }),
+    }
+}
+
+/// Runs `func` on the compositor and waits for its result, `None` if the compositor has exited.
+fn query<S: 'static, E: 'static>(
+    tx: &mpsc::UnboundedSender<Resume<S, E>>,
+    func: impl FnOnce(&mut Compositor<S, E>) -> Result<Value, String> + Send + 'static,
+) -> Option<Result<Value, String>> {
+    let (reply_tx, reply_rx) = std_mpsc::channel();
+    tx.unbounded_send(Resume::JobCallback(Box::new(move |comp| {
+        _ = reply_tx.send(func(comp));
+    })))
+    .ok()?;
+
+    reply_rx.recv().ok()
+}
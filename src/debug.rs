@@ -0,0 +1,247 @@
+use crate::{Component, ComponentMetrics, Event, Id, KeyEvent, LayerId, TerminalEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Statistics the compositor collects once the debug overlay is enabled.
+#[derive(Default)]
+pub(crate) struct DebugStats {
+    /// Key toggling the overlay.
+    pub(crate) key: Option<KeyEvent>,
+    last_event: Option<String>,
+    /// Components the last event was passed to and whether they consumed it.
+    reached: Vec<(Id, bool)>,
+    /// Whether the current event is shown, ticks would hide everything else.
+    recording: bool,
+    /// Times of events in the last second.
+    events: VecDeque<Instant>,
+    pub(crate) frame_time: Cell<Duration>,
+}
+
+impl DebugStats {
+    pub(crate) fn record<E>(&mut self, event: &Event<E>) {
+        let description = match event {
+            Event::Terminal(TerminalEvent::Key(key)) => format!("key {key} {:?}", key.kind),
+            Event::Terminal(TerminalEvent::Mouse(mouse)) => {
+                format!("mouse {:?} at {},{}", mouse.kind, mouse.column, mouse.row)
+            }
+            Event::Terminal(event) => format!("{event:?}"),
+            Event::User(_) => "user event".to_owned(),
+            Event::Exit => "exit".to_owned(),
+            Event::Tick | Event::AnimationFrame(_) | Event::None => {
+                self.recording = false;
+                return;
+            }
+        };
+
+        self.recording = true;
+        self.last_event = Some(description);
+        self.reached.clear();
+        self.events.push_back(Instant::now());
+    }
+
+    /// Notes that the current event was passed to the component with `id`.
+    pub(crate) fn reached(&mut self, id: Id, consumed: bool) {
+        if self.recording && id != DebugOverlay::overlay_id() {
+            self.reached.push((id, consumed));
+        }
+    }
+
+    pub(crate) fn snapshot(&mut self, layers: Vec<LayerInfo>, jobs: usize) -> DebugSnapshot {
+        while self
+            .events
+            .front()
+            .is_some_and(|at| at.elapsed() > Duration::from_secs(1))
+        {
+            self.events.pop_front();
+        }
+
+        DebugSnapshot {
+            layers,
+            last_event: self.last_event.clone(),
+            reached: self.reached.clone(),
+            events_per_sec: self.events.len(),
+            jobs,
+            frame_time: self.frame_time.get(),
+        }
+    }
+}
+
+/// Component listed in the [`DebugOverlay`].
+pub(crate) struct ComponentInfo {
+    pub(crate) id: Id,
+    pub(crate) title: Option<String>,
+    pub(crate) area: Rect,
+    pub(crate) focused: bool,
+    pub(crate) metrics: Option<ComponentMetrics>,
+}
+
+/// Layer listed in the [`DebugOverlay`].
+pub(crate) struct LayerInfo {
+    pub(crate) id: LayerId,
+    pub(crate) hidden: bool,
+    pub(crate) modal: bool,
+    pub(crate) components: Vec<ComponentInfo>,
+}
+
+#[derive(Default)]
+pub(crate) struct DebugSnapshot {
+    layers: Vec<LayerInfo>,
+    last_event: Option<String>,
+    reached: Vec<(Id, bool)>,
+    events_per_sec: usize,
+    jobs: usize,
+    frame_time: Duration,
+}
+
+/// Overlay showing layers with their components, the last event and which components it reached,
+/// events per second, [metrics](crate::Metrics) of components and the number of running jobs.
+/// Shown with [`Compositor::toggle_debug_overlay`](crate::Compositor::toggle_debug_overlay), it doesn't consume events.
+#[doc(cfg(feature = "debug"))]
+pub struct DebugOverlay {
+    snapshot: DebugSnapshot,
+}
+
+impl DebugOverlay {
+    pub(crate) fn new() -> Self {
+        Self {
+            snapshot: DebugSnapshot::default(),
+        }
+    }
+
+    /// Returns the id of the overlay, it is mounted at [`LayerId::TOPMOST`].
+    pub fn overlay_id() -> Id {
+        Id::new("gland-debug-overlay")
+    }
+
+    pub(crate) fn set_snapshot(&mut self, snapshot: DebugSnapshot) {
+        self.snapshot = snapshot;
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let snapshot = &self.snapshot;
+        let dim = Style::new().add_modifier(Modifier::DIM);
+        let mut lines = vec![
+            Line::raw(format!(
+                "events/s {}  jobs {}  frame {:.2?}",
+                snapshot.events_per_sec, snapshot.jobs, snapshot.frame_time
+            )),
+            Line::raw(format!(
+                "last {}",
+                snapshot.last_event.as_deref().unwrap_or("-")
+            )),
+        ];
+
+        let names = |id: Id| {
+            snapshot
+                .layers
+                .iter()
+                .flat_map(|l| &l.components)
+                .find(|c| c.id == id)
+                .map_or_else(|| short_id(id), name)
+        };
+        let reached = snapshot
+            .reached
+            .iter()
+            .map(|(id, consumed)| match consumed {
+                true => format!("{} (consumed)", names(*id)),
+                false => names(*id),
+            })
+            .collect::<Vec<_>>();
+        lines.push(match reached.is_empty() {
+            true => Line::styled("reached nothing", Style::new().fg(Color::Yellow)),
+            false => Line::raw(format!("reached {}", reached.join(", "))),
+        });
+
+        for layer in &snapshot.layers {
+            let mut title = format!("layer {}", layer.id.0);
+            if layer.hidden {
+                title.push_str(" hidden");
+            }
+            if layer.modal {
+                title.push_str(" modal");
+            }
+            lines.push(Line::styled(
+                title,
+                Style::new().add_modifier(Modifier::BOLD),
+            ));
+
+            for c in &layer.components {
+                let time = c.metrics.map_or("-".to_owned(), |m| {
+                    format!(
+                        "view {:.2?} event {:.2?}",
+                        m.view.last,
+                        m.handle_event.average()
+                    )
+                });
+                let Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                } = c.area;
+                let style = match c.focused {
+                    true => Style::new().fg(Color::Cyan),
+                    false => Style::new(),
+                };
+                lines.push(Line::styled(
+                    format!("  {} {width}x{height}+{x}+{y} {time}", name(c)),
+                    style,
+                ));
+            }
+        }
+        lines.push(Line::styled("cyan is focused", dim));
+        lines
+    }
+
+    /// Returns the area of the overlay in the top right corner.
+    fn area(&self, area: Rect, lines: &[Line<'_>]) -> Rect {
+        let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2;
+        let width = width.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        Rect {
+            x: area.right() - width,
+            y: area.y,
+            width,
+            height,
+        }
+    }
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for DebugOverlay {
+    fn id(&self) -> Id {
+        Self::overlay_id()
+    }
+
+    fn layout(&self, area: Rect) -> Rect {
+        self.area(area, &self.lines())
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        Clear.render(area, buf);
+        Paragraph::new(self.lines())
+            .block(Block::new().borders(Borders::ALL).title("debug"))
+            .render(area, buf);
+    }
+}
+
+fn name(component: &ComponentInfo) -> String {
+    match &component.title {
+        Some(title) => format!("{title} {}", short_id(component.id)),
+        None => short_id(component.id),
+    }
+}
+
+/// Formats the start of the id, enough to tell components apart.
+fn short_id(id: Id) -> String {
+    format!("#{:06x}", id.0.get() >> 40)
+}
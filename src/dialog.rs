@@ -0,0 +1,373 @@
+use crate::{
+    Component, Context, Event, Id, KeyCode, KeyEventKind, KeyModifiers, MouseButton,
+    MouseEventKind, Request, TerminalEvent,
+};
+use futures_channel::oneshot;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Options shown at most at once by a select dialog.
+const MAX_OPTIONS: u16 = 10;
+
+/// Question asked by a [`Dialog`].
+pub(crate) enum Kind {
+    /// `yes` and `no` are the button labels.
+    Confirm {
+        yes: String,
+        no: String,
+        confirmed: bool,
+    },
+    /// `cursor` is a byte index into `input`.
+    Prompt { input: String, cursor: usize },
+    Select {
+        options: Vec<String>,
+        selected: usize,
+    },
+}
+
+/// Answer sent back to the future returned when the dialog was opened.
+pub(crate) enum Answer {
+    Confirm(bool),
+    Prompt(Option<String>),
+    Select(Option<usize>),
+}
+
+/// Standard dialog shown on [`LayerId::POPUP`] by [`Context::confirm`], [`Context::prompt`]
+/// and [`Context::select`]. Removing it without an answer resolves as if it was cancelled.
+pub(crate) struct Dialog {
+    id: Id,
+    message: String,
+    kind: Kind,
+    answer: Option<oneshot::Sender<Answer>>,
+    /// Focused component when the dialog was mounted, focused again once it is closed.
+    previous: Option<Id>,
+    /// Area of the row with buttons, the input or the first option, set when drawing.
+    controls: Cell<Rect>,
+    offset: Cell<usize>,
+}
+
+impl Dialog {
+    /// Shows dialog asking `message` after this update, its answer is sent to the returned receiver.
+    pub(crate) fn open<S: 'static, E: 'static>(
+        cx: &mut Context<S, E>,
+        message: String,
+        kind: Kind,
+    ) -> oneshot::Receiver<Answer> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+
+        let (tx, rx) = oneshot::channel();
+        let id = Id::new("gland-dialog").with(NEXT.fetch_add(1, Ordering::Relaxed));
+        let dialog = Dialog {
+            id,
+            message,
+            kind,
+            answer: Some(tx),
+            previous: None,
+            controls: Cell::new(Rect::default()),
+            offset: Cell::new(0),
+        };
+        cx.request(Request::Dialog(dialog));
+        rx
+    }
+
+    /// Sends `answer` and removes the dialog after this update.
+    fn close<S: 'static, E: 'static>(&mut self, answer: Answer, cx: &mut Context<S, E>) {
+        if let Some(tx) = self.answer.take() {
+            _ = tx.send(answer);
+        }
+        let (id, previous) = (self.id, self.previous);
+        cx.add_callback(move |comp| {
+            comp.remove_all(id);
+            if let Some(previous) = previous {
+                _ = comp.set_focus(previous);
+            }
+        });
+    }
+
+    fn cancel<S: 'static, E: 'static>(&mut self, cx: &mut Context<S, E>) {
+        let answer = match self.kind {
+            Kind::Confirm { .. } => Answer::Confirm(false),
+            Kind::Prompt { .. } => Answer::Prompt(None),
+            Kind::Select { .. } => Answer::Select(None),
+        };
+        self.close(answer, cx);
+    }
+
+    fn submit<S: 'static, E: 'static>(&mut self, cx: &mut Context<S, E>) {
+        let answer = match &mut self.kind {
+            Kind::Confirm { confirmed, .. } => Answer::Confirm(*confirmed),
+            Kind::Prompt { input, .. } => Answer::Prompt(Some(std::mem::take(input))),
+            Kind::Select { selected, .. } => Answer::Select(Some(*selected)),
+        };
+        self.close(answer, cx);
+    }
+
+    /// Returns the number of rows below the message.
+    fn controls_height(&self) -> u16 {
+        match &self.kind {
+            Kind::Confirm { .. } | Kind::Prompt { .. } => 1,
+            Kind::Select { options, .. } => (options.len() as u16).clamp(1, MAX_OPTIONS),
+        }
+    }
+
+    /// Returns the ranges of columns of the confirm and cancel buttons within the controls.
+    fn buttons(yes: &str, no: &str) -> [(u16, u16); 2] {
+        let yes = yes.width() as u16 + 4;
+        let no = no.width() as u16 + 4;
+        [(0, yes), (yes + 2, yes + 2 + no)]
+    }
+
+    fn handle_key<S: 'static, E: 'static>(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        cx: &mut Context<S, E>,
+    ) {
+        let confirm = matches!(self.kind, Kind::Confirm { .. });
+        match code {
+            KeyCode::Esc => return self.cancel(cx),
+            KeyCode::Enter => return self.submit(cx),
+            KeyCode::Char('y') if confirm => return self.close(Answer::Confirm(true), cx),
+            KeyCode::Char('n') if confirm => return self.close(Answer::Confirm(false), cx),
+            _ => {}
+        }
+        match &mut self.kind {
+            Kind::Confirm { confirmed, .. } => {
+                if matches!(code, KeyCode::Left | KeyCode::Right | KeyCode::Tab) {
+                    *confirmed = !*confirmed;
+                }
+            }
+            Kind::Prompt { input, cursor } => match code {
+                KeyCode::Char(c)
+                    if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    input.insert(*cursor, c);
+                    *cursor += c.len_utf8();
+                }
+                KeyCode::Backspace => {
+                    if let Some(c) = input[..*cursor].chars().next_back() {
+                        *cursor -= c.len_utf8();
+                        input.remove(*cursor);
+                    }
+                }
+                KeyCode::Delete if *cursor < input.len() => _ = input.remove(*cursor),
+                KeyCode::Left => {
+                    *cursor -= input[..*cursor]
+                        .chars()
+                        .next_back()
+                        .map_or(0, char::len_utf8)
+                }
+                KeyCode::Right => {
+                    *cursor += input[*cursor..].chars().next().map_or(0, char::len_utf8)
+                }
+                KeyCode::Home => *cursor = 0,
+                KeyCode::End => *cursor = input.len(),
+                _ => {}
+            },
+            Kind::Select { options, selected } => match code {
+                KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    *selected = (*selected + 1).min(options.len().saturating_sub(1))
+                }
+                KeyCode::Home => *selected = 0,
+                KeyCode::End => *selected = options.len().saturating_sub(1),
+                _ => {}
+            },
+        }
+    }
+
+    fn click<S: 'static, E: 'static>(&mut self, column: u16, row: u16, cx: &mut Context<S, E>) {
+        let controls = self.controls.get();
+        if !controls.intersects(Rect::new(column, row, 1, 1)) {
+            return;
+        }
+        let (x, y) = (column - controls.x, row - controls.y);
+        let answer = match &self.kind {
+            Kind::Confirm { yes, no, .. } => {
+                let [yes, no] = Self::buttons(yes, no);
+                if (yes.0..yes.1).contains(&x) {
+                    Answer::Confirm(true)
+                } else if (no.0..no.1).contains(&x) {
+                    Answer::Confirm(false)
+                } else {
+                    return;
+                }
+            }
+            Kind::Prompt { .. } => return,
+            Kind::Select { options, .. } => {
+                let index = self.offset.get() + y as usize;
+                if index >= options.len() {
+                    return;
+                }
+                Answer::Select(Some(index))
+            }
+        };
+        self.close(answer, cx);
+    }
+}
+
+/// Returns the byte index input is shown from so the cursor fits in `width`, and the column of the cursor.
+fn prompt_scroll(input: &str, cursor: usize, width: u16) -> (usize, u16) {
+    let before = input[..cursor].width();
+    let skip = (before + 1).saturating_sub(width as usize);
+    let mut skipped = 0;
+    let start = input
+        .char_indices()
+        .find(|(_, c)| {
+            let found = skipped >= skip;
+            if !found {
+                skipped += c.width().unwrap_or(0);
+            }
+            found
+        })
+        .map_or(input.len(), |(i, _)| i);
+    (start, (before - skipped) as u16)
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for Dialog {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        self.previous = cx.focused();
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        let width = area.width.min(60);
+        let inner = width.saturating_sub(2).max(1) as usize;
+        let lines = self
+            .message
+            .lines()
+            .map(|l| l.width().div_ceil(inner).max(1) as u16)
+            .sum::<u16>();
+        let height = (lines + self.controls_height() + 3).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 3,
+            width,
+            height,
+        };
+
+        Clear.render(popup, buf);
+        let block = Block::new().borders(Borders::ALL);
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+        let controls_y = (inner.y + lines + 1).min(inner.bottom());
+        Paragraph::new(self.message.as_str())
+            .wrap(Wrap { trim: false })
+            .render(
+                Rect {
+                    height: controls_y - inner.y,
+                    ..inner
+                },
+                buf,
+            );
+
+        let controls = Rect {
+            y: controls_y,
+            height: inner.bottom() - controls_y,
+            ..inner
+        };
+        self.controls.set(controls);
+        if controls.height == 0 {
+            return;
+        }
+
+        let selected_style = Style::new().add_modifier(Modifier::REVERSED);
+        match &self.kind {
+            Kind::Confirm { yes, no, confirmed } => {
+                let [yes_range, no_range] = Self::buttons(yes, no);
+                for ((start, _), label, selected) in
+                    [(yes_range, yes, *confirmed), (no_range, no, !*confirmed)]
+                {
+                    let style = if selected {
+                        selected_style
+                    } else {
+                        Style::new()
+                    };
+                    let x = controls.x + start.min(controls.width);
+                    let max = (controls.right() - x) as usize;
+                    buf.set_stringn(x, controls.y, format!("[ {label} ]"), max, style);
+                }
+            }
+            Kind::Prompt { input, cursor } => {
+                let (start, _) = prompt_scroll(input, *cursor, controls.width);
+                let style = Style::new().add_modifier(Modifier::UNDERLINED);
+                let row = Rect {
+                    height: 1,
+                    ..controls
+                };
+                buf.set_style(row, style);
+                buf.set_stringn(row.x, row.y, &input[start..], row.width as usize, style);
+            }
+            Kind::Select { options, selected } => {
+                let rows = controls.height as usize;
+                let offset = self
+                    .offset
+                    .get()
+                    .clamp((selected + 1).saturating_sub(rows), *selected);
+                self.offset.set(offset);
+                for (y, (i, option)) in
+                    (controls.y..controls.bottom()).zip(options.iter().enumerate().skip(offset))
+                {
+                    let style = if i == *selected {
+                        selected_style
+                    } else {
+                        Style::new()
+                    };
+                    let row = Rect {
+                        y,
+                        height: 1,
+                        ..controls
+                    };
+                    buf.set_style(row, style);
+                    buf.set_stringn(row.x, y, option, row.width as usize, style);
+                }
+            }
+        }
+    }
+
+    fn cursor(&self, _area: Rect, _state: &S) -> Option<(u16, u16)> {
+        let Kind::Prompt { input, cursor } = &self.kind else {
+            return None;
+        };
+        let controls = self.controls.get();
+        if controls.height == 0 {
+            return None;
+        }
+        let (_, x) = prompt_scroll(input, *cursor, controls.width);
+        Some((controls.x + x, controls.y))
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        match event {
+            Event::Terminal(TerminalEvent::Key(key)) => {
+                if key.kind != KeyEventKind::Release {
+                    self.handle_key(key.code, key.modifiers, cx);
+                }
+            }
+            Event::Terminal(TerminalEvent::Mouse(mouse)) => {
+                if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+                    self.click(mouse.column, mouse.row, cx);
+                }
+            }
+            _ => return,
+        }
+        // Dialogs are modal, keys and clicks never reach components below.
+        _ = event.consume();
+    }
+}
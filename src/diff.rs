@@ -0,0 +1,98 @@
+use ratatui::{buffer::Buffer, style::Modifier};
+use std::time::{Duration, Instant};
+
+/// Debug helper that highlights cells changed since the previous frame.
+pub(crate) struct FrameDiff {
+    highlight: Duration,
+    previous: Buffer,
+    changed_at: Vec<Option<Instant>>,
+    /// When the earliest highlight in the last frame expires.
+    expires: Option<Instant>,
+    /// Wakeup requested with [`Self::schedule_wakeup`].
+    scheduled: Option<Instant>,
+}
+
+impl FrameDiff {
+    pub(crate) fn new(highlight: Duration) -> Self {
+        Self {
+            highlight,
+            previous: Buffer::default(),
+            changed_at: Vec::new(),
+            expires: None,
+            scheduled: None,
+        }
+    }
+
+    /// Compares `buf` with the previous frame and inverts cells that changed within the highlight duration.
+    pub(crate) fn apply(&mut self, buf: &mut Buffer) {
+        let now = Instant::now();
+
+        if self.previous.area != buf.area {
+            self.previous = Buffer::empty(buf.area);
+            self.changed_at = vec![Some(now); buf.content.len()];
+        }
+
+        self.expires = None;
+        let cells = buf.content.iter_mut().zip(self.previous.content.iter_mut());
+        for ((cell, previous), changed_at) in cells.zip(self.changed_at.iter_mut()) {
+            if cell != previous {
+                previous.clone_from(cell);
+                *changed_at = Some(now);
+            }
+
+            if let Some(at) = changed_at.filter(|at| now - *at < self.highlight) {
+                cell.modifier.toggle(Modifier::REVERSED);
+                let expires = at + self.highlight;
+                self.expires = Some(self.expires.map_or(expires, |e| e.min(expires)));
+            } else {
+                *changed_at = None;
+            }
+        }
+    }
+
+    /// Returns when the frame has to be drawn again to clear expired highlights,
+    /// `None` if there are none or a wakeup is already scheduled for them.
+    pub(crate) fn schedule_wakeup(&mut self) -> Option<Instant> {
+        let expires = self.expires?;
+        if self
+            .scheduled
+            .is_some_and(|at| at <= expires && at > Instant::now())
+        {
+            return None;
+        }
+        self.scheduled = Some(expires);
+        Some(expires)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameDiff;
+    use ratatui::{buffer::Buffer, layout::Rect, style::Modifier};
+    use std::{thread::sleep, time::Duration};
+
+    #[test]
+    fn wakes_up_when_highlights_expire() {
+        let area = Rect::new(0, 0, 2, 1);
+        let mut diff = FrameDiff::new(Duration::from_millis(50));
+        diff.apply(&mut Buffer::empty(area));
+        let first = diff.schedule_wakeup().expect("new cells are highlighted");
+        assert_eq!(diff.schedule_wakeup(), None);
+
+        // Redrawing before the expiry doesn't schedule another wakeup.
+        diff.apply(&mut Buffer::empty(area));
+        assert_eq!(diff.schedule_wakeup(), None);
+
+        sleep(Duration::from_millis(60));
+        let mut buf = Buffer::empty(area);
+        diff.apply(&mut buf);
+        assert!(!buf.content[0].modifier.contains(Modifier::REVERSED));
+        assert_eq!(diff.schedule_wakeup(), None);
+
+        buf = Buffer::empty(area);
+        buf.content[0].set_symbol("x");
+        diff.apply(&mut buf);
+        assert!(buf.content[0].modifier.contains(Modifier::REVERSED));
+        assert!(diff.schedule_wakeup().expect("changed cell is highlighted") > first);
+    }
+}
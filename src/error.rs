@@ -0,0 +1,133 @@
+use crate::{Component, Context, Event, Id, KeyCode, TerminalEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+use std::{fmt, io};
+
+/// Error returned when running the compositor.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Writing to or setting up the terminal failed.
+    Io(io::Error),
+    /// Stdout is not a terminal, e.g. it is piped or runs in CI.
+    /// Use [`Compositor::with_headless_fallback`](crate::Compositor::with_headless_fallback) to run anyway.
+    NotATty,
+    /// Component failed to handle an event, see [`Component::try_handle_event`].
+    Component {
+        id: Id,
+        error: Box<dyn std::error::Error>,
+    },
+    /// Component panicked with isolation enabled,
+    /// see [`Compositor::with_component_isolation`](crate::Compositor::with_component_isolation).
+    Panicked { id: Id, message: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "terminal io failed: {e}"),
+            Self::NotATty => write!(f, "stdout is not a terminal"),
+            Self::Component { id, error } => write!(f, "component {id:?} failed: {error}"),
+            Self::Panicked { id, message } => write!(f, "component {id:?} panicked: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::NotATty | Self::Panicked { .. } => None,
+            Self::Component { error, .. } => Some(error.as_ref()),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Popup showing an error of a component, mounted when there is no error handler.
+/// Dismissed with `Esc` or `Enter`, consumes key events while shown.
+pub struct ErrorPopup {
+    title: String,
+    message: String,
+}
+
+impl ErrorPopup {
+    /// Creates popup showing `message` under `title`.
+    pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Returns the id every error popup has, there is at most one mounted.
+    pub fn popup_id() -> Id {
+        Id::new("gland-error-popup")
+    }
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for ErrorPopup {
+    fn id(&self) -> Id {
+        Self::popup_id()
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        let width = area.width.min(60);
+        let inner = width.saturating_sub(2).max(1) as usize;
+        let lines = self
+            .message
+            .lines()
+            .map(|l| l.chars().count().div_ceil(inner).max(1) as u16)
+            .sum::<u16>();
+        let height = (lines + 2).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        Clear.render(popup, buf);
+        Paragraph::new(self.message.as_str())
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .border_style(Style::new().fg(Color::Red))
+                    .title(self.title.as_str()),
+            )
+            .render(popup, buf);
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        if let Event::Terminal(TerminalEvent::Key(key)) = event {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+                cx.add_callback(|comp| comp.remove_all(Self::popup_id()));
+            }
+            _ = event.consume();
+        }
+    }
+}
+
+/// Decides how the compositor continues after an error, see
+/// [`Compositor::with_error_handler`](crate::Compositor::with_error_handler).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Skips the failed frame, the next one is drawn as usual.
+    Continue,
+    /// Draws the frame again right away, the handler is called again if it fails.
+    Retry,
+    /// Stops the compositor, the error is returned from `run`.
+    Exit,
+}
+
+pub(crate) type ErrorHandler = Box<dyn FnMut(&Error) -> ErrorAction>;
@@ -0,0 +1,340 @@
+use crate::ansi::write_sgr;
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+use std::{fmt::Write as _, path::Path};
+use unicode_width::UnicodeWidthStr;
+
+/// Format of an exported frame, see [`Compositor::export_frame`](crate::Compositor::export_frame).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// Text with ANSI escape sequences, can be printed to a terminal.
+    Ansi,
+    /// Standalone HTML page.
+    Html,
+    /// Standalone SVG image.
+    Svg,
+    /// Plain text followed by the styled spans of every row, meant for golden files
+    /// as it stays readable in diffs, see [`testing::assert_snapshot`](crate::testing::assert_snapshot).
+    Snapshot,
+}
+
+impl ExportFormat {
+    /// Picks format from the extension of `path`, `.html`/`.htm`, `.svg` and `.snap` are recognized, ANSI otherwise.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm") => {
+                Self::Html
+            }
+            Some(e) if e.eq_ignore_ascii_case("svg") => Self::Svg,
+            Some(e) if e.eq_ignore_ascii_case("snap") => Self::Snapshot,
+            _ => Self::Ansi,
+        }
+    }
+}
+
+const DEFAULT_FG: (u8, u8, u8) = (0xe5, 0xe5, 0xe5);
+const DEFAULT_BG: (u8, u8, u8) = (0x00, 0x00, 0x00);
+/// Cell size in pixels used by SVG export.
+const CELL_WIDTH: f32 = 8.4;
+const CELL_HEIGHT: f32 = 17.0;
+
+/// Cells in a row that share the same style.
+struct Run {
+    x: u16,
+    /// Width in cells.
+    width: u16,
+    text: String,
+    fg: Color,
+    bg: Color,
+    modifier: Modifier,
+}
+
+pub(crate) fn export(buf: &Buffer, format: ExportFormat) -> String {
+    let rows = runs(buf);
+    match format {
+        ExportFormat::Ansi => ansi(&rows),
+        ExportFormat::Html => html(&rows),
+        ExportFormat::Svg => svg(&rows, buf.area.width, buf.area.height),
+        ExportFormat::Snapshot => snapshot(&rows),
+    }
+}
+
+fn runs(buf: &Buffer) -> Vec<Vec<Run>> {
+    let area = buf.area;
+    let mut rows = Vec::with_capacity(area.height as usize);
+
+    for y in area.top()..area.bottom() {
+        let mut row: Vec<Run> = Vec::new();
+        let mut x = area.left();
+        while x < area.right() {
+            let cell = buf.get(x, y);
+            // Cells covered by a wide character are skipped.
+            let width = (cell.symbol().width() as u16).max(1);
+
+            match row.last_mut() {
+                Some(run)
+                    if (run.fg, run.bg, run.modifier) == (cell.fg, cell.bg, cell.modifier) =>
+                {
+                    run.text.push_str(cell.symbol());
+                    run.width += width;
+                }
+                _ => row.push(Run {
+                    x: x - area.left(),
+                    width,
+                    text: cell.symbol().to_owned(),
+                    fg: cell.fg,
+                    bg: cell.bg,
+                    modifier: cell.modifier,
+                }),
+            }
+            x = x.saturating_add(width);
+        }
+        rows.push(row);
+    }
+
+    rows
+}
+
+fn ansi(rows: &[Vec<Run>]) -> String {
+    let mut out = String::new();
+    for row in rows {
+        for run in row {
+            write_sgr(&mut out, run.fg, run.bg, run.modifier);
+            out.push_str(&run.text);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+fn snapshot(rows: &[Vec<Run>]) -> String {
+    let mut out = String::new();
+    for row in rows {
+        row.iter().for_each(|run| out.push_str(&run.text));
+        out.push('\n');
+    }
+
+    let styled = rows.iter().enumerate().flat_map(|(y, row)| {
+        row.iter()
+            .filter(|run| {
+                (run.fg, run.bg, run.modifier) != (Color::Reset, Color::Reset, Modifier::empty())
+            })
+            .map(move |run| (y, run))
+    });
+    for (i, (y, run)) in styled.enumerate() {
+        if i == 0 {
+            out.push_str("--- styles\n");
+        }
+        _ = write!(out, "{y}:{}..{}", run.x, run.x + run.width);
+        if run.fg != Color::Reset {
+            _ = write!(out, " fg={:?}", run.fg);
+        }
+        if run.bg != Color::Reset {
+            _ = write!(out, " bg={:?}", run.bg);
+        }
+        if !run.modifier.is_empty() {
+            _ = write!(out, " {:?}", run.modifier);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn html(rows: &[Vec<Run>]) -> String {
+    let mut out = String::new();
+    _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>gland</title></head>\n\
+         <body style=\"margin:0;background:{bg}\">\n\
+         <pre style=\"margin:0;font-family:monospace;line-height:1.2;color:{fg};background:{bg}\">",
+        fg = hex(DEFAULT_FG),
+        bg = hex(DEFAULT_BG),
+    );
+
+    for row in rows {
+        for run in row {
+            let (fg, bg) = colors(run);
+            let mut style = String::new();
+            if fg != DEFAULT_FG {
+                _ = write!(style, "color:{};", hex(fg));
+            }
+            if bg != DEFAULT_BG {
+                _ = write!(style, "background:{};", hex(bg));
+            }
+            style.push_str(&font_css(run.modifier));
+
+            if style.is_empty() {
+                out.push_str(&escape(&run.text));
+            } else {
+                _ = write!(out, "<span style=\"{style}\">{}</span>", escape(&run.text));
+            }
+        }
+        out.push('\n');
+    }
+
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
+
+fn svg(rows: &[Vec<Run>], width: u16, height: u16) -> String {
+    let mut out = String::new();
+    _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.1}\" height=\"{h:.1}\" viewBox=\"0 0 {w:.1} {h:.1}\" \
+         font-family=\"monospace\" font-size=\"14\">\n<rect width=\"100%\" height=\"100%\" fill=\"{bg}\"/>",
+        w = width as f32 * CELL_WIDTH,
+        h = height as f32 * CELL_HEIGHT,
+        bg = hex(DEFAULT_BG),
+    );
+
+    for (y, row) in rows.iter().enumerate() {
+        let top = y as f32 * CELL_HEIGHT;
+        for run in row {
+            let (fg, bg) = colors(run);
+            let left = run.x as f32 * CELL_WIDTH;
+            let length = run.width as f32 * CELL_WIDTH;
+
+            if bg != DEFAULT_BG {
+                _ = writeln!(
+                    out,
+                    "<rect x=\"{left:.1}\" y=\"{top:.1}\" width=\"{length:.1}\" height=\"{CELL_HEIGHT:.1}\" fill=\"{}\"/>",
+                    hex(bg)
+                );
+            }
+            if run.text.trim().is_empty() {
+                continue;
+            }
+
+            // `textLength` keeps runs aligned to the grid whatever the font.
+            _ = writeln!(
+                out,
+                "<text x=\"{left:.1}\" y=\"{:.1}\" textLength=\"{length:.1}\" lengthAdjust=\"spacingAndGlyphs\" \
+                 xml:space=\"preserve\" fill=\"{}\" style=\"{}\">{}</text>",
+                top + CELL_HEIGHT * 0.8,
+                hex(fg),
+                font_css(run.modifier),
+                escape(&run.text)
+            );
+        }
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Resolves foreground and background of a run, applying reversed and hidden modifiers.
+fn colors(run: &Run) -> ((u8, u8, u8), (u8, u8, u8)) {
+    let fg = rgb(run.fg).unwrap_or(DEFAULT_FG);
+    let bg = rgb(run.bg).unwrap_or(DEFAULT_BG);
+    let (fg, bg) = if run.modifier.contains(Modifier::REVERSED) {
+        (bg, fg)
+    } else {
+        (fg, bg)
+    };
+
+    if run.modifier.contains(Modifier::HIDDEN) {
+        (bg, bg)
+    } else {
+        (fg, bg)
+    }
+}
+
+fn font_css(modifier: Modifier) -> String {
+    let mut css = String::new();
+    if modifier.contains(Modifier::BOLD) {
+        css.push_str("font-weight:bold;");
+    }
+    if modifier.contains(Modifier::ITALIC) {
+        css.push_str("font-style:italic;");
+    }
+    if modifier.contains(Modifier::DIM) {
+        css.push_str("opacity:0.5;");
+    }
+    match (
+        modifier.contains(Modifier::UNDERLINED),
+        modifier.contains(Modifier::CROSSED_OUT),
+    ) {
+        (true, true) => css.push_str("text-decoration:underline line-through;"),
+        (true, false) => css.push_str("text-decoration:underline;"),
+        (false, true) => css.push_str("text-decoration:line-through;"),
+        (false, false) => {}
+    }
+    css
+}
+
+/// Returns color in xterm's default palette, `None` for the default color.
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    const PALETTE: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0xcd, 0x00, 0x00),
+        (0x00, 0xcd, 0x00),
+        (0xcd, 0xcd, 0x00),
+        (0x00, 0x00, 0xee),
+        (0xcd, 0x00, 0xcd),
+        (0x00, 0xcd, 0xcd),
+        (0xe5, 0xe5, 0xe5),
+        (0x7f, 0x7f, 0x7f),
+        (0xff, 0x00, 0x00),
+        (0x00, 0xff, 0x00),
+        (0xff, 0xff, 0x00),
+        (0x5c, 0x5c, 0xff),
+        (0xff, 0x00, 0xff),
+        (0x00, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some((r, g, b)),
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Indexed(i) => i,
+    };
+
+    Some(match index {
+        0..=15 => PALETTE[index as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = index - 16;
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + (index - 232) * 10;
+            (v, v, v)
+        }
+    })
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
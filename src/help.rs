@@ -0,0 +1,132 @@
+use crate::{
+    keymap::normalize, Component, Context, Event, Id, KeyCode, KeyEvent, KeyEventKind,
+    TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+/// Bindings of a component or layer listed in a [`HelpPopup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpSection {
+    pub title: String,
+    /// Keys with descriptions of what they do, e.g. `ctrl-s` and `Save the file`.
+    pub bindings: Vec<(String, String)>,
+}
+
+/// Popup listing key bindings, see [`Compositor::show_help`](crate::Compositor::show_help).
+/// Scrolled with arrow keys, dismissed with `Esc`, `Enter`, `q` or the key that opened it.
+/// Consumes key events while shown.
+pub struct HelpPopup {
+    title: String,
+    sections: Vec<HelpSection>,
+    close_key: Option<KeyEvent>,
+    scroll: u16,
+}
+
+impl HelpPopup {
+    /// Creates popup listing `sections` under `title`.
+    pub fn new(title: impl Into<String>, sections: Vec<HelpSection>) -> Self {
+        Self {
+            title: title.into(),
+            sections,
+            close_key: None,
+            scroll: 0,
+        }
+    }
+
+    /// Dismisses the popup with `key` as well, e.g. the key that opened it.
+    pub fn with_close_key(mut self, key: KeyEvent) -> Self {
+        self.close_key = Some(key);
+        self
+    }
+
+    /// Returns the id every help popup has, there is at most one mounted.
+    pub fn popup_id() -> Id {
+        Id::new("gland-help-popup")
+    }
+
+    fn lines(&self) -> Vec<Line<'_>> {
+        let width = self
+            .sections
+            .iter()
+            .flat_map(|s| &s.bindings)
+            .map(|(keys, _)| keys.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = Vec::new();
+        for section in &self.sections {
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(Line::styled(
+                section.title.as_str(),
+                Style::new().add_modifier(Modifier::BOLD),
+            ));
+            for (keys, description) in &section.bindings {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {keys:width$}  "), Style::new().fg(Color::Cyan)),
+                    Span::raw(description.as_str()),
+                ]));
+            }
+        }
+        lines
+    }
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for HelpPopup {
+    fn id(&self) -> Id {
+        Self::popup_id()
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        let lines = self.lines();
+        let content = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+        let width = (content + 4).max(self.title.chars().count() as u16 + 4);
+        let width = width.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .scroll((self.scroll, 0))
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.title.as_str()),
+            )
+            .render(popup, buf);
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        if let Event::Terminal(TerminalEvent::Key(key)) = event {
+            match key.code {
+                // Release of the key that opened the popup would close it right away.
+                _ if key.kind == KeyEventKind::Release => {}
+                KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let last = self.lines().len().saturating_sub(1) as u16;
+                    self.scroll = (self.scroll + 1).min(last);
+                }
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                    cx.add_callback(|comp| comp.remove_all(Self::popup_id()));
+                }
+                _ if self.close_key == Some(normalize(*key)) => {
+                    cx.add_callback(|comp| comp.remove_all(Self::popup_id()));
+                }
+                _ => {}
+            }
+            _ = event.consume();
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Undo and redo of state changes expressed as [`Command`]s.
+//!
+//! Commands applied with [`Context::apply`](crate::Context::apply) are recorded by the [`History`]
+//! of the compositor, see [`Compositor::with_history`](crate::Compositor::with_history), and undone with
+//! [`Context::undo`](crate::Context::undo). Rapid edits like typing are undone together if their commands
+//! return the same [`Command::coalesce`] key.
+//!
+//! ```
+//! # use gland::history::{Command, History};
+//! struct Append(char);
+//!
+//! impl Command<String> for Append {
+//!     fn apply(&mut self, text: &mut String) {
+//!         text.push(self.0);
+//!     }
+//!
+//!     fn undo(&mut self, text: &mut String) {
+//!         text.pop();
+//!     }
+//!
+//!     fn coalesce(&self) -> Option<&'static str> {
+//!         Some("typing")
+//!     }
+//! }
+//!
+//! let mut text = String::new();
+//! let mut history = History::new();
+//! history.apply(Append('h'), &mut text);
+//! history.apply(Append('i'), &mut text);
+//! assert_eq!(text, "hi");
+//!
+//! history.undo(&mut text);
+//! assert_eq!(text, "");
+//! history.redo(&mut text);
+//! assert_eq!(text, "hi");
+//! ```
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Change of the state that can be undone.
+pub trait Command<S>: 'static {
+    /// Changes the state, called again when the command is redone.
+    fn apply(&mut self, state: &mut S);
+
+    /// Reverts the changes made by [`Self::apply`].
+    fn undo(&mut self, state: &mut S);
+
+    /// Commands with the same key applied within the coalescing window of each other are undone together,
+    /// see [`History::with_coalesce_window`]. Not coalesced by default.
+    fn coalesce(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Commands undone and redone together.
+struct Group<S> {
+    commands: Vec<Box<dyn Command<S>>>,
+    key: Option<&'static str>,
+    last: Instant,
+}
+
+/// Undo and redo stacks of applied commands.
+pub struct History<S> {
+    undo: VecDeque<Group<S>>,
+    redo: Vec<Group<S>>,
+    depth: usize,
+    coalesce_window: Duration,
+}
+
+impl<S> Default for History<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder functions
+impl<S> History<S> {
+    /// Creates a history keeping 100 steps and coalescing commands applied within 500ms.
+    pub fn new() -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            depth: 100,
+            coalesce_window: Duration::from_millis(500),
+        }
+    }
+
+    /// Sets the number of steps that can be undone, older ones are forgotten.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the time within which commands with the same [`Command::coalesce`] key are undone together.
+    pub fn with_coalesce_window(mut self, window: Duration) -> Self {
+        self.coalesce_window = window;
+        self
+    }
+}
+
+/// Non-builder functions
+impl<S: 'static> History<S> {
+    /// Applies `command` to `state` and records it, clearing the redo stack.
+    pub fn apply(&mut self, mut command: impl Command<S>, state: &mut S) {
+        command.apply(state);
+        self.redo.clear();
+
+        let now = Instant::now();
+        let key = command.coalesce();
+        if let Some(group) = self.undo.back_mut().filter(|group| {
+            key.is_some() && group.key == key && now - group.last <= self.coalesce_window
+        }) {
+            group.commands.push(Box::new(command));
+            group.last = now;
+            return;
+        }
+
+        self.undo.push_back(Group {
+            commands: vec![Box::new(command)],
+            key,
+            last: now,
+        });
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Reverts the last step, returns `false` if there is nothing to undo.
+    pub fn undo(&mut self, state: &mut S) -> bool {
+        let Some(mut group) = self.undo.pop_back() else {
+            return false;
+        };
+        group.commands.iter_mut().rev().for_each(|c| c.undo(state));
+        self.redo.push(group);
+        true
+    }
+
+    /// Applies the last undone step again, returns `false` if there is nothing to redo.
+    pub fn redo(&mut self, state: &mut S) -> bool {
+        let Some(mut group) = self.redo.pop() else {
+            return false;
+        };
+        group.commands.iter_mut().for_each(|c| c.apply(state));
+        // Commands applied after a redo start a new step.
+        group.key = None;
+        self.undo.push_back(group);
+        true
+    }
+
+    /// Returns `true` if there is a step to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` if there is a step to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets all steps.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
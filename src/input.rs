@@ -0,0 +1,446 @@
+use bitflags::bitflags;
+use std::{fmt, str::FromStr};
+
+/// Event received from the terminal, independent of the terminal library.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TerminalEvent {
+    /// Key was pressed, repeated or released.
+    Key(KeyEvent),
+    /// Mouse was moved, clicked or scrolled.
+    Mouse(MouseEvent),
+    /// Terminal was resized to `(columns, rows)`.
+    Resize(u16, u16),
+    /// Text was pasted with bracketed paste enabled.
+    Paste(String),
+    /// Terminal window gained focus.
+    FocusGained,
+    /// Terminal window lost focus.
+    FocusLost,
+    /// Clipboard contents requested with [`Clipboard::request`](crate::Clipboard::request).
+    Clipboard(String),
+}
+
+/// Keyboard event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+    pub kind: KeyEventKind,
+}
+
+impl KeyEvent {
+    /// Creates new key press event.
+    #[inline]
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+        }
+    }
+}
+
+impl From<KeyCode> for KeyEvent {
+    #[inline]
+    fn from(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+}
+
+/// Error returned when parsing [`KeyEvent`] from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeyError(pub(crate) String);
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid key: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+/// Parses keys like `ctrl-s`, `alt-shift-left`, `f5` or `?`.
+/// Modifiers are `ctrl`, `alt`, `shift`, `super`, `hyper` and `meta`, uppercase characters imply `shift`.
+impl FromStr for KeyEvent {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseKeyError(s.to_owned());
+
+        // `-` is a key on its own, e.g. `ctrl--`.
+        let (mods, key) = match s.strip_suffix("--") {
+            Some(mods) => (mods, "-"),
+            None if s == "-" => ("", s),
+            None => s.rsplit_once('-').unwrap_or(("", s)),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for m in mods.split('-').filter(|m| !m.is_empty()) {
+            modifiers |= match m.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                "super" => KeyModifiers::SUPER,
+                "hyper" => KeyModifiers::HYPER,
+                "meta" => KeyModifiers::META,
+                _ => return Err(err()),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                if c.is_uppercase() {
+                    modifiers |= KeyModifiers::SHIFT;
+                }
+                KeyCode::Char(c)
+            }
+            _ => match key.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "space" => KeyCode::Char(' '),
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                f if f.starts_with('f') => KeyCode::F(f[1..].parse().map_err(|_| err())?),
+                _ => return Err(err()),
+            },
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+/// Formats keys the way they are parsed, e.g. `ctrl-s`.
+impl fmt::Display for KeyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut modifiers = self.modifiers;
+        if matches!(self.code, KeyCode::Char(c) if c.is_uppercase()) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        let names = [
+            (KeyModifiers::CONTROL, "ctrl"),
+            (KeyModifiers::ALT, "alt"),
+            (KeyModifiers::SHIFT, "shift"),
+            (KeyModifiers::SUPER, "super"),
+            (KeyModifiers::HYPER, "hyper"),
+            (KeyModifiers::META, "meta"),
+        ];
+        for (modifier, name) in names {
+            if modifiers.contains(modifier) {
+                write!(f, "{name}-")?;
+            }
+        }
+
+        let name = match self.code {
+            KeyCode::Char(' ') => "space",
+            KeyCode::Char(c) => return write!(f, "{c}"),
+            KeyCode::F(n) => return write!(f, "f{n}"),
+            KeyCode::Backspace => "backspace",
+            KeyCode::Enter => "enter",
+            KeyCode::Left => "left",
+            KeyCode::Right => "right",
+            KeyCode::Up => "up",
+            KeyCode::Down => "down",
+            KeyCode::Home => "home",
+            KeyCode::End => "end",
+            KeyCode::PageUp => "pageup",
+            KeyCode::PageDown => "pagedown",
+            KeyCode::Tab => "tab",
+            KeyCode::BackTab => "backtab",
+            KeyCode::Delete => "delete",
+            KeyCode::Insert => "insert",
+            KeyCode::Esc => "esc",
+            KeyCode::CapsLock => "capslock",
+            KeyCode::ScrollLock => "scrolllock",
+            KeyCode::NumLock => "numlock",
+            KeyCode::PrintScreen => "printscreen",
+            KeyCode::Pause => "pause",
+            KeyCode::Menu => "menu",
+            KeyCode::KeypadBegin => "keypadbegin",
+            KeyCode::Null => "null",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Key that was pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Backspace,
+    Enter,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Tab,
+    BackTab,
+    Delete,
+    Insert,
+    /// Function key, `F(1)` is `F1`.
+    F(u8),
+    Char(char),
+    Esc,
+    CapsLock,
+    ScrollLock,
+    NumLock,
+    PrintScreen,
+    Pause,
+    Menu,
+    KeypadBegin,
+    /// Keys that gland doesn't distinguish, e.g. media and modifier keys.
+    Null,
+}
+
+bitflags! {
+    /// Modifier keys held during key or mouse event.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct KeyModifiers: u8 {
+        const NONE = 0;
+        const SHIFT = 1 << 0;
+        const CONTROL = 1 << 1;
+        const ALT = 1 << 2;
+        const SUPER = 1 << 3;
+        const HYPER = 1 << 4;
+        const META = 1 << 5;
+    }
+}
+
+bitflags! {
+    /// Kitty keyboard protocol enhancements, see [`Compositor::with_keyboard_enhancements`](crate::Compositor::with_keyboard_enhancements).
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct KeyboardEnhancementFlags: u8 {
+        /// Reports escape and modified keys unambiguously, e.g. `ctrl-i` apart from `tab`.
+        const DISAMBIGUATE_ESCAPE_CODES = 1 << 0;
+        /// Reports key repeat and release events.
+        const REPORT_EVENT_TYPES = 1 << 1;
+        /// Reports alternate key codes, e.g. shifted keys.
+        const REPORT_ALTERNATE_KEYS = 1 << 2;
+        /// Reports all keys as escape codes, required for release events of text keys.
+        const REPORT_ALL_KEYS_AS_ESCAPE_CODES = 1 << 3;
+    }
+}
+
+/// Kind of the key event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyEventKind {
+    Press,
+    Repeat,
+    Release,
+}
+
+/// Mouse event, coordinates are in cells starting at the top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub column: u16,
+    pub row: u16,
+    pub modifiers: KeyModifiers,
+}
+
+/// Kind of the mouse event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseEventKind {
+    Down(MouseButton),
+    Up(MouseButton),
+    Drag(MouseButton),
+    Moved,
+    ScrollDown,
+    ScrollUp,
+    ScrollLeft,
+    ScrollRight,
+}
+
+/// Mouse button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod from_crossterm {
+    use super::*;
+    use crossterm::event as ct;
+
+    impl From<ct::Event> for TerminalEvent {
+        fn from(event: ct::Event) -> Self {
+            match event {
+                ct::Event::Key(e) => Self::Key(e.into()),
+                ct::Event::Mouse(e) => Self::Mouse(e.into()),
+                ct::Event::Resize(w, h) => Self::Resize(w, h),
+                ct::Event::Paste(text) => Self::Paste(text),
+                ct::Event::FocusGained => Self::FocusGained,
+                ct::Event::FocusLost => Self::FocusLost,
+            }
+        }
+    }
+
+    impl From<ct::KeyEvent> for KeyEvent {
+        fn from(event: ct::KeyEvent) -> Self {
+            Self {
+                code: event.code.into(),
+                modifiers: event.modifiers.into(),
+                kind: match event.kind {
+                    ct::KeyEventKind::Press => KeyEventKind::Press,
+                    ct::KeyEventKind::Repeat => KeyEventKind::Repeat,
+                    ct::KeyEventKind::Release => KeyEventKind::Release,
+                },
+            }
+        }
+    }
+
+    impl From<ct::KeyCode> for KeyCode {
+        fn from(code: ct::KeyCode) -> Self {
+            match code {
+                ct::KeyCode::Backspace => Self::Backspace,
+                ct::KeyCode::Enter => Self::Enter,
+                ct::KeyCode::Left => Self::Left,
+                ct::KeyCode::Right => Self::Right,
+                ct::KeyCode::Up => Self::Up,
+                ct::KeyCode::Down => Self::Down,
+                ct::KeyCode::Home => Self::Home,
+                ct::KeyCode::End => Self::End,
+                ct::KeyCode::PageUp => Self::PageUp,
+                ct::KeyCode::PageDown => Self::PageDown,
+                ct::KeyCode::Tab => Self::Tab,
+                ct::KeyCode::BackTab => Self::BackTab,
+                ct::KeyCode::Delete => Self::Delete,
+                ct::KeyCode::Insert => Self::Insert,
+                ct::KeyCode::F(n) => Self::F(n),
+                ct::KeyCode::Char(c) => Self::Char(c),
+                ct::KeyCode::Esc => Self::Esc,
+                ct::KeyCode::CapsLock => Self::CapsLock,
+                ct::KeyCode::ScrollLock => Self::ScrollLock,
+                ct::KeyCode::NumLock => Self::NumLock,
+                ct::KeyCode::PrintScreen => Self::PrintScreen,
+                ct::KeyCode::Pause => Self::Pause,
+                ct::KeyCode::Menu => Self::Menu,
+                ct::KeyCode::KeypadBegin => Self::KeypadBegin,
+                ct::KeyCode::Null | ct::KeyCode::Media(_) | ct::KeyCode::Modifier(_) => Self::Null,
+            }
+        }
+    }
+
+    impl From<ct::KeyModifiers> for KeyModifiers {
+        #[inline]
+        fn from(modifiers: ct::KeyModifiers) -> Self {
+            Self::from_bits_truncate(modifiers.bits())
+        }
+    }
+
+    impl From<ct::MouseEvent> for MouseEvent {
+        fn from(event: ct::MouseEvent) -> Self {
+            Self {
+                kind: match event.kind {
+                    ct::MouseEventKind::Down(b) => MouseEventKind::Down(b.into()),
+                    ct::MouseEventKind::Up(b) => MouseEventKind::Up(b.into()),
+                    ct::MouseEventKind::Drag(b) => MouseEventKind::Drag(b.into()),
+                    ct::MouseEventKind::Moved => MouseEventKind::Moved,
+                    ct::MouseEventKind::ScrollDown => MouseEventKind::ScrollDown,
+                    ct::MouseEventKind::ScrollUp => MouseEventKind::ScrollUp,
+                    ct::MouseEventKind::ScrollLeft => MouseEventKind::ScrollLeft,
+                    ct::MouseEventKind::ScrollRight => MouseEventKind::ScrollRight,
+                },
+                column: event.column,
+                row: event.row,
+                modifiers: event.modifiers.into(),
+            }
+        }
+    }
+
+    impl From<ct::MouseButton> for MouseButton {
+        #[inline]
+        fn from(button: ct::MouseButton) -> Self {
+            match button {
+                ct::MouseButton::Left => Self::Left,
+                ct::MouseButton::Right => Self::Right,
+                ct::MouseButton::Middle => Self::Middle,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "termion")]
+mod from_termion {
+    use super::*;
+    use termion::event::{Event as TEvent, Key, MouseButton as TButton, MouseEvent as TMouse};
+
+    impl TryFrom<TEvent> for TerminalEvent {
+        /// Raw bytes of the unsupported event.
+        type Error = Vec<u8>;
+
+        fn try_from(event: TEvent) -> Result<Self, Self::Error> {
+            match event {
+                TEvent::Key(key) => {
+                    let (code, modifiers) = match key {
+                        Key::Backspace => (KeyCode::Backspace, KeyModifiers::NONE),
+                        Key::Left => (KeyCode::Left, KeyModifiers::NONE),
+                        Key::Right => (KeyCode::Right, KeyModifiers::NONE),
+                        Key::Up => (KeyCode::Up, KeyModifiers::NONE),
+                        Key::Down => (KeyCode::Down, KeyModifiers::NONE),
+                        Key::Home => (KeyCode::Home, KeyModifiers::NONE),
+                        Key::End => (KeyCode::End, KeyModifiers::NONE),
+                        Key::PageUp => (KeyCode::PageUp, KeyModifiers::NONE),
+                        Key::PageDown => (KeyCode::PageDown, KeyModifiers::NONE),
+                        Key::BackTab => (KeyCode::BackTab, KeyModifiers::SHIFT),
+                        Key::Delete => (KeyCode::Delete, KeyModifiers::NONE),
+                        Key::Insert => (KeyCode::Insert, KeyModifiers::NONE),
+                        Key::F(n) => (KeyCode::F(n), KeyModifiers::NONE),
+                        Key::Char('\n') => (KeyCode::Enter, KeyModifiers::NONE),
+                        Key::Char('\t') => (KeyCode::Tab, KeyModifiers::NONE),
+                        Key::Char(c) if c.is_uppercase() => (KeyCode::Char(c), KeyModifiers::SHIFT),
+                        Key::Char(c) => (KeyCode::Char(c), KeyModifiers::NONE),
+                        Key::Alt(c) => (KeyCode::Char(c), KeyModifiers::ALT),
+                        Key::Ctrl(c) => (KeyCode::Char(c), KeyModifiers::CONTROL),
+                        Key::Esc => (KeyCode::Esc, KeyModifiers::NONE),
+                        _ => (KeyCode::Null, KeyModifiers::NONE),
+                    };
+
+                    Ok(Self::Key(KeyEvent::new(code, modifiers)))
+                }
+                TEvent::Mouse(mouse) => {
+                    let (kind, column, row) = match mouse {
+                        TMouse::Press(button, x, y) => {
+                            let kind = match button {
+                                TButton::Left => MouseEventKind::Down(MouseButton::Left),
+                                TButton::Right => MouseEventKind::Down(MouseButton::Right),
+                                TButton::Middle => MouseEventKind::Down(MouseButton::Middle),
+                                TButton::WheelUp => MouseEventKind::ScrollUp,
+                                TButton::WheelDown => MouseEventKind::ScrollDown,
+                            };
+                            (kind, x, y)
+                        }
+                        // Termion doesn't report which button was released or held.
+                        TMouse::Release(x, y) => (MouseEventKind::Up(MouseButton::Left), x, y),
+                        TMouse::Hold(x, y) => (MouseEventKind::Drag(MouseButton::Left), x, y),
+                    };
+
+                    // Termion coordinates are one-based.
+                    Ok(Self::Mouse(MouseEvent {
+                        kind,
+                        column: column.saturating_sub(1),
+                        row: row.saturating_sub(1),
+                        modifiers: KeyModifiers::NONE,
+                    }))
+                }
+                TEvent::Unsupported(bytes) => Err(bytes),
+            }
+        }
+    }
+}
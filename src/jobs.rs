@@ -1,6 +1,22 @@
-use crate::{Callback, Compositor, Resume};
-use std::future::Future;
-use tokio::sync::mpsc;
+use crate::{Callback, Component, Compositor, Event, Id, Request, Resume, Runtime};
+use futures_channel::{mpsc, oneshot};
+use futures_util::{
+    future::{AbortHandle, Abortable, BoxFuture, FutureExt},
+    Stream, StreamExt,
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+pub use futures_util::future::Aborted;
 
 mod sealed {
     pub trait Sealed<S, E> {}
@@ -37,32 +53,385 @@ impl<S, E, C: IntoCallback<S, E>> IntoCallback<S, E> for Option<C> {
     }
 }
 
+/// Handle to a spawned job. Dropping it detaches the job, it keeps running.
+///
+/// Awaiting the handle waits until the job finishes and its callback is queued,
+/// it resolves to `Err(Aborted)` if the job was aborted or its callback was dropped, e.g. because
+/// the compositor has exited or the job was spawned through a [`Lens`](crate::Lens).
+pub struct JobHandle {
+    abort: AbortHandle,
+    finished: Arc<AtomicBool>,
+    done: oneshot::Receiver<()>,
+}
+
+impl JobHandle {
+    /// Aborts the job, its callback is not run even if the job has already finished.
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+
+    /// Returns `true` if the job has finished or was aborted.
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire) || self.abort.is_aborted()
+    }
+}
+
+impl Future for JobHandle {
+    type Output = Result<(), Aborted>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.done)
+            .poll(cx)
+            .map(|result| result.map_err(|_| Aborted))
+    }
+}
+
+/// Counts a job as running until it is dropped, so aborted jobs are counted as well.
+struct Running(Arc<AtomicUsize>);
+
+impl Running {
+    fn new(count: &Arc<AtomicUsize>) -> Self {
+        count.fetch_add(1, Ordering::Relaxed);
+        Self(count.clone())
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+struct OwnedJob {
+    abort: AbortHandle,
+    finished: Arc<AtomicBool>,
+}
+
+/// Forwards events and requests of jobs of a projected component, see [`Jobs::project`].
+type Forward<E> = Arc<dyn Fn(Resume<(), E>) -> bool + Send + Sync>;
+
+/// Where jobs send their callbacks and events.
+pub(crate) enum Sender<S, E> {
+    Compositor(mpsc::UnboundedSender<Resume<S, E>>),
+    /// Callbacks are dropped, there is no compositor over the state.
+    Projected(Forward<E>),
+}
+
+impl<S, E> Clone for Sender<S, E> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Compositor(sender) => Self::Compositor(sender.clone()),
+            Self::Projected(forward) => Self::Projected(forward.clone()),
+        }
+    }
+}
+
+impl<S, E> Sender<S, E> {
+    /// Returns `false` if the compositor has exited or the callback was dropped.
+    pub(crate) fn send(&self, resume: Resume<S, E>) -> bool {
+        match (self, resume) {
+            (Self::Compositor(sender), resume) => sender.unbounded_send(resume).is_ok(),
+            (Self::Projected(forward), Resume::Event(event)) => forward(Resume::Event(event)),
+            (Self::Projected(forward), Resume::Request(request)) => {
+                forward(Resume::Request(request))
+            }
+            (Self::Projected(_), Resume::JobCallback(_)) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("callback of a job spawned through a lens or `MapEvent` is dropped");
+                false
+            }
+        }
+    }
+}
+
 /// Job system, allows to execute futures and run callbacks when job is finished.
 pub struct Jobs<S, E> {
-    sender: mpsc::Sender<Resume<S, E>>,
+    sender: Sender<S, E>,
+    runtime: Arc<dyn Runtime>,
+    /// Jobs aborted when their owner is unmounted.
+    owned: Arc<Mutex<HashMap<Id, Vec<OwnedJob>>>>,
+    running: Arc<AtomicUsize>,
+}
+
+impl<S, E> Clone for Jobs<S, E> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            runtime: self.runtime.clone(),
+            owned: self.owned.clone(),
+            running: self.running.clone(),
+        }
+    }
 }
 
 impl<S: 'static, E: 'static> Jobs<S, E> {
-    pub(crate) fn new(sender: mpsc::Sender<Resume<S, E>>) -> Self {
-        Self { sender }
+    pub(crate) fn new(
+        sender: mpsc::UnboundedSender<Resume<S, E>>,
+        runtime: Arc<dyn Runtime>,
+    ) -> Self {
+        Self {
+            sender: Sender::Compositor(sender),
+            runtime,
+            owned: Arc::default(),
+            running: Arc::default(),
+        }
     }
 
-    pub fn spawn<C, F>(&self, job: F)
+    /// Returns the number of jobs that haven't finished yet.
+    pub fn running(&self) -> usize {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Spawns a job on the runtime, the callback it returns is run on the compositor once it finishes.
+    /// Callbacks of jobs spawned by components in a [`Lens`](crate::Lens) or [`MapEvent`](crate::MapEvent)
+    /// never run, their handles resolve to `Err(Aborted)`.
+    pub fn spawn<C, F>(&self, job: F) -> JobHandle
     where
         C: IntoCallback<S, E>,
         F: Future<Output = C> + Send + 'static,
         S: Send + 'static,
         E: Send + 'static,
+    {
+        let (job, handle) = self.prepare(job);
+        self.runtime.spawn(Box::pin(job));
+        handle
+    }
+
+    /// Same as [`Self::spawn`] but the job runs on the thread running the compositor,
+    /// so neither the job nor the state have to be `Send`.
+    ///
+    /// # Panics
+    /// Panics if called outside of the compositor's thread, or if the runtime doesn't support local
+    /// jobs, like the one used by `run_blocking`. See [`Runtime::spawn_local`].
+    pub fn spawn_local<C, F>(&self, job: F) -> JobHandle
+    where
+        C: IntoCallback<S, E>,
+        F: Future<Output = C> + 'static,
+    {
+        let (job, handle) = self.prepare(job);
+        self.runtime.spawn_local(Box::pin(job));
+        handle
+    }
+
+    /// Wraps `job` so it can be aborted and queues its callback once it finishes.
+    fn prepare<C, F>(&self, job: F) -> (impl Future<Output = ()>, JobHandle)
+    where
+        C: IntoCallback<S, E>,
+        F: Future<Output = C>,
+    {
+        let sender = self.sender.clone();
+        let (abort, registration) = AbortHandle::new_pair();
+        let finished = Arc::new(AtomicBool::new(false));
+        let (done_tx, done) = oneshot::channel();
+
+        let job = Abortable::new(job, registration);
+        let (aborted, job_finished) = (abort.clone(), finished.clone());
+        let running = Running::new(&self.running);
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!("job", stream = false);
+        let job = async move {
+            let _running = running;
+            let Ok(output) = job.await else {
+                return;
+            };
+            if !queue(&sender, &aborted, output) {
+                return;
+            }
+            job_finished.store(true, Ordering::Release);
+            _ = done_tx.send(());
+        };
+        #[cfg(feature = "tracing")]
+        let job = tracing::Instrument::instrument(job, span);
+
+        let handle = JobHandle {
+            abort,
+            finished,
+            done,
+        };
+        (job, handle)
+    }
+
+    /// Spawns a job that yields callbacks as it progresses, e.g. to update a progress bar, each one is run
+    /// on the compositor as soon as it is yielded. The handle resolves once the stream ends.
+    pub fn spawn_stream<C, St>(&self, job: St) -> JobHandle
+    where
+        C: IntoCallback<S, E>,
+        St: Stream<Item = C> + Send + 'static,
+        S: Send + 'static,
+        E: Send + 'static,
     {
         let sender = self.sender.clone();
+        let (abort, registration) = AbortHandle::new_pair();
+        let finished = Arc::new(AtomicBool::new(false));
+        let (done_tx, done) = oneshot::channel();
 
-        tokio::spawn(async move {
-            if let Some(callback) = job.await.into_callback() {
-                sender
-                    .send(Resume::JobCallback(callback))
-                    .await
-                    .expect("jobs closed");
+        let mut job = Box::pin(Abortable::new(job, registration));
+        let (aborted, job_finished) = (abort.clone(), finished.clone());
+        let running = Running::new(&self.running);
+        let job = async move {
+            let _running = running;
+            while let Some(output) = job.next().await {
+                if !queue(&sender, &aborted, output) {
+                    return;
+                }
+            }
+            if job.is_aborted() {
+                return;
             }
+            job_finished.store(true, Ordering::Release);
+            _ = done_tx.send(());
+        };
+        #[cfg(feature = "tracing")]
+        let job = tracing::Instrument::instrument(job, tracing::debug_span!("job", stream = true));
+        self.runtime.spawn(Box::pin(job));
+
+        JobHandle {
+            abort,
+            finished,
+            done,
+        }
+    }
+
+    /// Same as [`Self::spawn`] but the job is aborted when the component with `owner` id is unmounted.
+    pub fn spawn_owned<C, F>(&self, owner: Id, job: F) -> JobHandle
+    where
+        C: IntoCallback<S, E>,
+        F: Future<Output = C> + Send + 'static,
+        S: Send + 'static,
+        E: Send + 'static,
+    {
+        let handle = self.spawn(job);
+
+        let mut owned = self.owned.lock().unwrap_or_else(PoisonError::into_inner);
+        let jobs = owned.entry(owner).or_default();
+        jobs.retain(|job| !job.finished.load(Ordering::Acquire));
+        jobs.push(OwnedJob {
+            abort: handle.abort.clone(),
+            finished: handle.finished.clone(),
         });
+
+        handle
     }
+
+    /// Aborts jobs owned by the component with `owner` id.
+    pub(crate) fn abort_owned(&self, owner: Id) {
+        let mut owned = self.owned.lock().unwrap_or_else(PoisonError::into_inner);
+        for job in owned.remove(&owner).into_iter().flatten() {
+            job.abort.abort();
+        }
+    }
+
+    /// Asks the topmost component with `id` for data, `query` is called with it on the compositor.
+    /// Resolves to `None` if there is no such component or it isn't a `C`, see [`Context::query`](crate::Context::query).
+    pub fn query<C, R>(
+        &self,
+        id: Id,
+        query: impl FnOnce(&C) -> R + Send + 'static,
+    ) -> impl Future<Output = Option<R>> + Send + 'static
+    where
+        C: Component<S, E>,
+        R: Send + 'static,
+    {
+        let (callback, answer) = query_callback(id, query);
+        // Compositor has exited, answer resolves to `None`.
+        _ = self.sender.send(Resume::JobCallback(callback));
+        answer
+    }
+
+    /// Emits user event `event`, it is passed to components like events from streams.
+    /// Dropped if the compositor has exited.
+    pub fn emit(&self, event: E) {
+        _ = self.sender.send(Resume::Event(Event::User(event)));
+    }
+
+    /// Returns channel of the compositor, e.g. to pass callbacks from outside of jobs.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn sender(&self) -> Sender<S, E> {
+        self.sender.clone()
+    }
+
+    /// Returns jobs for components over the part `T` of the state or with user events `E2`,
+    /// see [`Lens`](crate::Lens) and [`MapEvent`](crate::MapEvent). Events they emit are converted
+    /// with `map` and reach the compositor like suspended closures, callbacks are dropped since there is
+    /// no compositor for them.
+    pub(crate) fn project<T: 'static, E2: 'static>(
+        &self,
+        map: impl Fn(E2) -> E + Send + Sync + 'static,
+    ) -> Jobs<T, E2>
+    where
+        E: Send,
+    {
+        let parent = self.sender.clone();
+        let sender = Sender::Projected(Arc::new(move |resume: Resume<(), E2>| match resume {
+            Resume::Event(event) => parent.send(Resume::Event(event.map(&map))),
+            Resume::Request(request) => parent.send(Resume::Request(request)),
+            // Callbacks are over the projected state, there is nothing to run them on.
+            Resume::JobCallback(_) => false,
+        }));
+        Jobs {
+            sender,
+            runtime: self.runtime.clone(),
+            owned: self.owned.clone(),
+            running: self.running.clone(),
+        }
+    }
+
+    /// Returns a future that resolves after `duration` using the compositor's runtime.
+    pub fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        self.runtime.sleep(duration)
+    }
+
+    /// Runs `f` with the terminal restored, e.g. to open `$EDITOR` or a pager, then sets the terminal up
+    /// again and redraws. Events aren't handled until `f` returns, the returned future resolves to its output.
+    /// Resolves to `None` if the compositor has stopped before running `f`, see [`Context::suspend`](crate::Context::suspend).
+    pub fn suspend<R: Send + 'static>(
+        &self,
+        f: impl FnOnce() -> R + Send + 'static,
+    ) -> BoxFuture<'static, Option<R>> {
+        let (tx, rx) = oneshot::channel();
+        _ = self
+            .sender
+            .send(Resume::Request(Request::Suspend(Box::new(move || {
+                _ = tx.send(f())
+            }))));
+        rx.map(Result::ok).boxed()
+    }
+}
+
+/// Sends callback returned by a job to the compositor, it is skipped if the job is aborted before it runs.
+/// Returns `false` if the callback was dropped.
+fn queue<S: 'static, E: 'static>(
+    sender: &Sender<S, E>,
+    aborted: &AbortHandle,
+    output: impl IntoCallback<S, E>,
+) -> bool {
+    let Some(callback) = output.into_callback() else {
+        return true;
+    };
+    let aborted = aborted.clone();
+    let callback: Callback<S, E> = Box::new(move |comp| {
+        if !aborted.is_aborted() {
+            callback(comp);
+        }
+    });
+    sender.send(Resume::JobCallback(callback))
+}
+
+/// Creates callback running `query` on the component with `id` and the future resolving to its answer.
+pub(crate) fn query_callback<S: 'static, E: 'static, C, R>(
+    id: Id,
+    query: impl FnOnce(&C) -> R + Send + 'static,
+) -> (
+    Callback<S, E>,
+    impl Future<Output = Option<R>> + Send + 'static,
+)
+where
+    C: Component<S, E>,
+    R: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    let callback: Callback<S, E> = Box::new(move |comp| {
+        _ = tx.send(comp.get::<C>(id).map(query));
+    });
+    (callback, rx.map(|answer| answer.ok().flatten()))
 }
@@ -0,0 +1,310 @@
+use crate::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers, ParseKeyError};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Named action bound to keys in a [`Keymap`], e.g. `save`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Action(Cow<'static, str>);
+
+impl Action {
+    pub const fn new(name: &'static str) -> Self {
+        Self(Cow::Borrowed(name))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&'static str> for Action {
+    fn from(name: &'static str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for Action {
+    fn from(name: String) -> Self {
+        Self(Cow::Owned(name))
+    }
+}
+
+impl PartialEq<str> for Action {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Action {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Sequence of keys bound to an action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub keys: Vec<KeyEvent>,
+    pub action: Action,
+}
+
+/// Result of passing a key to [`Keymap::feed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyMatch {
+    /// Key completed a binding.
+    Action(Action),
+    /// Key started or continued a chord.
+    Pending,
+    /// Key isn't bound.
+    None,
+}
+
+/// Error loading bindings with [`Keymap::load`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapError {
+    /// Line of the error, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+/// Binds keys to named actions, which the compositor passes to [`Component::handle_action`](crate::Component::handle_action)
+/// instead of the keys, see [`Compositor::with_keymap`](crate::Compositor::with_keymap).
+///
+/// Keys are written like `ctrl-s` and separated by spaces for chords like `g g`, see [`KeyEvent`]'s [`FromStr`](std::str::FromStr).
+/// Keys of a chord have to be pressed within the chord timeout of each other. A binding that
+/// is a prefix of another one takes precedence over it.
+///
+/// ```
+/// # use gland::{Action, Keymap, KeyMatch, KeyCode, KeyEvent};
+/// let mut keymap = Keymap::default()
+///     .with_action("top", "Go to the top")
+///     .with_binding("g g", "top");
+/// keymap.load("# Vim users feel at home\nctrl-s = save").unwrap();
+///
+/// assert_eq!(keymap.feed(&KeyEvent::from(KeyCode::Char('g'))), KeyMatch::Pending);
+/// assert_eq!(keymap.feed(&KeyEvent::from(KeyCode::Char('g'))), KeyMatch::Action(Action::new("top")));
+/// assert_eq!(keymap.feed(&"ctrl-s".parse().unwrap()), KeyMatch::Action(Action::new("save")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Declared actions with their descriptions.
+    actions: BTreeMap<Action, String>,
+    bindings: Vec<Binding>,
+    chord_timeout: Duration,
+    /// Keys of the chord typed so far.
+    pending: Vec<KeyEvent>,
+    last_key: Option<Instant>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            actions: BTreeMap::new(),
+            bindings: Vec::new(),
+            chord_timeout: Duration::from_secs(1),
+            pending: Vec::new(),
+            last_key: None,
+        }
+    }
+}
+
+/// Builder functions
+impl Keymap {
+    /// Declares an action with a description shown to the user.
+    pub fn with_action(
+        mut self,
+        action: impl Into<Action>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.declare(action, description);
+        self
+    }
+
+    /// Binds `keys` to `action`.
+    ///
+    /// # Panics
+    ///
+    /// If `keys` are invalid, use [`Self::bind`] for keys that aren't known in advance.
+    pub fn with_binding(mut self, keys: &str, action: impl Into<Action>) -> Self {
+        if let Err(e) = self.bind(keys, action) {
+            panic!("{e}");
+        }
+        self
+    }
+
+    /// Sets the time within which keys of a chord have to be pressed. Default is 1 second.
+    pub fn with_chord_timeout(mut self, timeout: Duration) -> Self {
+        self.chord_timeout = timeout;
+        self
+    }
+}
+
+/// Non-builder functions
+impl Keymap {
+    /// Declares an action with a description shown to the user, replacing the previous description.
+    pub fn declare(&mut self, action: impl Into<Action>, description: impl Into<String>) {
+        self.actions.insert(action.into(), description.into());
+    }
+
+    /// Binds `keys` to `action`, replacing the action previously bound to them.
+    pub fn bind(&mut self, keys: &str, action: impl Into<Action>) -> Result<(), ParseKeyError> {
+        let keys = parse_keys(keys)?;
+        let action = action.into();
+        match self.bindings.iter_mut().find(|b| b.keys == keys) {
+            Some(binding) => binding.action = action,
+            None => self.bindings.push(Binding { keys, action }),
+        }
+        Ok(())
+    }
+
+    /// Removes the binding of `keys`, returns `false` if they weren't bound.
+    pub fn unbind(&mut self, keys: &str) -> Result<bool, ParseKeyError> {
+        let keys = parse_keys(keys)?;
+        let len = self.bindings.len();
+        self.bindings.retain(|b| b.keys != keys);
+        Ok(self.bindings.len() != len)
+    }
+
+    /// Removes all bindings of `action`.
+    pub fn unbind_action(&mut self, action: &Action) {
+        self.bindings.retain(|b| b.action != *action);
+    }
+
+    /// Loads bindings from lines like `ctrl-s = save`, overriding existing ones, e.g. from a user's config.
+    /// Lines without an action like `ctrl-s =` unbind the keys, empty lines and lines starting with `#` are skipped.
+    /// Bindings before an invalid line are kept.
+    pub fn load(&mut self, config: &str) -> Result<(), KeymapError> {
+        for (i, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| KeymapError {
+                line: i + 1,
+                message,
+            };
+
+            let Some((keys, action)) = line.rsplit_once('=') else {
+                return Err(error(format!("expected `keys = action`, got `{line}`")));
+            };
+            let (keys, action) = (keys.trim(), action.trim());
+            let result = if action.is_empty() {
+                self.unbind(keys).map(drop)
+            } else {
+                self.bind(keys, action.to_owned())
+            };
+            result.map_err(|e| error(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Returns declared actions with their descriptions.
+    pub fn actions(&self) -> impl Iterator<Item = (&Action, &str)> {
+        self.actions.iter().map(|(a, d)| (a, d.as_str()))
+    }
+
+    /// Returns description of a declared action.
+    pub fn description(&self, action: &Action) -> Option<&str> {
+        self.actions.get(action).map(String::as_str)
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// Returns key sequences bound to `action`.
+    pub fn keys_for<'a>(&'a self, action: &'a Action) -> impl Iterator<Item = &'a [KeyEvent]> {
+        self.bindings
+            .iter()
+            .filter(move |b| b.action == *action)
+            .map(|b| b.keys.as_slice())
+    }
+
+    /// Passes a pressed key, returns the action it completes. Releases are ignored.
+    ///
+    /// If a chord is interrupted by a key that doesn't continue it, that key is matched on its own.
+    pub fn feed(&mut self, key: &KeyEvent) -> KeyMatch {
+        if key.kind == KeyEventKind::Release {
+            return KeyMatch::None;
+        }
+        if self
+            .last_key
+            .is_some_and(|last| last.elapsed() > self.chord_timeout)
+        {
+            self.pending.clear();
+        }
+
+        let chord = !self.pending.is_empty();
+        self.pending.push(normalize(*key));
+
+        let bound = self.bindings.iter().find(|b| b.keys == self.pending);
+        if let Some(action) = bound.map(|b| b.action.clone()) {
+            self.reset();
+            return KeyMatch::Action(action);
+        }
+        let prefix = self
+            .bindings
+            .iter()
+            .any(|b| b.keys.starts_with(&self.pending));
+
+        if prefix {
+            self.last_key = Some(Instant::now());
+            return KeyMatch::Pending;
+        }
+        self.reset();
+        if chord {
+            return self.feed(key);
+        }
+        KeyMatch::None
+    }
+
+    /// Forgets keys of an unfinished chord.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.last_key = None;
+    }
+}
+
+fn parse_keys(keys: &str) -> Result<Vec<KeyEvent>, ParseKeyError> {
+    let keys = keys
+        .split_whitespace()
+        .map(|k| k.parse().map(normalize))
+        .collect::<Result<Vec<_>, _>>()?;
+    if keys.is_empty() {
+        return Err(ParseKeyError(String::new()));
+    }
+    Ok(keys)
+}
+
+/// Makes keys comparable, terminals don't agree on how to report shifted characters.
+pub(crate) fn normalize(mut key: KeyEvent) -> KeyEvent {
+    key.kind = KeyEventKind::Press;
+    if let KeyCode::Char(c) = key.code {
+        if c.is_uppercase() {
+            key.modifiers |= KeyModifiers::SHIFT;
+        } else if key.modifiers.contains(KeyModifiers::SHIFT) && c.is_lowercase() {
+            key.code = KeyCode::Char(c.to_uppercase().next().unwrap_or(c));
+        } else if !c.is_alphabetic() && !c.is_whitespace() {
+            // Symbols like `?` already are shifted.
+            key.modifiers -= KeyModifiers::SHIFT;
+        }
+    }
+    key
+}
@@ -0,0 +1,114 @@
+use crate::{Component, Id, LayerId};
+use std::collections::{BTreeMap, HashMap};
+
+/// Components of all layers in drawing order, indexed by id so lookups don't scan layers.
+pub(crate) struct Layers<S, E> {
+    layers: BTreeMap<LayerId, Vec<Box<dyn Component<S, E>>>>,
+    /// Position of every component in its layer.
+    index: HashMap<(LayerId, Id), usize>,
+}
+
+impl<S, E> Default for Layers<S, E> {
+    fn default() -> Self {
+        Self {
+            layers: BTreeMap::new(),
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<S: 'static, E: 'static> Layers<S, E> {
+    /// Returns the number of components in the layer.
+    pub(crate) fn len(&self, layer_id: LayerId) -> usize {
+        self.layers.get(&layer_id).map_or(0, Vec::len)
+    }
+
+    pub(crate) fn contains(&self, layer_id: LayerId, id: Id) -> bool {
+        self.index.contains_key(&(layer_id, id))
+    }
+
+    pub(crate) fn position(&self, layer_id: LayerId, id: Id) -> Option<usize> {
+        self.index.get(&(layer_id, id)).copied()
+    }
+
+    pub(crate) fn get(&self, layer_id: LayerId, id: Id) -> Option<&dyn Component<S, E>> {
+        let position = self.position(layer_id, id)?;
+        Some(&*self.layers[&layer_id][position])
+    }
+
+    pub(crate) fn get_mut(
+        &mut self,
+        layer_id: LayerId,
+        id: Id,
+    ) -> Option<&mut Box<dyn Component<S, E>>> {
+        let position = self.position(layer_id, id)?;
+        self.layers.get_mut(&layer_id).map(|l| &mut l[position])
+    }
+
+    /// Returns the topmost layer with a component with `id` among layers accepted by `filter`.
+    pub(crate) fn find(&self, id: Id, mut filter: impl FnMut(LayerId) -> bool) -> Option<LayerId> {
+        self.layers
+            .keys()
+            .rev()
+            .copied()
+            .find(|layer_id| filter(*layer_id) && self.contains(*layer_id, id))
+    }
+
+    /// Inserts a component at `index`, or at the end if it is past the end.
+    /// The layer must not have a component with the same id.
+    pub(crate) fn insert(
+        &mut self,
+        layer_id: LayerId,
+        index: usize,
+        component: Box<dyn Component<S, E>>,
+    ) {
+        debug_assert!(!self.contains(layer_id, component.id()));
+
+        let layer = self.layers.entry(layer_id).or_default();
+        let index = index.min(layer.len());
+        layer.insert(index, component);
+        self.reindex(layer_id, index);
+    }
+
+    pub(crate) fn remove(&mut self, layer_id: LayerId, id: Id) -> Option<Box<dyn Component<S, E>>> {
+        let position = self.index.remove(&(layer_id, id))?;
+        let component = self.layers.get_mut(&layer_id)?.remove(position);
+        self.reindex(layer_id, position);
+        Some(component)
+    }
+
+    /// Moves a component to `index` within its layer, returns `false` if there is no such component.
+    pub(crate) fn move_to(&mut self, layer_id: LayerId, id: Id, index: usize) -> bool {
+        let Some(component) = self.remove(layer_id, id) else {
+            return false;
+        };
+        self.insert(layer_id, index, component);
+        true
+    }
+
+    /// Iterates over layers from the bottom up.
+    pub(crate) fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (LayerId, &[Box<dyn Component<S, E>>])> {
+        self.layers.iter().map(|(id, l)| (*id, l.as_slice()))
+    }
+
+    /// Same as [`Self::iter`] but with mutable components, they can't be reordered.
+    pub(crate) fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (LayerId, &mut [Box<dyn Component<S, E>>])> {
+        self.layers
+            .iter_mut()
+            .map(|(id, l)| (*id, l.as_mut_slice()))
+    }
+
+    /// Updates positions of components in the layer starting at `from`.
+    fn reindex(&mut self, layer_id: LayerId, from: usize) {
+        let Some(layer) = self.layers.get(&layer_id) else {
+            return;
+        };
+        for (i, component) in layer.iter().enumerate().skip(from) {
+            self.index.insert((layer_id, component.id()), i);
+        }
+    }
+}
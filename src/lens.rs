@@ -0,0 +1,332 @@
+use crate::{Action, Component, Context, CursorStyle, Event, Id, Jobs, Projection, Transition};
+use ratatui::{buffer::Buffer, layout::Rect};
+use std::{any::Any, marker::PhantomData};
+
+/// Mounts a component written against the part `T` of the state into a compositor over `S`,
+/// so reusable components don't depend on the whole state of the application.
+///
+/// The component reads and changes `T` through the projections, everything else it does through
+/// [`Context`] reaches the compositor, screens it pushes are wrapped in a lens too. Callbacks including
+/// those returned by jobs never run since there is no compositor over `T`: a warning is logged with
+/// the `tracing` feature and handles of such jobs resolve to `Err(Aborted)`. [`Context::query`] resolves to `None`.
+///
+/// ```
+/// # use gland::{Compositor, Component, Id, LayerId, Lens};
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// struct App {
+///     counter: u32,
+/// }
+///
+/// struct Counter;
+///
+/// impl Component<u32> for Counter {
+///     fn id(&self) -> Id {
+///         Id::new("counter")
+///     }
+///
+///     fn view(&self, area: Rect, buf: &mut Buffer, counter: &u32) {
+///         buf.set_string(area.x, area.y, counter.to_string(), Default::default());
+///     }
+/// }
+///
+/// let mut comp = Compositor::with_state(App { counter: 0 });
+/// _ = comp.insert_at(LayerId::MIDDLE, Lens::new(Counter, |app: &App| &app.counter, |app| &mut app.counter));
+/// ```
+pub struct Lens<S, T, C, E = ()> {
+    inner: C,
+    get: fn(&S) -> &T,
+    get_mut: fn(&mut S) -> &mut T,
+    /// Created once the component gets a context.
+    jobs: Option<Jobs<T, E>>,
+    _marker: PhantomData<fn(&E)>,
+}
+
+impl<S, T, C, E> Lens<S, T, C, E> {
+    pub fn new(inner: C, get: fn(&S) -> &T, get_mut: fn(&mut S) -> &mut T) -> Self {
+        Self {
+            inner,
+            get,
+            get_mut,
+            jobs: None,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<S: 'static, T: 'static, C, E: Send + 'static> Lens<S, T, C, E> {
+    /// Runs `f` with the component and a context over `T`.
+    fn project<R>(
+        &mut self,
+        cx: &mut Context<S, E>,
+        f: impl FnOnce(&mut C, &mut Context<T, E>) -> R,
+    ) -> R {
+        let jobs = self
+            .jobs
+            .get_or_insert_with(|| cx.jobs().project(|event| event));
+        let (get, get_mut) = (self.get, self.get_mut);
+        let wrap = move |screen| {
+            Box::new(Lens::new(Boxed(screen), get, get_mut)) as Box<dyn Component<S, E>>
+        };
+        let projection = Projection {
+            get,
+            get_mut,
+            wrap: &wrap,
+        };
+        let inner = &mut self.inner;
+        cx.project(jobs, projection, |cx| f(inner, cx))
+    }
+}
+
+impl<S, T, C, E> Component<S, E> for Lens<S, T, C, E>
+where
+    S: 'static,
+    T: 'static,
+    C: Component<T, E>,
+    E: Send + 'static,
+{
+    fn id(&self) -> Id {
+        self.inner.id()
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        self.inner.view(area, buf, (self.get)(state));
+    }
+
+    fn layout(&self, area: Rect) -> Rect {
+        self.inner.layout(area)
+    }
+
+    fn should_update(&self, state: &S) -> bool {
+        self.inner.should_update((self.get)(state))
+    }
+
+    fn focusable(&self) -> bool {
+        self.inner.focusable()
+    }
+
+    fn cursor(&self, area: Rect, state: &S) -> Option<(u16, u16)> {
+        self.inner.cursor(area, (self.get)(state))
+    }
+
+    fn cursor_style(&self, state: &S) -> Option<CursorStyle> {
+        self.inner.cursor_style((self.get)(state))
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.on_mount(cx));
+    }
+
+    fn on_unmount(&mut self, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.on_unmount(cx));
+    }
+
+    fn on_enter(&mut self, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.on_enter(cx));
+    }
+
+    fn on_leave(&mut self, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.on_leave(cx));
+    }
+
+    fn title(&self) -> Option<&str> {
+        self.inner.title()
+    }
+
+    fn actions(&self) -> &[Action] {
+        self.inner.actions()
+    }
+
+    fn handle_action(&mut self, action: &Action, cx: &mut Context<S, E>) -> bool {
+        self.project(cx, |inner, cx| inner.handle_action(action, cx))
+    }
+
+    fn enter_transition(&self) -> Option<Transition> {
+        self.inner.enter_transition()
+    }
+
+    fn exit_transition(&self) -> Option<Transition> {
+        self.inner.exit_transition()
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.handle_event(event, cx));
+    }
+
+    fn try_handle_event(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.project(cx, |inner, cx| inner.try_handle_event(event, cx))
+    }
+
+    fn handle_message(&mut self, message: Box<dyn Any>, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.handle_message(message, cx));
+    }
+}
+
+/// Screen pushed through a [`Lens`] or [`MapEvent`](crate::MapEvent), wrapped again to mount it
+/// in the compositor.
+pub(crate) struct Boxed<S, E>(pub(crate) Box<dyn Component<S, E>>);
+
+impl<S: 'static, E: 'static> Component<S, E> for Boxed<S, E> {
+    fn id(&self) -> Id {
+        self.0.id()
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        self.0.view(area, buf, state);
+    }
+
+    fn layout(&self, area: Rect) -> Rect {
+        self.0.layout(area)
+    }
+
+    fn should_update(&self, state: &S) -> bool {
+        self.0.should_update(state)
+    }
+
+    fn focusable(&self) -> bool {
+        self.0.focusable()
+    }
+
+    fn cursor(&self, area: Rect, state: &S) -> Option<(u16, u16)> {
+        self.0.cursor(area, state)
+    }
+
+    fn cursor_style(&self, state: &S) -> Option<CursorStyle> {
+        self.0.cursor_style(state)
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        self.0.on_mount(cx);
+    }
+
+    fn on_unmount(&mut self, cx: &mut Context<S, E>) {
+        self.0.on_unmount(cx);
+    }
+
+    fn on_enter(&mut self, cx: &mut Context<S, E>) {
+        self.0.on_enter(cx);
+    }
+
+    fn on_leave(&mut self, cx: &mut Context<S, E>) {
+        self.0.on_leave(cx);
+    }
+
+    fn title(&self) -> Option<&str> {
+        self.0.title()
+    }
+
+    fn actions(&self) -> &[Action] {
+        self.0.actions()
+    }
+
+    fn handle_action(&mut self, action: &Action, cx: &mut Context<S, E>) -> bool {
+        self.0.handle_action(action, cx)
+    }
+
+    fn enter_transition(&self) -> Option<Transition> {
+        self.0.enter_transition()
+    }
+
+    fn exit_transition(&self) -> Option<Transition> {
+        self.0.exit_transition()
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        self.0.handle_event(event, cx);
+    }
+
+    fn try_handle_event(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.0.try_handle_event(event, cx)
+    }
+
+    fn handle_message(&mut self, message: Box<dyn Any>, cx: &mut Context<S, E>) {
+        self.0.handle_message(message, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Compositor, LayerId};
+
+    struct App {
+        counter: u32,
+    }
+
+    /// Asks for confirmation and pushes a screen on the first tick.
+    struct Asker {
+        asked: bool,
+    }
+
+    impl Component<u32> for Asker {
+        fn id(&self) -> Id {
+            Id::new("asker")
+        }
+
+        fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &u32) {}
+
+        fn handle_event(&mut self, _event: &mut Event, cx: &mut Context<u32>) {
+            if !std::mem::replace(&mut self.asked, true) {
+                drop(cx.confirm("sure?"));
+                cx.push_screen(Counter);
+            }
+        }
+    }
+
+    /// Counts ticks in the projected state.
+    struct Counter;
+
+    impl Component<u32> for Counter {
+        fn id(&self) -> Id {
+            Id::new("counter")
+        }
+
+        fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &u32) {}
+
+        fn handle_event(&mut self, event: &mut Event, cx: &mut Context<u32>) {
+            if let Event::Tick = event {
+                *cx.state_mut() += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn dialogs_and_screens_reach_compositor() {
+        let mut comp = Compositor::with_state(App { counter: 0 });
+        let asker = Lens::new(
+            Asker { asked: false },
+            |app: &App| &app.counter,
+            |app| &mut app.counter,
+        );
+        _ = comp.insert_at(LayerId::BACKGROUND, asker);
+
+        comp.dispatch(Event::Tick);
+        let popups = comp
+            .iter()
+            .filter(|(layer_id, ..)| *layer_id == LayerId::POPUP);
+        assert_eq!(popups.count(), 1);
+        assert_eq!(comp.screen_depth(), 1);
+
+        comp.dispatch(Event::Tick);
+        assert_eq!(comp.state().counter, 1);
+    }
+}
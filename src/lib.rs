@@ -1,4 +1,3 @@
-#![feature(trait_upcasting)]
 #![feature(doc_cfg)]
 
 use ratatui::prelude::{Buffer, Rect};
@@ -6,14 +5,109 @@ use std::{
     any::Any,
     fmt,
     hash::{Hash, Hasher},
+    marker::PhantomData,
     num::NonZeroU64,
+    time::Duration,
 };
 use twox_hash::XxHash64;
 
+mod error;
+pub use error::{Error, ErrorAction, ErrorPopup};
 mod jobs;
 pub use jobs::*;
+mod anim;
 mod compositor;
+mod layers;
+pub use anim::{Animated, AnimationStep, Easing, Edge, Effect, Lerp, Transition};
 pub use compositor::*;
+mod input;
+pub use input::*;
+mod keymap;
+pub use keymap::*;
+mod commands;
+pub use commands::{Command, CommandPalette};
+mod help;
+pub use help::{HelpPopup, HelpSection};
+mod a11y;
+pub use a11y::{Announcement, Priority};
+mod caps;
+pub use caps::*;
+mod clipboard;
+pub use clipboard::Clipboard;
+mod window;
+pub use window::{CursorShape, CursorStyle, PointerShape, Progress};
+mod windows;
+pub use windows::{Window, WindowManager};
+mod container;
+pub use container::Container;
+mod lens;
+pub use lens::Lens;
+mod map_event;
+pub use map_event::MapEvent;
+mod widget_component;
+pub use widget_component::{stateful_widget_component, widget_component};
+mod program;
+pub use program::Program;
+mod memory;
+pub use memory::Memory;
+mod resources;
+pub use resources::Resources;
+mod export;
+mod watch;
+pub use export::ExportFormat;
+mod theme;
+pub use theme::*;
+mod plugin;
+pub use plugin::Plugin;
+mod locale;
+pub use locale::*;
+mod ansi;
+mod dialog;
+mod diff;
+#[cfg(any(
+    feature = "event-stream",
+    all(feature = "blocking", not(target_arch = "wasm32"))
+))]
+mod quirks;
+mod terminal;
+pub use ansi::{AnsiBackend, AnsiOutput};
+mod remote;
+pub use remote::*;
+mod session;
+pub use session::*;
+mod surface;
+pub use surface::*;
+mod runtime;
+pub use runtime::*;
+#[cfg(feature = "control")]
+mod control;
+#[cfg(feature = "persist")]
+mod persist;
+#[cfg(feature = "persist")]
+pub use persist::{PersistentComponent, Snapshot};
+#[cfg(feature = "debug")]
+mod debug;
+#[cfg(feature = "debug")]
+pub use debug::DebugOverlay;
+mod status;
+pub use status::{StatusAlign, StatusBar, StatusSegment};
+mod metrics;
+pub use metrics::{ComponentMetrics, Metrics, Timing};
+#[cfg(feature = "tracing")]
+mod log;
+#[cfg(feature = "tracing")]
+pub use log::{log_bridge, LogBridge, LogRecord, LogSubscriber, LogView};
+#[cfg(feature = "history")]
+#[doc(cfg(feature = "history"))]
+pub mod history;
+#[cfg(feature = "rhai")]
+#[doc(cfg(feature = "rhai"))]
+pub mod script;
+pub mod testing;
+#[cfg(feature = "wasm")]
+#[doc(cfg(feature = "wasm"))]
+pub mod wasm;
+pub mod widgets;
 
 /// LayerId describes elevation of the component.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -58,16 +152,79 @@ impl Id {
     }
 }
 
+/// Typed reference to a mounted component, returned by [`Compositor::insert_at`].
+pub struct Handle<C> {
+    layer_id: LayerId,
+    id: Id,
+    _component: PhantomData<fn() -> C>,
+}
+
+impl<C> Handle<C> {
+    pub(crate) fn new(layer_id: LayerId, id: Id) -> Self {
+        Self {
+            layer_id,
+            id,
+            _component: PhantomData,
+        }
+    }
+
+    /// Returns the layer the component was mounted at.
+    pub fn layer_id(&self) -> LayerId {
+        self.layer_id
+    }
+
+    /// Returns id of the component.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+}
+
+impl<C> Clone for Handle<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for Handle<C> {}
+
+impl<C> PartialEq for Handle<C> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.layer_id, self.id) == (other.layer_id, other.id)
+    }
+}
+
+impl<C> Eq for Handle<C> {}
+
+impl<C> fmt::Debug for Handle<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle")
+            .field("layer_id", &self.layer_id)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+/// Timing of an animation frame, so components can animate based on time without tracking it themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Number of the frame, increases by one with every animation frame.
+    pub frame: u64,
+    /// Time since the previous frame, or since the frame was requested if nothing was animating.
+    pub elapsed: Duration,
+}
+
 /// Event that can occur during runtime.
 #[non_exhaustive]
 pub enum Event<E = ()> {
     /// User event
     User(E),
     /// Event from the terminal
-    Terminal(crossterm::event::Event),
+    Terminal(TerminalEvent),
     /// Next tick occured without intermediate event
     Tick,
-    /// Exits compositor when emitted
+    /// Next animation frame, emitted while components request them, see [`Context::request_animation_frame`].
+    AnimationFrame(FrameInfo),
+    /// Exits compositor when emitted, unless a component consumes it, see [`Compositor::exit`].
     Exit,
     #[doc(hidden)]
     None,
@@ -81,6 +238,25 @@ impl<T> Default for Event<T> {
 }
 
 impl<T> Event<T> {
+    /// Returns the name of the variant, recorded in tracing spans.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::User(_) => "user",
+            Self::Terminal(TerminalEvent::Key(_)) => "key",
+            Self::Terminal(TerminalEvent::Mouse(_)) => "mouse",
+            Self::Terminal(TerminalEvent::Resize(..)) => "resize",
+            Self::Terminal(TerminalEvent::Paste(_)) => "paste",
+            Self::Terminal(TerminalEvent::FocusGained) => "focus_gained",
+            Self::Terminal(TerminalEvent::FocusLost) => "focus_lost",
+            Self::Terminal(TerminalEvent::Clipboard(_)) => "clipboard",
+            Self::Tick => "tick",
+            Self::AnimationFrame(_) => "animation_frame",
+            Self::Exit => "exit",
+            Self::None => "none",
+        }
+    }
+
     /// Checks if event is user.
     #[inline]
     pub fn is_user(&self) -> bool {
@@ -122,7 +298,7 @@ impl<T> Event<T> {
 
     /// Converts into terminal event ref on success.
     #[inline]
-    pub fn as_terminal(&self) -> Option<&crossterm::event::Event> {
+    pub fn as_terminal(&self) -> Option<&TerminalEvent> {
         match self {
             Event::Terminal(e) => Some(e),
             _ => None,
@@ -131,7 +307,7 @@ impl<T> Event<T> {
 
     /// Converts into terminal event mut ref on success.
     #[inline]
-    pub fn as_mut_terminal(&mut self) -> Option<&mut crossterm::event::Event> {
+    pub fn as_mut_terminal(&mut self) -> Option<&mut TerminalEvent> {
         match self {
             Event::Terminal(e) => Some(e),
             _ => None,
@@ -140,13 +316,66 @@ impl<T> Event<T> {
 
     /// Converts into terminal event on success.
     #[inline]
-    pub fn into_terminal(self) -> Result<crossterm::event::Event, Self> {
+    pub fn into_terminal(self) -> Result<TerminalEvent, Self> {
         match self {
             Event::Terminal(e) => Ok(e),
             _ => Err(self),
         }
     }
 
+    /// Returns the key event if this is one, releases included.
+    #[inline]
+    pub fn as_key(&self) -> Option<&KeyEvent> {
+        match self {
+            Event::Terminal(TerminalEvent::Key(key)) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Checks if `key` is pressed or repeated, e.g. `event.is_key(key!("ctrl-s"))`.
+    /// Shifted characters match regardless of how the terminal reports them, see [`key!`].
+    pub fn is_key(&self, key: KeyEvent) -> bool {
+        self.as_key().is_some_and(|k| {
+            k.kind != KeyEventKind::Release && keymap::normalize(*k) == keymap::normalize(key)
+        })
+    }
+
+    /// Checks if `code` is pressed or repeated without modifiers other than shift.
+    pub fn key_pressed(&self, code: KeyCode) -> bool {
+        self.as_key().is_some_and(|k| {
+            k.kind != KeyEventKind::Release
+                && k.code == code
+                && (k.modifiers - KeyModifiers::SHIFT).is_empty()
+        })
+    }
+
+    /// Checks if `ctrl` and `c` are pressed or repeated, e.g. `event.ctrl('c')`.
+    pub fn ctrl(&self, c: char) -> bool {
+        self.is_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+    }
+
+    /// Consumes the event if it is `key`, see [`Self::is_key`].
+    /// Returns `true` if it was consumed, e.g. `if event.consume_if_key(key!("q")) { ... }`.
+    pub fn consume_if_key(&mut self, key: KeyEvent) -> bool {
+        let matches = self.is_key(key);
+        if matches {
+            self.consume();
+        }
+        matches
+    }
+
+    /// Converts the user event with `f`, other events are kept as they are.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Event<U> {
+        match self {
+            Event::User(e) => Event::User(f(e)),
+            Event::Terminal(e) => Event::Terminal(e),
+            Event::Tick => Event::Tick,
+            Event::AnimationFrame(info) => Event::AnimationFrame(info),
+            Event::Exit => Event::Exit,
+            Event::None => Event::None,
+        }
+    }
+
     /// Checks if the event is consumed.
     #[inline]
     pub fn is_consumed(&self) -> bool {
@@ -170,6 +399,7 @@ impl<T: Clone> Clone for Event<T> {
             Event::Terminal(e) => Self::Terminal(e.clone()),
             Event::User(e) => Self::User(e.clone()),
             Event::Tick => Self::Tick,
+            Event::AnimationFrame(info) => Self::AnimationFrame(*info),
             Event::Exit => Self::Exit,
             Event::None => Self::None,
         }
@@ -179,9 +409,10 @@ impl<T: Clone> Clone for Event<T> {
 impl<T: fmt::Debug> fmt::Debug for Event<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Event::Terminal(e) => f.debug_tuple("Crossterm").field(e).finish(),
+            Event::Terminal(e) => f.debug_tuple("Terminal").field(e).finish(),
             Event::User(e) => f.debug_tuple("User").field(e).finish(),
             Event::Tick => write!(f, "Tick"),
+            Event::AnimationFrame(info) => f.debug_tuple("AnimationFrame").field(info).finish(),
             Event::Exit => write!(f, "Exit"),
             Event::None => write!(f, "None"),
         }
@@ -197,10 +428,104 @@ pub trait Component<S = (), E = ()>: Any {
     /// If component is root the `area` equals to the whole screen.
     fn view(&self, area: Rect, buf: &mut Buffer, state: &S);
 
+    /// Returns the area to draw this component in, given the `area` assigned by the layout of its layer,
+    /// see [`Compositor::set_layout`]. Defaults to the assigned area.
+    fn layout(&self, area: Rect) -> Rect {
+        area
+    }
+
+    /// Returns `false` if the component would draw the same as in the previous frame.
+    /// The compositor then reuses its previous output instead of calling [`Self::view`].
+    ///
+    /// Once a component has returned `false`, it is drawn into a separate buffer and can't read
+    /// what components below it have drawn.
+    fn should_update(&self, _state: &S) -> bool {
+        true
+    }
+
+    /// Returns `true` if the component takes part in focus cycling, see [`Compositor::focus_next`].
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    /// Returns the position of the terminal cursor within `area` while the component is focused,
+    /// e.g. after the last typed character of a text input. The cursor is hidden for `None`.
+    fn cursor(&self, _area: Rect, _state: &S) -> Option<(u16, u16)> {
+        None
+    }
+
+    /// Returns the style of the cursor while the component places it, see [`Self::cursor`].
+    /// `None` keeps the style set with [`Context::set_cursor_style`].
+    fn cursor_style(&self, _state: &S) -> Option<CursorStyle> {
+        None
+    }
+
+    /// Called after the component is inserted, or when the compositor starts running
+    /// if it was inserted before, e.g. to start jobs.
+    fn on_mount(&mut self, _cx: &mut Context<S, E>) {}
+
+    /// Called after the component is removed if it was mounted, e.g. to cancel jobs.
+    fn on_unmount(&mut self, _cx: &mut Context<S, E>) {}
+
+    /// Called when the component becomes the top screen, after it is pushed or the screen above it
+    /// is popped, see [`Compositor::push_screen`]. [`widgets::Tabs`] also call it when a tab is selected.
+    fn on_enter(&mut self, _cx: &mut Context<S, E>) {}
+
+    /// Called when the component stops being the top screen, before it is covered or removed,
+    /// or when its tab is switched away from.
+    fn on_leave(&mut self, _cx: &mut Context<S, E>) {}
+
+    /// Returns the title of the component, e.g. to group its actions under in the [`HelpPopup`].
+    fn title(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns actions the component handles, listed in the [`HelpPopup`] if they are bound.
+    fn actions(&self) -> &[Action] {
+        &[]
+    }
+
+    /// Handles an action bound in the compositor's keymap instead of its keys, see [`Keymap`].
+    /// Actions reach the focused component first, then components from the top layer down like events.
+    /// Returns `true` if the action is handled, which stops it and consumes its key.
+    fn handle_action(&mut self, _action: &Action, _cx: &mut Context<S, E>) -> bool {
+        false
+    }
+
+    /// Returns transition played when the component is mounted, it receives events right away.
+    /// Transitions are skipped with reduced motion.
+    fn enter_transition(&self) -> Option<Transition> {
+        None
+    }
+
+    /// Returns transition played when the component is removed, it is drawn until the transition ends
+    /// but doesn't receive events anymore.
+    fn exit_transition(&self) -> Option<Transition> {
+        None
+    }
+
+    /// Handles an event, consuming it stops it from reaching components below.
+    /// Mouse events are only delivered to components whose area contains the cursor.
     fn handle_event(&mut self, _event: &mut Event<E>, _cx: &mut Context<S, E>) {}
+
+    /// Fallible version of [`Self::handle_event`], the compositor calls this one.
+    /// Errors go to the handler set with [`Compositor::with_error_handler`] as [`Error::Component`],
+    /// without one they are shown in an [`ErrorPopup`] on [`LayerId::OVERLAY`].
+    fn try_handle_event(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.handle_event(event, cx);
+        Ok(())
+    }
+
+    /// Handles a message sent to the component with [`Context::send_to`], downcast it to read it.
+    fn handle_message(&mut self, _message: Box<dyn Any>, _cx: &mut Context<S, E>) {}
 }
 
 /// Forwards `handle_event` to multiple child components.
+/// [`Container`] also forwards mouse events only to the child under the cursor and tracks focus.
 #[macro_export]
 macro_rules! forward_handle_event {
     (@ret $($tail:tt)*) => {
@@ -222,7 +547,8 @@ macro_rules! forward_handle_event {
     };
 }
 
-/// Forwards `view` to multiple child components.
+/// Forwards `view` to multiple child components that need to be drawn,
+/// [`Container`] draws all of them.
 #[macro_export]
 macro_rules! forward_view {
     ($area:expr, $buf:expr, $state:expr, $($comp:expr),*) => {
@@ -254,3 +580,112 @@ macro_rules! id {
         <_ as $crate::Component<$state, $event>>::id($self)
     };
 }
+
+/// Parses a [`KeyEvent`] like `ctrl-shift-p`, see [`KeyEvent`]'s [`FromStr`](std::str::FromStr) for the syntax.
+/// Panics if the key is invalid.
+///
+/// ```
+/// # use gland::{key, Event, KeyCode, KeyEvent, KeyModifiers, TerminalEvent};
+/// let mut event: Event = Event::Terminal(TerminalEvent::Key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL)));
+/// assert!(event.ctrl('s'));
+/// assert!(!event.key_pressed(KeyCode::Char('s')));
+/// assert!(event.consume_if_key(key!("ctrl-s")));
+/// assert!(event.is_consumed());
+/// ```
+#[macro_export]
+macro_rules! key {
+    ($key:expr) => {
+        <$crate::KeyEvent as ::std::str::FromStr>::from_str($key).unwrap_or_else(|e| panic!("{e}"))
+    };
+}
+
+/// Declares components of a compositor in one block, mounting each at its layer.
+///
+/// Layers are names of [`LayerId`] constants or expressions in brackets. Components marked with `?`
+/// are `Option`s and are only mounted if they are `Some`. Mounting two components with the same id
+/// at the same layer panics.
+///
+/// ```
+/// # use gland::{ui, Component, Compositor, Id, LayerId};
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// struct Panel(&'static str);
+///
+/// impl Component for Panel {
+///     fn id(&self) -> Id {
+///         Id::new(self.0)
+///     }
+///
+///     fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &()) {}
+/// }
+///
+/// let welcome = false;
+/// let compositor = ui! {
+///     Compositor::new(),
+///     BACKGROUND: Panel("wallpaper"),
+///     FOREGROUND: Panel("main"),
+///     POPUP?: welcome.then(|| Panel("welcome")),
+///     [LayerId(42)]: Panel("custom"),
+/// };
+///
+/// let mounted = compositor.iter().map(|(layer_id, id, _)| (layer_id, id)).collect::<Vec<_>>();
+/// assert_eq!(
+///     mounted,
+///     [
+///         (LayerId::BACKGROUND, Id::new("wallpaper")),
+///         (LayerId(42), Id::new("custom")),
+///         (LayerId::FOREGROUND, Id::new("main")),
+///     ]
+/// );
+/// ```
+///
+/// ```should_panic
+/// # use gland::{ui, Component, Compositor, Id};
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// # struct Panel;
+/// # impl Component for Panel {
+/// #     fn id(&self) -> Id {
+/// #         Id::new("panel")
+/// #     }
+/// #     fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &()) {}
+/// # }
+/// // Panics with "`Panel` is already mounted".
+/// let compositor = ui! {
+///     Compositor::new(),
+///     BACKGROUND: Panel,
+///     BACKGROUND: Panel,
+/// };
+/// ```
+#[macro_export]
+macro_rules! ui {
+    (@mount $comp:ident;) => {};
+    (@mount $comp:ident; $layer:ident ?: $component:expr $(, $($rest:tt)*)?) => {
+        if let Some(component) = $component {
+            $crate::ui!(@insert $comp, $crate::LayerId::$layer, component, $component);
+        }
+        $crate::ui!(@mount $comp; $($($rest)*)?);
+    };
+    (@mount $comp:ident; $layer:ident: $component:expr $(, $($rest:tt)*)?) => {
+        $crate::ui!(@insert $comp, $crate::LayerId::$layer, $component, $component);
+        $crate::ui!(@mount $comp; $($($rest)*)?);
+    };
+    (@mount $comp:ident; [$layer:expr] ?: $component:expr $(, $($rest:tt)*)?) => {
+        if let Some(component) = $component {
+            $crate::ui!(@insert $comp, $layer, component, $component);
+        }
+        $crate::ui!(@mount $comp; $($($rest)*)?);
+    };
+    (@mount $comp:ident; [$layer:expr]: $component:expr $(, $($rest:tt)*)?) => {
+        $crate::ui!(@insert $comp, $layer, $component, $component);
+        $crate::ui!(@mount $comp; $($($rest)*)?);
+    };
+    (@insert $comp:ident, $layer:expr, $component:expr, $source:expr) => {
+        if $comp.insert_at($layer, $component).is_err() {
+            panic!("`{}` is already mounted", stringify!($source));
+        }
+    };
+    ($compositor:expr $(, $($body:tt)*)?) => {{
+        let mut compositor = $compositor;
+        $crate::ui!(@mount compositor; $($($body)*)?);
+        compositor
+    }};
+}
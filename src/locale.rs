@@ -0,0 +1,251 @@
+use std::{borrow::Cow, fmt};
+
+/// Argument of a translated message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arg<'a> {
+    String(&'a str),
+    Number(f64),
+}
+
+impl<'a> From<&'a str> for Arg<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::String(value)
+    }
+}
+
+impl<'a> From<&'a String> for Arg<'a> {
+    fn from(value: &'a String) -> Self {
+        Self::String(value)
+    }
+}
+
+macro_rules! impl_number_arg {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Arg<'_> {
+                fn from(value: $ty) -> Self {
+                    Self::Number(value as f64)
+                }
+            }
+        )*
+    };
+}
+impl_number_arg!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+impl fmt::Display for Arg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(s) => f.write_str(s),
+            Self::Number(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// Calendar date formatted by [`Localizer::format_date`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    /// Month from 1 to 12.
+    pub month: u8,
+    /// Day from 1 to 31.
+    pub day: u8,
+}
+
+/// Translates strings of built-in components and formats numbers and dates for the user's locale.
+///
+/// Built-in components look messages up by ids prefixed with `gland-`, e.g. `gland-dialog-ok`,
+/// and fall back to English if the localizer has none.
+pub trait Localizer: Send + Sync + 'static {
+    /// Returns the locale, e.g. `en-US`.
+    fn locale(&self) -> &str {
+        "en-US"
+    }
+
+    /// Returns message `id` with `args` substituted, `None` if there is no such message.
+    fn translate(&self, id: &str, args: &[(&str, Arg<'_>)]) -> Option<String>;
+
+    /// Returns message `id` or `fallback` if there is no such message.
+    fn text<'a>(&self, id: &str, fallback: &'a str) -> Cow<'a, str> {
+        self.translate(id, &[])
+            .map_or(Cow::Borrowed(fallback), Cow::Owned)
+    }
+
+    /// Formats `value` with `precision` decimal places, e.g. `1,234.50`.
+    fn format_number(&self, value: f64, precision: usize) -> String {
+        format_number(value, precision, ",", ".")
+    }
+
+    /// Formats `date`, ISO 8601 by default.
+    fn format_date(&self, date: Date) -> String {
+        format!("{:04}-{:02}-{:02}", date.year, date.month, date.day)
+    }
+}
+
+/// Localizer without translations, built-in components show English text.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultLocalizer;
+
+impl Localizer for DefaultLocalizer {
+    fn translate(&self, _id: &str, _args: &[(&str, Arg<'_>)]) -> Option<String> {
+        None
+    }
+}
+
+/// Formats number with `group` separating thousands and `decimal` before the fraction.
+pub(crate) fn format_number(value: f64, precision: usize, group: &str, decimal: &str) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let formatted = format!("{:.*}", precision, value.abs());
+    let (int, frac) = formatted.split_once('.').unwrap_or((&formatted, ""));
+
+    let mut out = String::with_capacity(formatted.len() + int.len() / 3 * group.len() + 1);
+    if value.is_sign_negative() && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+        out.push('-');
+    }
+    for (i, digit) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            out.push_str(group);
+        }
+        out.push(digit);
+    }
+    if !frac.is_empty() {
+        out.push_str(decimal);
+        out.push_str(frac);
+    }
+    out
+}
+
+#[cfg(feature = "fluent")]
+pub use fluent::*;
+
+#[cfg(feature = "fluent")]
+mod fluent {
+    use super::{format_number, Arg, Date, Localizer};
+    use fluent_bundle::{
+        concurrent::FluentBundle, FluentArgs, FluentError, FluentResource, FluentValue,
+    };
+    use std::fmt;
+    use unic_langid::LanguageIdentifier;
+
+    /// Error creating a [`FluentLocalizer`].
+    #[derive(Debug)]
+    pub enum FluentLocalizerError {
+        /// Locale is not a valid language identifier.
+        Locale(String),
+        /// Source failed to parse or redefines a message.
+        Source(Vec<String>),
+    }
+
+    impl fmt::Display for FluentLocalizerError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Locale(locale) => write!(f, "invalid locale: {locale}"),
+                Self::Source(errors) => write!(f, "invalid fluent source: {}", errors.join(", ")),
+            }
+        }
+    }
+
+    impl std::error::Error for FluentLocalizerError {}
+
+    /// Localizer backed by [Fluent](https://projectfluent.org) translations.
+    ///
+    /// Numbers use separators of the locale's language. Dates use the `gland-date` message if it is
+    /// defined, it receives `$year`, `$month` and `$day`, otherwise the common order of the locale.
+    #[doc(cfg(feature = "fluent"))]
+    pub struct FluentLocalizer {
+        locale: String,
+        bundle: FluentBundle<FluentResource>,
+    }
+
+    impl FluentLocalizer {
+        /// Creates localizer for `locale` like `de-DE` from Fluent `sources`.
+        pub fn new(locale: &str, sources: &[&str]) -> Result<Self, FluentLocalizerError> {
+            let langid: LanguageIdentifier = locale
+                .parse()
+                .map_err(|_| FluentLocalizerError::Locale(locale.to_owned()))?;
+
+            let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+            // Isolation marks around arguments show up as garbage in terminals.
+            bundle.set_use_isolating(false);
+
+            for source in sources {
+                let resource = FluentResource::try_new(source.to_string()).map_err(|(_, e)| {
+                    FluentLocalizerError::Source(e.iter().map(ToString::to_string).collect())
+                })?;
+                bundle.add_resource(resource).map_err(|e| {
+                    FluentLocalizerError::Source(e.iter().map(FluentError::to_string).collect())
+                })?;
+            }
+
+            Ok(Self {
+                locale: locale.to_owned(),
+                bundle,
+            })
+        }
+
+        fn language(&self) -> &str {
+            self.locale.split(['-', '_']).next().unwrap_or_default()
+        }
+    }
+
+    impl Localizer for FluentLocalizer {
+        fn locale(&self) -> &str {
+            &self.locale
+        }
+
+        fn translate(&self, id: &str, args: &[(&str, Arg<'_>)]) -> Option<String> {
+            let pattern = self.bundle.get_message(id)?.value()?;
+            let args = (!args.is_empty()).then(|| {
+                args.iter()
+                    .map(|(name, arg)| match *arg {
+                        Arg::String(s) => (*name, FluentValue::from(s)),
+                        Arg::Number(n) => (*name, FluentValue::from(n)),
+                    })
+                    .collect::<FluentArgs>()
+            });
+
+            let mut errors = Vec::new();
+            let text = self
+                .bundle
+                .format_pattern(pattern, args.as_ref(), &mut errors);
+            Some(text.into_owned())
+        }
+
+        fn format_number(&self, value: f64, precision: usize) -> String {
+            let (group, decimal) = match self.language() {
+                "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" | "ro" | "hr"
+                | "sl" => (".", ","),
+                "fr" | "ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "hu"
+                | "bg" => ("\u{a0}", ","),
+                _ => (",", "."),
+            };
+            format_number(value, precision, group, decimal)
+        }
+
+        fn format_date(&self, date: Date) -> String {
+            let args = [
+                ("year", Arg::from(date.year)),
+                ("month", Arg::from(date.month)),
+                ("day", Arg::from(date.day)),
+            ];
+            if let Some(text) = self.translate("gland-date", &args) {
+                return text;
+            }
+
+            let Date { year, month, day } = date;
+            match (self.language(), self.locale.as_str()) {
+                (_, "en-US" | "en_US") => format!("{month:02}/{day:02}/{year:04}"),
+                ("ja" | "zh" | "ko" | "hu", _) => format!("{year:04}/{month:02}/{day:02}"),
+                ("de" | "ru" | "uk" | "pl" | "cs" | "sk" | "fi" | "nb" | "no" | "tr", _) => {
+                    format!("{day:02}.{month:02}.{year:04}")
+                }
+                ("en" | "fr" | "es" | "it" | "pt" | "el", _) => {
+                    format!("{day:02}/{month:02}/{year:04}")
+                }
+                _ => format!("{year:04}-{month:02}-{day:02}"),
+            }
+        }
+    }
+}
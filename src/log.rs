@@ -0,0 +1,432 @@
+use crate::{
+    Callback, Component, Context, Event, Id, KeyCode, KeyEventKind, KeyModifiers, Resume,
+    TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::{
+    field::{Field, Visit},
+    span,
+    subscriber::SetGlobalDefaultError,
+    Level, Metadata, Subscriber,
+};
+
+/// Records kept until a [`LogView`] is mounted.
+const PENDING: usize = 1000;
+
+/// Event recorded by the [`LogSubscriber`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[doc(cfg(feature = "tracing"))]
+pub struct LogRecord {
+    pub level: Level,
+    pub target: String,
+    /// Message followed by other fields, e.g. `saved path=notes.txt`.
+    pub message: String,
+    pub time: SystemTime,
+}
+
+/// Forwards records to the mounted [`LogView`], see [`log_bridge`].
+type Forward = Box<dyn Fn(LogRecord) -> bool + Send>;
+
+#[derive(Default)]
+struct Shared {
+    pending: VecDeque<LogRecord>,
+    forward: Option<Forward>,
+}
+
+/// Connects [`LogSubscriber`]s with a [`LogView`], records are sent through the compositor's job channel.
+/// Records emitted while no view is mounted are kept and shown once one is.
+#[derive(Clone, Default)]
+#[doc(cfg(feature = "tracing"))]
+pub struct LogBridge {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl LogBridge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates subscriber recording events up to `max_level`, e.g. to be combined with other
+    /// subscribers instead of installing it globally with [`log_bridge`].
+    pub fn subscriber(&self, max_level: Level) -> LogSubscriber {
+        LogSubscriber {
+            bridge: self.clone(),
+            max_level,
+            next_span: AtomicU64::new(1),
+        }
+    }
+
+    /// Creates view showing records of this bridge.
+    pub fn view(&self) -> LogView {
+        LogView::new(self.clone())
+    }
+
+    fn send(&self, record: LogRecord) {
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        let record = match &shared.forward {
+            Some(forward) if forward(record.clone()) => return,
+            // Compositor has exited.
+            Some(_) => {
+                shared.forward = None;
+                record
+            }
+            None => record,
+        };
+        if shared.pending.len() == PENDING {
+            shared.pending.pop_front();
+        }
+        shared.pending.push_back(record);
+    }
+
+    fn connect(&self, forward: Forward) -> VecDeque<LogRecord> {
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.forward = Some(forward);
+        std::mem::take(&mut shared.pending)
+    }
+
+    fn disconnect(&self) {
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.forward = None;
+    }
+}
+
+/// Installs a global tracing subscriber recording events up to `DEBUG` into the returned bridge,
+/// mount [`LogBridge::view`] to show them. Fails if a global subscriber is already installed.
+///
+/// ```no_run
+/// # use gland::{log_bridge, Compositor, LayerId};
+/// let bridge = log_bridge().unwrap();
+/// let mut compositor: Compositor = Compositor::new();
+/// _ = compositor.insert_at(LayerId::MIDDLE, bridge.view());
+/// tracing::info!("started");
+/// ```
+#[doc(cfg(feature = "tracing"))]
+pub fn log_bridge() -> Result<LogBridge, SetGlobalDefaultError> {
+    let bridge = LogBridge::new();
+    tracing::subscriber::set_global_default(bridge.subscriber(Level::DEBUG))?;
+    Ok(bridge)
+}
+
+/// Tracing subscriber passing events to a [`LogBridge`], spans are ignored.
+#[doc(cfg(feature = "tracing"))]
+pub struct LogSubscriber {
+    bridge: LogBridge,
+    max_level: Level,
+    next_span: AtomicU64,
+}
+
+impl Subscriber for LogSubscriber {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.is_event() && *metadata.level() <= self.max_level
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        Some(self.max_level.into())
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        self.bridge.send(LogRecord {
+            level: *metadata.level(),
+            target: metadata.target().to_owned(),
+            message: visitor.message + visitor.fields.as_str(),
+            time: SystemTime::now(),
+        });
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message.push_str(value),
+            name => _ = write!(self.fields, " {name}={value}"),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => _ = write!(self.message, "{value:?}"),
+            name => _ = write!(self.fields, " {name}={value:?}"),
+        }
+    }
+}
+
+/// Scrolling pane showing records of a [`LogBridge`], newest at the bottom.
+///
+/// While focused, arrow keys, `PageUp`, `PageDown`, `Home` and `End` scroll, `+` and `-` change the
+/// least severe level shown, `/` starts typing a filter on targets and messages and `Esc` clears it.
+/// The view follows new records while scrolled to the bottom.
+#[doc(cfg(feature = "tracing"))]
+pub struct LogView {
+    id: Id,
+    bridge: LogBridge,
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+    level: Level,
+    filter: String,
+    typing: bool,
+    /// Lines scrolled up from the bottom.
+    scroll: usize,
+}
+
+impl LogView {
+    pub fn new(bridge: LogBridge) -> Self {
+        Self {
+            id: Self::log_id(),
+            bridge,
+            records: VecDeque::new(),
+            capacity: 1000,
+            level: Level::TRACE,
+            filter: String::new(),
+            typing: false,
+            scroll: 0,
+        }
+    }
+
+    /// Returns the id log views have unless changed with [`Self::with_id`].
+    pub fn log_id() -> Id {
+        Id::new("gland-log-view")
+    }
+
+    /// Sets the id of the view.
+    pub fn with_id(mut self, id: Id) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Sets how many records are kept, older ones are dropped. `1000` by default.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Sets the least severe level shown, `TRACE` by default.
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Adds `record` to the view, records of the bridge are added when they arrive.
+    pub fn push(&mut self, record: LogRecord) {
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        let shown = self.shows(&record);
+        self.records.push_back(record);
+        // Keeps the same lines in view while scrolled up.
+        if shown && self.scroll > 0 {
+            self.scroll += 1;
+        }
+    }
+
+    /// Returns records currently kept, oldest first.
+    pub fn records(&self) -> impl Iterator<Item = &LogRecord> {
+        self.records.iter()
+    }
+
+    /// Removes all records.
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.scroll = 0;
+    }
+
+    fn shows(&self, record: &LogRecord) -> bool {
+        record.level <= self.level
+            && (self.filter.is_empty()
+                || record.message.contains(&self.filter)
+                || record.target.contains(&self.filter))
+    }
+
+    fn shown(&self) -> impl DoubleEndedIterator<Item = &LogRecord> {
+        self.records.iter().filter(|r| self.shows(r))
+    }
+
+    fn scroll_by(&mut self, lines: isize) {
+        let max = self.shown().count().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(lines).min(max);
+    }
+}
+
+impl<S: 'static, E: Send + 'static> Component<S, E> for LogView {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn title(&self) -> Option<&str> {
+        Some("Log")
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        let sender = cx.jobs().sender();
+        let id = self.id;
+        let pending = self.bridge.connect(Box::new(move |record| {
+            let callback: Callback<S, E> = Box::new(move |comp| {
+                if let Some(view) = comp.get_mut::<LogView>(id) {
+                    view.push(record);
+                    comp.request_redraw();
+                }
+            });
+            sender.send(Resume::JobCallback(callback))
+        }));
+        for record in pending {
+            self.push(record);
+        }
+    }
+
+    fn on_unmount(&mut self, _cx: &mut Context<S, E>) {
+        self.bridge.disconnect();
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        let mut title = format!("log {}", self.level);
+        if self.typing || !self.filter.is_empty() {
+            _ = write!(title, " /{}", self.filter);
+        }
+        let block = Block::new().borders(Borders::ALL).title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let height = inner.height as usize;
+        let mut lines = self
+            .shown()
+            .rev()
+            .skip(self.scroll)
+            .take(height)
+            .map(record_line)
+            .collect::<Vec<_>>();
+        lines.reverse();
+        Paragraph::new(lines).render(inner, buf);
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        let Event::Terminal(TerminalEvent::Key(key)) = event else {
+            return;
+        };
+        if cx.focused() != Some(self.id) || key.kind == KeyEventKind::Release {
+            return;
+        }
+
+        if self.typing {
+            match key.code {
+                KeyCode::Enter => self.typing = false,
+                KeyCode::Esc => {
+                    self.typing = false;
+                    self.filter.clear();
+                }
+                KeyCode::Backspace => _ = self.filter.pop(),
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.filter.push(c);
+                }
+                _ => return,
+            }
+            self.scroll = 0;
+            _ = event.consume();
+            return;
+        }
+
+        let page = cx.size().height.saturating_sub(2).max(1) as isize;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(-1),
+            KeyCode::PageUp => self.scroll_by(page),
+            KeyCode::PageDown => self.scroll_by(-page),
+            KeyCode::Home => self.scroll_by(isize::MAX),
+            KeyCode::End => self.scroll = 0,
+            KeyCode::Char('+') => self.level = more_verbose(self.level),
+            KeyCode::Char('-') => self.level = less_verbose(self.level),
+            KeyCode::Char('/') => self.typing = true,
+            KeyCode::Esc if !self.filter.is_empty() => self.filter.clear(),
+            _ => return,
+        }
+        _ = event.consume();
+    }
+}
+
+fn record_line(record: &LogRecord) -> Line<'_> {
+    let color = match record.level {
+        Level::ERROR => Color::Red,
+        Level::WARN => Color::Yellow,
+        Level::INFO => Color::Green,
+        Level::DEBUG => Color::Blue,
+        Level::TRACE => Color::Magenta,
+    };
+    // Time of day in UTC, dates rarely matter while the app runs.
+    let seconds = record
+        .time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() % 86400);
+    let dim = Style::new().add_modifier(Modifier::DIM);
+
+    Line::from(vec![
+        Span::styled(
+            format!(
+                "{:02}:{:02}:{:02} ",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            ),
+            dim,
+        ),
+        Span::styled(format!("{:5} ", record.level), Style::new().fg(color)),
+        Span::styled(format!("{}: ", record.target), dim),
+        Span::raw(record.message.as_str()),
+    ])
+}
+
+fn more_verbose(level: Level) -> Level {
+    match level {
+        Level::ERROR => Level::WARN,
+        Level::WARN => Level::INFO,
+        Level::INFO => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+fn less_verbose(level: Level) -> Level {
+    match level {
+        Level::TRACE => Level::DEBUG,
+        Level::DEBUG => Level::INFO,
+        Level::INFO => Level::WARN,
+        _ => Level::ERROR,
+    }
+}
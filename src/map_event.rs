@@ -0,0 +1,280 @@
+use crate::{
+    lens::Boxed, Action, Component, Context, CursorStyle, Event, Id, Projection, Transition,
+};
+use ratatui::{buffer::Buffer, layout::Rect};
+use std::{any::Any, marker::PhantomData};
+
+/// Mounts a component with user events `E2` into a compositor with user events `E`,
+/// so a library of components can define its own events.
+///
+/// User events of the compositor reach the component if `to_child` converts them, events the component
+/// emits are converted with `from_child`. Like with [`Lens`](crate::Lens), screens the component pushes
+/// are wrapped too, while its callbacks including those returned by jobs never run since there is
+/// no compositor with user events `E2`, handles of such jobs resolve to `Err(Aborted)`.
+///
+/// ```
+/// # use gland::{Compositor, Component, Context, Event, Id, LayerId, MapEvent};
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// enum PickerEvent {
+///     Picked(usize),
+/// }
+///
+/// struct Picker;
+///
+/// impl Component<(), PickerEvent> for Picker {
+///     fn id(&self) -> Id {
+///         Id::new("picker")
+///     }
+///
+///     fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &()) {}
+/// }
+///
+/// enum AppEvent {
+///     Open(usize),
+///     Quit,
+/// }
+///
+/// let mut comp = Compositor::<(), AppEvent>::new();
+/// let picker = MapEvent::new(Picker, |_: &AppEvent| None, |PickerEvent::Picked(i)| AppEvent::Open(i));
+/// _ = comp.insert_at(LayerId::MIDDLE, picker);
+/// ```
+pub struct MapEvent<C, E2, E = ()> {
+    inner: C,
+    to_child: fn(&E) -> Option<E2>,
+    from_child: fn(E2) -> E,
+    _marker: PhantomData<fn(E2) -> E>,
+}
+
+impl<C, E2, E> MapEvent<C, E2, E> {
+    pub fn new(inner: C, to_child: fn(&E) -> Option<E2>, from_child: fn(E2) -> E) -> Self {
+        Self {
+            inner,
+            to_child,
+            from_child,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C, E2: 'static, E: Send + 'static> MapEvent<C, E2, E> {
+    /// Runs `f` with the component and a context with user events `E2`.
+    fn project<S: 'static, R>(
+        &mut self,
+        cx: &mut Context<S, E>,
+        f: impl FnOnce(&mut C, &mut Context<S, E2>) -> R,
+    ) -> R {
+        let jobs = cx.jobs().project(self.from_child);
+        let (to_child, from_child) = (self.to_child, self.from_child);
+        let wrap = move |screen| {
+            Box::new(MapEvent::new(Boxed(screen), to_child, from_child)) as Box<dyn Component<S, E>>
+        };
+        let projection = Projection {
+            get: |state| state,
+            get_mut: |state| state,
+            wrap: &wrap,
+        };
+        let inner = &mut self.inner;
+        cx.project(&jobs, projection, |cx| f(inner, cx))
+    }
+
+    /// Runs `f` with the component and `event` converted to `E2`, skipped if the user event isn't converted.
+    fn forward<S: 'static, R>(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+        f: impl FnOnce(&mut C, &mut Event<E2>, &mut Context<S, E2>) -> R,
+    ) -> Option<R> {
+        let mut child = match event {
+            Event::User(e) => Event::User((self.to_child)(e)?),
+            _ => std::mem::take(event).map(|_| unreachable!("user events are converted above")),
+        };
+        let out = self.project(cx, |inner, cx| f(inner, &mut child, cx));
+        match event {
+            Event::User(_) if child.is_consumed() => _ = event.consume(),
+            Event::User(_) => {}
+            _ => *event = child.map(self.from_child),
+        }
+        Some(out)
+    }
+}
+
+impl<S, C, E2, E> Component<S, E> for MapEvent<C, E2, E>
+where
+    S: 'static,
+    C: Component<S, E2>,
+    E2: 'static,
+    E: Send + 'static,
+{
+    fn id(&self) -> Id {
+        self.inner.id()
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        self.inner.view(area, buf, state);
+    }
+
+    fn layout(&self, area: Rect) -> Rect {
+        self.inner.layout(area)
+    }
+
+    fn should_update(&self, state: &S) -> bool {
+        self.inner.should_update(state)
+    }
+
+    fn focusable(&self) -> bool {
+        self.inner.focusable()
+    }
+
+    fn cursor(&self, area: Rect, state: &S) -> Option<(u16, u16)> {
+        self.inner.cursor(area, state)
+    }
+
+    fn cursor_style(&self, state: &S) -> Option<CursorStyle> {
+        self.inner.cursor_style(state)
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.on_mount(cx));
+    }
+
+    fn on_unmount(&mut self, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.on_unmount(cx));
+    }
+
+    fn on_enter(&mut self, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.on_enter(cx));
+    }
+
+    fn on_leave(&mut self, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.on_leave(cx));
+    }
+
+    fn title(&self) -> Option<&str> {
+        self.inner.title()
+    }
+
+    fn actions(&self) -> &[Action] {
+        self.inner.actions()
+    }
+
+    fn handle_action(&mut self, action: &Action, cx: &mut Context<S, E>) -> bool {
+        self.project(cx, |inner, cx| inner.handle_action(action, cx))
+    }
+
+    fn enter_transition(&self) -> Option<Transition> {
+        self.inner.enter_transition()
+    }
+
+    fn exit_transition(&self) -> Option<Transition> {
+        self.inner.exit_transition()
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        self.forward(event, cx, |inner, event, cx| inner.handle_event(event, cx));
+    }
+
+    fn try_handle_event(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.forward(event, cx, |inner, event, cx| {
+            inner.try_handle_event(event, cx)
+        })
+        .unwrap_or(Ok(()))
+    }
+
+    fn handle_message(&mut self, message: Box<dyn Any>, cx: &mut Context<S, E>) {
+        self.project(cx, |inner, cx| inner.handle_message(message, cx));
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::{Compositor, JobHandle, LayerId};
+    use futures_util::future::Aborted;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    };
+
+    /// Suspends the compositor on the first event.
+    struct Suspender {
+        ran: Arc<AtomicBool>,
+    }
+
+    impl Component<(), ()> for Suspender {
+        fn id(&self) -> Id {
+            Id::new("suspender")
+        }
+
+        fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &()) {}
+
+        fn handle_event(&mut self, _event: &mut Event, cx: &mut Context) {
+            let ran = self.ran.clone();
+            drop(cx.suspend(move || ran.store(true, Ordering::Relaxed)));
+        }
+    }
+
+    #[test]
+    fn suspends_reach_compositor() {
+        let ran = Arc::new(AtomicBool::new(false));
+        let mut comp = Compositor::<(), u8>::new();
+        let suspender = Suspender { ran: ran.clone() };
+        _ = comp.insert_at(LayerId::MIDDLE, MapEvent::new(suspender, |_| None, |()| 0));
+
+        comp.dispatch(Event::Tick);
+        assert!(!ran.load(Ordering::Relaxed));
+
+        let suspended = comp.take_suspended();
+        assert_eq!(suspended.len(), 1);
+        suspended.into_iter().for_each(|f| f());
+        assert!(ran.load(Ordering::Relaxed));
+        assert!(comp.take_suspended().is_empty());
+    }
+
+    /// Spawns a job returning a callback on the first event.
+    struct Spawner {
+        handle: Arc<Mutex<Option<JobHandle>>>,
+    }
+
+    impl Component<(), ()> for Spawner {
+        fn id(&self) -> Id {
+            Id::new("spawner")
+        }
+
+        fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &()) {}
+
+        fn handle_event(&mut self, _event: &mut Event, cx: &mut Context) {
+            let handle = cx.jobs().spawn(async { |_: &mut Compositor| {} });
+            self.handle.lock().unwrap().get_or_insert(handle);
+        }
+    }
+
+    #[test]
+    fn dropped_callbacks_abort_handles() {
+        let handle = Arc::new(Mutex::new(None));
+        let mut comp = Compositor::<(), u8>::new();
+        let spawner = Spawner {
+            handle: handle.clone(),
+        };
+        _ = comp.insert_at(LayerId::MIDDLE, MapEvent::new(spawner, |_| None, |()| 0));
+
+        comp.dispatch(Event::Tick);
+        let handle = handle.lock().unwrap().take().unwrap();
+        assert_eq!(futures_executor::block_on(handle), Err(Aborted));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::Id;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+/// Values components keep outside of the state, e.g. scroll offsets, selections or caches,
+/// that survive the component being unmounted and mounted again, see [`Context::memory`](crate::Context::memory).
+///
+/// Values are keyed by the id of the component and their type, so a component can keep one value of each type.
+///
+/// ```
+/// # use gland::{Component, Context, Id};
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// struct Offset(usize);
+///
+/// struct Log {
+///     offset: usize,
+/// }
+///
+/// impl Component for Log {
+///     fn id(&self) -> Id {
+///         Id::new("log")
+///     }
+///
+///     fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &()) {}
+///
+///     fn on_mount(&mut self, cx: &mut Context) {
+///         self.offset = cx.memory().get::<Offset>(self.id()).map_or(0, |offset| offset.0);
+///     }
+///
+///     fn on_unmount(&mut self, cx: &mut Context) {
+///         cx.memory().insert(self.id(), Offset(self.offset));
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct Memory {
+    values: HashMap<(Id, TypeId), Box<dyn Any>>,
+}
+
+impl Memory {
+    /// Returns the value of type `T` kept for component `id`.
+    pub fn get<T: 'static>(&self, id: Id) -> Option<&T> {
+        self.values.get(&(id, TypeId::of::<T>()))?.downcast_ref()
+    }
+
+    /// Returns the value of type `T` kept for component `id` mutably.
+    pub fn get_mut<T: 'static>(&mut self, id: Id) -> Option<&mut T> {
+        self.values
+            .get_mut(&(id, TypeId::of::<T>()))?
+            .downcast_mut()
+    }
+
+    /// Returns the value of type `T` kept for component `id`, inserts the one returned by `f` if there is none.
+    pub fn get_or_insert_with<T: 'static>(&mut self, id: Id, f: impl FnOnce() -> T) -> &mut T {
+        self.values
+            .entry((id, TypeId::of::<T>()))
+            .or_insert_with(|| Box::new(f()))
+            .downcast_mut()
+            .expect("values are keyed by their type")
+    }
+
+    /// Same as [`Self::get_or_insert_with`] with the default value.
+    pub fn get_or_default<T: Default + 'static>(&mut self, id: Id) -> &mut T {
+        self.get_or_insert_with(id, T::default)
+    }
+
+    /// Keeps `value` for component `id`, returns the previous value of type `T`.
+    pub fn insert<T: 'static>(&mut self, id: Id, value: T) -> Option<T> {
+        let old = self
+            .values
+            .insert((id, TypeId::of::<T>()), Box::new(value))?;
+        old.downcast().ok().map(|old| *old)
+    }
+
+    /// Removes the value of type `T` kept for component `id`.
+    pub fn remove<T: 'static>(&mut self, id: Id) -> Option<T> {
+        let old = self.values.remove(&(id, TypeId::of::<T>()))?;
+        old.downcast().ok().map(|old| *old)
+    }
+
+    /// Removes all values kept for component `id`.
+    pub fn clear(&mut self, id: Id) {
+        self.values.retain(|(key, _), _| *key != id);
+    }
+
+    /// Removes all values.
+    pub fn clear_all(&mut self) {
+        self.values.clear();
+    }
+}
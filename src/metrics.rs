@@ -0,0 +1,100 @@
+use crate::{Id, LayerId};
+use std::{cell::RefCell, collections::HashMap, time::Duration};
+
+/// Durations of calls to one method of a component.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timing {
+    pub count: u64,
+    pub total: Duration,
+    pub max: Duration,
+    pub last: Duration,
+}
+
+impl Timing {
+    /// Returns the average duration of a call, zero if there were none.
+    pub fn average(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => self.total / count.min(u32::MAX as u64) as u32,
+        }
+    }
+
+    fn add(&mut self, duration: Duration) {
+        self.count += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+        self.last = duration;
+    }
+}
+
+/// Time a component has spent in [`Component::view`](crate::Component::view)
+/// and [`Component::handle_event`](crate::Component::handle_event).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentMetrics {
+    pub layer_id: LayerId,
+    pub id: Id,
+    pub view: Timing,
+    pub handle_event: Timing,
+}
+
+/// Timings of mounted components, collected once enabled with
+/// [`Compositor::with_metrics`](crate::Compositor::with_metrics).
+/// Metrics of a component are dropped when it is removed.
+#[derive(Default)]
+pub struct Metrics {
+    components: RefCell<HashMap<(LayerId, Id), ComponentMetrics>>,
+}
+
+impl Metrics {
+    /// Returns metrics of the topmost component with `id`.
+    pub fn component(&self, id: Id) -> Option<ComponentMetrics> {
+        let components = self.components.borrow();
+        components
+            .values()
+            .filter(|m| m.id == id)
+            .max_by_key(|m| m.layer_id)
+            .copied()
+    }
+
+    /// Returns metrics of all components, the one with the longest total time first.
+    pub fn components(&self) -> Vec<ComponentMetrics> {
+        let mut components = self
+            .components
+            .borrow()
+            .values()
+            .copied()
+            .collect::<Vec<_>>();
+        components.sort_by_key(|m| std::cmp::Reverse(m.view.total + m.handle_event.total));
+        components
+    }
+
+    /// Forgets all timings, e.g. to measure a single interaction.
+    pub fn reset(&self) {
+        self.components.borrow_mut().clear();
+    }
+
+    pub(crate) fn record_view(&self, layer_id: LayerId, id: Id, duration: Duration) {
+        self.entry(layer_id, id, |m| m.view.add(duration));
+    }
+
+    pub(crate) fn record_event(&self, layer_id: LayerId, id: Id, duration: Duration) {
+        self.entry(layer_id, id, |m| m.handle_event.add(duration));
+    }
+
+    pub(crate) fn remove(&self, layer_id: LayerId, id: Id) {
+        self.components.borrow_mut().remove(&(layer_id, id));
+    }
+
+    fn entry(&self, layer_id: LayerId, id: Id, f: impl FnOnce(&mut ComponentMetrics)) {
+        let mut components = self.components.borrow_mut();
+        let metrics = components
+            .entry((layer_id, id))
+            .or_insert(ComponentMetrics {
+                layer_id,
+                id,
+                view: Timing::default(),
+                handle_event: Timing::default(),
+            });
+        f(metrics);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{Component, Compositor, Id, LayerId};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    any::Any,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Component that can be saved in a [`Snapshot`] and reconstructed on the next run, e.g. open popups,
+/// tabs or windows. Fields that can't be serialized are skipped with `#[serde(skip)]`.
+///
+/// ```
+/// # use gland::{Component, Compositor, Id, LayerId, PersistentComponent};
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Notes {
+///     text: String,
+/// }
+///
+/// impl Component for Notes {
+///     fn id(&self) -> Id {
+///         Id::new("notes")
+///     }
+///
+///     fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &()) {}
+/// }
+///
+/// impl PersistentComponent for Notes {
+///     const KIND: &'static str = "notes";
+/// }
+///
+/// let mut comp = Compositor::new().with_persistent::<Notes>();
+/// _ = comp.insert_at(LayerId::POPUP, Notes { text: "todo".into() });
+///
+/// let snapshot = comp.snapshot().unwrap();
+/// let mut next = Compositor::new().with_persistent::<Notes>();
+/// assert_eq!(next.restore(snapshot), 1);
+/// assert_eq!(next.get::<Notes>(Id::new("notes")).unwrap().text, "todo");
+/// ```
+pub trait PersistentComponent<S = (), E = ()>:
+    Component<S, E> + Serialize + DeserializeOwned
+{
+    /// Name of the component type in snapshots, has to stay the same between versions of the application.
+    const KIND: &'static str;
+}
+
+/// Persistent components mounted in a compositor with their layers, see [`Compositor::snapshot`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    components: Vec<SavedComponent>,
+    /// Index of the focused component.
+    focused: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedComponent {
+    layer: i16,
+    kind: String,
+    data: Value,
+}
+
+impl Snapshot {
+    /// Reads a snapshot written with [`Self::save`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = fs::read(path)?;
+        Ok(serde_json::from_slice(&file)?)
+    }
+
+    /// Writes the snapshot to a file as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_vec(self)?)
+    }
+
+    /// Returns the number of saved components.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns `true` if no components were saved.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+/// Inserts a saved component, returns its id if there wasn't one with the same id.
+type Restore<S, E> = fn(&mut Compositor<S, E>, LayerId, Value) -> serde_json::Result<Option<Id>>;
+
+/// Component type registered with [`Compositor::with_persistent`].
+pub(crate) struct Persistent<S, E> {
+    kind: &'static str,
+    save: fn(&dyn Component<S, E>) -> Option<serde_json::Result<Value>>,
+    restore: Restore<S, E>,
+}
+
+impl<S: 'static, E: 'static> Persistent<S, E> {
+    fn new<C: PersistentComponent<S, E>>() -> Self {
+        Self {
+            kind: C::KIND,
+            save: |component| {
+                let component = (component as &dyn Any).downcast_ref::<C>()?;
+                Some(serde_json::to_value(component))
+            },
+            restore: |comp, layer_id, data| {
+                let component = serde_json::from_value::<C>(data)?;
+                let id = component.id();
+                Ok(comp.insert_at(layer_id, component).ok().map(|_| id))
+            },
+        }
+    }
+}
+
+/// Session functions
+impl<S: 'static, E: 'static> Compositor<S, E> {
+    /// Saves components of type `C` in snapshots and allows restoring them, see [`Self::snapshot`].
+    #[doc(cfg(feature = "persist"))]
+    pub fn with_persistent<C: PersistentComponent<S, E>>(mut self) -> Self {
+        self.persistent.push(Persistent::new::<C>());
+        self
+    }
+
+    /// Restores components from the snapshot at `path` if it exists and saves them there on exit,
+    /// so the application opens the way the user has left it. Components have to be registered with
+    /// [`Self::with_persistent`] before, components inserted later with the same ids are ignored.
+    ///
+    /// Failing to write the snapshot on exit is returned from [`Self::run`] as [`Error::Io`](crate::Error::Io).
+    #[doc(cfg(feature = "persist"))]
+    pub fn with_session_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        if let Ok(snapshot) = Snapshot::load(&path) {
+            self.restore(snapshot);
+        }
+        self.session_file = Some(path);
+        self
+    }
+
+    /// Saves mounted components registered with [`Self::with_persistent`] with their layers and the focus.
+    #[doc(cfg(feature = "persist"))]
+    pub fn snapshot(&self) -> serde_json::Result<Snapshot> {
+        let mut snapshot = Snapshot::default();
+        for (layer_id, id, component) in self.iter() {
+            let Some((kind, data)) = self.persistent.iter().find_map(|persistent| {
+                (persistent.save)(component).map(|data| (persistent.kind, data))
+            }) else {
+                continue;
+            };
+            if self.focused() == Some(id) {
+                snapshot.focused = Some(snapshot.components.len());
+            }
+            snapshot.components.push(SavedComponent {
+                layer: layer_id.0,
+                kind: kind.to_owned(),
+                data: data?,
+            });
+        }
+        Ok(snapshot)
+    }
+
+    /// Inserts components saved in `snapshot`, returns the number of restored components.
+    /// Components of unknown kinds, that fail to deserialize, e.g. saved by an older version,
+    /// or with ids of already inserted components are skipped.
+    #[doc(cfg(feature = "persist"))]
+    pub fn restore(&mut self, snapshot: Snapshot) -> usize {
+        let mut restored = 0;
+        for (i, saved) in snapshot.components.into_iter().enumerate() {
+            let Some(restore) = self
+                .persistent
+                .iter()
+                .find(|persistent| persistent.kind == saved.kind)
+                .map(|persistent| persistent.restore)
+            else {
+                continue;
+            };
+            let Ok(Some(id)) = restore(self, LayerId(saved.layer), saved.data) else {
+                continue;
+            };
+            restored += 1;
+            if snapshot.focused == Some(i) {
+                _ = self.set_focus(id);
+            }
+        }
+        restored
+    }
+
+    /// Writes the snapshot to the session file when the compositor stops, see [`Self::with_session_file`].
+    pub(crate) fn save_session(&self) -> io::Result<()> {
+        let Some(path) = &self.session_file else {
+            return Ok(());
+        };
+        self.snapshot()?.save(path)
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{Context, Event};
+use ratatui::buffer::Buffer;
+
+/// Extension of the compositor that sees its whole lifecycle, e.g. for logging, metrics or persistence,
+/// see [`Compositor::with_plugin`](crate::Compositor::with_plugin). All hooks do nothing by default.
+pub trait Plugin<S = (), E = ()>: 'static {
+    /// Called once when the compositor starts running, after components inserted before are mounted.
+    fn on_start(&mut self, _cx: &mut Context<S, E>) {}
+
+    /// Called with every event before event hooks and components,
+    /// see [`Compositor::with_event_hook`](crate::Compositor::with_event_hook).
+    fn on_event(&mut self, _event: &mut Event<E>, _cx: &mut Context<S, E>) {}
+
+    /// Called before every frame is drawn.
+    fn before_render(&mut self, _state: &S) {}
+
+    /// Called with every frame drawn, see [`Compositor::with_after_render`](crate::Compositor::with_after_render).
+    fn after_render(&mut self, _frame: &Buffer, _state: &S) {}
+
+    /// Called once when the compositor stops running.
+    /// Not called for compositors driven with [`Compositor::dispatch`](crate::Compositor::dispatch).
+    fn on_exit(&mut self, _state: &S) {}
+}
@@ -0,0 +1,114 @@
+use crate::{
+    widget_component::WidgetComponent, Component, Compositor, Error, Event, Id, Jobs, LayerId,
+};
+use ratatui::{backend::Backend, buffer::Buffer, layout::Rect};
+
+/// Elm style application on top of the compositor, an alternative to changing the state
+/// with [`Context::state_mut`](crate::Context::state_mut) from components.
+///
+/// The model `M` is only changed by a single `update` function folding messages `Msg` into it and drawn
+/// by `view`. Messages are user events of the underlying compositor: components emit them with
+/// [`Context::emit`](crate::Context::emit), jobs passed to `update` with [`Jobs::emit`] or by returning
+/// them from futures. Messages are passed to `update` before components and don't reach them.
+///
+/// ```
+/// # use gland::Program;
+/// enum Msg {
+///     Increment,
+///     Reset,
+/// }
+///
+/// let mut program = Program::new(
+///     0u32,
+///     |count, msg, _jobs| match msg {
+///         Msg::Increment => *count += 1,
+///         Msg::Reset => *count = 0,
+///     },
+///     |count, area, buf| _ = buf.set_string(area.x, area.y, count.to_string(), Default::default()),
+/// );
+/// program.send(Msg::Increment);
+/// program.send(Msg::Increment);
+/// assert_eq!(*program.model(), 2);
+/// ```
+pub struct Program<M, Msg = ()> {
+    compositor: Compositor<M, Msg>,
+}
+
+/// Builder functions
+impl<M: 'static, Msg: 'static> Program<M, Msg> {
+    /// Creates a program, `view` draws the whole area below all components.
+    pub fn new(
+        model: M,
+        mut update: impl FnMut(&mut M, Msg, &Jobs<M, Msg>) + 'static,
+        view: impl Fn(&M, Rect, &mut Buffer) + 'static,
+    ) -> Self {
+        let mut compositor = Compositor::with_state(model).with_event_hook(move |event, cx| {
+            if let Event::User(_) = event {
+                let Event::User(msg) = event.consume() else {
+                    unreachable!()
+                };
+                let jobs = cx.jobs();
+                update(cx.state_mut(), msg, jobs);
+            }
+        });
+        _ = compositor.insert_at(
+            LayerId::BACKGROUND,
+            WidgetComponent {
+                id: Id::new("gland-program-view"),
+                area: |area| area,
+                widget: view,
+            },
+        );
+
+        Self { compositor }
+    }
+
+    /// Adds a component reading the model and emitting messages, e.g. an input or a list.
+    /// Components with the same id as one added before are ignored.
+    pub fn with_component(mut self, layer_id: LayerId, component: impl Component<M, Msg>) -> Self {
+        _ = self.compositor.insert_at(layer_id, component);
+        self
+    }
+
+    /// Configures the underlying compositor, e.g. adds a keymap or plugins.
+    pub fn with_compositor(
+        mut self,
+        f: impl FnOnce(Compositor<M, Msg>) -> Compositor<M, Msg>,
+    ) -> Self {
+        self.compositor = f(self.compositor);
+        self
+    }
+}
+
+/// Non-builder functions
+impl<M: 'static, Msg: 'static> Program<M, Msg> {
+    /// Returns the current model.
+    pub fn model(&self) -> &M {
+        self.compositor.state()
+    }
+
+    /// Folds `msg` into the model, same as emitting it from a component.
+    pub fn send(&mut self, msg: Msg) {
+        self.compositor.dispatch(Event::User(msg));
+    }
+
+    /// Returns the underlying compositor.
+    pub fn compositor(&self) -> &Compositor<M, Msg> {
+        &self.compositor
+    }
+
+    /// Returns the underlying compositor mutably, the model should only be changed with [`Self::send`].
+    pub fn compositor_mut(&mut self) -> &mut Compositor<M, Msg> {
+        &mut self.compositor
+    }
+
+    /// Returns the underlying compositor, e.g. to run it with a different function.
+    pub fn into_compositor(self) -> Compositor<M, Msg> {
+        self.compositor
+    }
+
+    /// Runs the program, see [`Compositor::run`]. Returns the final model.
+    pub async fn run<B: Backend>(self, backend: B) -> Result<M, Error> {
+        self.compositor.run(backend).await
+    }
+}
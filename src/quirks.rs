@@ -0,0 +1,187 @@
+use crate::{KeyEvent, KeyEventKind, TerminalEvent};
+
+/// Normalizes terminal events reported differently by Windows consoles.
+/// Translation itself is platform independent, it is only applied to crossterm input on Windows.
+#[derive(Debug, Default)]
+pub(crate) struct WindowsQuirks {
+    last_size: Option<(u16, u16)>,
+    /// Last key event, legacy consoles may report a release twice.
+    last_key: Option<KeyEvent>,
+}
+
+impl WindowsQuirks {
+    /// Returns `None` if the event should be dropped.
+    /// `window_size` is queried on resize because consoles may report the buffer size instead of the window size.
+    pub(crate) fn filter(
+        &mut self,
+        event: TerminalEvent,
+        window_size: impl FnOnce() -> Option<(u16, u16)>,
+    ) -> Option<TerminalEvent> {
+        match event {
+            TerminalEvent::Resize(w, h) => {
+                let size = window_size().unwrap_or((w, h));
+
+                // Consoles emit resize for every buffer change, even if window size hasn't changed.
+                if self.last_size.replace(size) == Some(size) {
+                    None
+                } else {
+                    Some(TerminalEvent::Resize(size.0, size.1))
+                }
+            }
+            TerminalEvent::Key(key) => {
+                let last = self.last_key.replace(key);
+                if key.kind == KeyEventKind::Release && last == Some(key) {
+                    None
+                } else {
+                    Some(TerminalEvent::Key(key))
+                }
+            }
+            event => Some(event),
+        }
+    }
+}
+
+/// Applies [`WindowsQuirks`] to crossterm events on Windows, passes them through elsewhere.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn crossterm_event(
+    quirks: &mut WindowsQuirks,
+    event: crossterm::event::Event,
+) -> Option<TerminalEvent> {
+    translate(cfg!(windows), quirks, event, || {
+        crossterm::terminal::size().ok()
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn translate(
+    windows: bool,
+    quirks: &mut WindowsQuirks,
+    event: crossterm::event::Event,
+    window_size: impl FnOnce() -> Option<(u16, u16)>,
+) -> Option<TerminalEvent> {
+    if windows {
+        quirks.filter(event.into(), window_size)
+    } else {
+        Some(event.into())
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::{KeyCode, KeyModifiers};
+    use crossterm::event::{self as ct, KeyEventKind as Kind};
+
+    fn key(code: char, kind: Kind) -> ct::Event {
+        ct::Event::Key(ct::KeyEvent::new_with_kind(
+            ct::KeyCode::Char(code),
+            ct::KeyModifiers::NONE,
+            kind,
+        ))
+    }
+
+    fn normalized(
+        events: impl IntoIterator<Item = ct::Event>,
+        window_size: Option<(u16, u16)>,
+    ) -> Vec<TerminalEvent> {
+        let mut quirks = WindowsQuirks::default();
+        events
+            .into_iter()
+            .filter_map(|e| translate(true, &mut quirks, e, || window_size))
+            .collect()
+    }
+
+    fn expected(code: char, kind: KeyEventKind) -> TerminalEvent {
+        TerminalEvent::Key(KeyEvent {
+            code: KeyCode::Char(code),
+            modifiers: KeyModifiers::NONE,
+            kind,
+        })
+    }
+
+    #[test]
+    fn drops_duplicate_releases() {
+        let events = normalized(
+            [
+                key('a', Kind::Press),
+                key('a', Kind::Release),
+                key('a', Kind::Release),
+                key('b', Kind::Release),
+                key('a', Kind::Press),
+                key('a', Kind::Release),
+            ],
+            None,
+        );
+        assert_eq!(
+            events,
+            [
+                expected('a', KeyEventKind::Press),
+                expected('a', KeyEventKind::Release),
+                expected('b', KeyEventKind::Release),
+                expected('a', KeyEventKind::Press),
+                expected('a', KeyEventKind::Release),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_repeats() {
+        let events = normalized(
+            [
+                key('a', Kind::Press),
+                key('a', Kind::Repeat),
+                key('a', Kind::Repeat),
+                key('a', Kind::Release),
+            ],
+            None,
+        );
+        assert_eq!(
+            events,
+            [
+                expected('a', KeyEventKind::Press),
+                expected('a', KeyEventKind::Repeat),
+                expected('a', KeyEventKind::Repeat),
+                expected('a', KeyEventKind::Release),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_window_size_once() {
+        let events = normalized(
+            [
+                ct::Event::Resize(120, 9001),
+                ct::Event::Resize(120, 9001),
+                ct::Event::Resize(100, 9001),
+            ],
+            Some((80, 24)),
+        );
+        assert_eq!(events, [TerminalEvent::Resize(80, 24)]);
+
+        let events = normalized(
+            [
+                ct::Event::Resize(80, 24),
+                ct::Event::Resize(80, 24),
+                ct::Event::Resize(100, 30),
+            ],
+            None,
+        );
+        assert_eq!(
+            events,
+            [
+                TerminalEvent::Resize(80, 24),
+                TerminalEvent::Resize(100, 30)
+            ]
+        );
+    }
+
+    #[test]
+    fn passes_through_elsewhere() {
+        let mut quirks = WindowsQuirks::default();
+        let events = [ct::Event::Resize(80, 24), ct::Event::Resize(80, 24)]
+            .into_iter()
+            .filter_map(|e| translate(false, &mut quirks, e, || None))
+            .count();
+        assert_eq!(events, 2);
+    }
+}
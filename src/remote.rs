@@ -0,0 +1,448 @@
+use crate::{
+    ansi::parse_sgr_mouse,
+    clipboard::decode_osc52,
+    terminal::Setup,
+    window::{POP_TITLE, PUSH_TITLE, RESET_CURSOR_STYLE},
+    AnsiBackend, AnsiOutput, Compositor, Error, Event, KeyCode, KeyEvent, KeyModifiers,
+    TerminalCaps, TerminalEvent,
+};
+use futures_util::{stream, Stream, StreamExt};
+use std::io::{self, Write};
+
+/// Parses raw bytes typed into a terminal into [`TerminalEvent`]s.
+/// Incomplete escape sequences, UTF-8 characters and bracketed pastes are kept until the next call
+/// to [`Self::feed`], malformed ones are skipped.
+#[derive(Debug, Default)]
+pub struct InputParser {
+    buf: Vec<u8>,
+}
+
+impl InputParser {
+    /// Creates new parser.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses `bytes` appending them to the leftovers of the previous call.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<TerminalEvent> {
+        self.buf.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        let mut pos = 0;
+        while pos < self.buf.len() {
+            match parse_one(&self.buf[pos..]) {
+                Parsed::Event(event, len) => {
+                    events.extend(event);
+                    pos += len;
+                }
+                Parsed::Incomplete => break,
+            }
+        }
+        self.buf.drain(..pos);
+
+        events
+    }
+}
+
+enum Parsed {
+    /// Parsed event, if recognized, and the number of bytes consumed.
+    Event(Option<TerminalEvent>, usize),
+    Incomplete,
+}
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Option<TerminalEvent> {
+    Some(TerminalEvent::Key(KeyEvent::new(code, modifiers)))
+}
+
+fn parse_one(bytes: &[u8]) -> Parsed {
+    let none = KeyModifiers::NONE;
+
+    match bytes[0] {
+        // Lone escape can't be told apart from the start of a sequence split across reads,
+        // treat it as the escape key when nothing follows.
+        0x1b if bytes.len() == 1 => Parsed::Event(key(KeyCode::Esc, none), 1),
+        0x1b => match bytes[1] {
+            b'[' => parse_csi(bytes),
+            // Replies start with a numeric command, anything else is Alt+].
+            b']' if bytes.get(2).is_some_and(u8::is_ascii_digit) => parse_osc(bytes),
+            b'O' if bytes.len() < 3 => Parsed::Incomplete,
+            b'O' => {
+                let code = match bytes[2] {
+                    b'P' => KeyCode::F(1),
+                    b'Q' => KeyCode::F(2),
+                    b'R' => KeyCode::F(3),
+                    b'S' => KeyCode::F(4),
+                    b'A' => KeyCode::Up,
+                    b'B' => KeyCode::Down,
+                    b'C' => KeyCode::Right,
+                    b'D' => KeyCode::Left,
+                    b'H' => KeyCode::Home,
+                    b'F' => KeyCode::End,
+                    _ => return Parsed::Event(None, 3),
+                };
+                Parsed::Event(key(code, none), 3)
+            }
+            _ => match parse_one(&bytes[1..]) {
+                Parsed::Event(Some(TerminalEvent::Key(mut ke)), len) => {
+                    ke.modifiers |= KeyModifiers::ALT;
+                    Parsed::Event(Some(TerminalEvent::Key(ke)), len + 1)
+                }
+                Parsed::Event(event, len) => Parsed::Event(event, len + 1),
+                Parsed::Incomplete => Parsed::Incomplete,
+            },
+        },
+        b'\r' | b'\n' => Parsed::Event(key(KeyCode::Enter, none), 1),
+        b'\t' => Parsed::Event(key(KeyCode::Tab, none), 1),
+        0x7f | 0x08 => Parsed::Event(key(KeyCode::Backspace, none), 1),
+        0x00 => Parsed::Event(key(KeyCode::Char(' '), KeyModifiers::CONTROL), 1),
+        c @ 0x01..=0x1a => Parsed::Event(
+            key(KeyCode::Char((c - 1 + b'a') as char), KeyModifiers::CONTROL),
+            1,
+        ),
+        c => {
+            let len = match c {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 1,
+            };
+            // Invalid bytes are skipped one by one, so the byte breaking a character is parsed on its own.
+            if bytes[1..len.min(bytes.len())]
+                .iter()
+                .any(|b| b & 0xc0 != 0x80)
+            {
+                return Parsed::Event(None, 1);
+            }
+            if bytes.len() < len {
+                return Parsed::Incomplete;
+            }
+
+            let Some(event) = std::str::from_utf8(&bytes[..len])
+                .ok()
+                .and_then(|s| s.chars().next())
+                .and_then(|ch| {
+                    let modifiers = if ch.is_uppercase() {
+                        KeyModifiers::SHIFT
+                    } else {
+                        none
+                    };
+                    key(KeyCode::Char(ch), modifiers)
+                })
+            else {
+                return Parsed::Event(None, 1);
+            };
+            Parsed::Event(Some(event), len)
+        }
+    }
+}
+
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+fn parse_csi(bytes: &[u8]) -> Parsed {
+    // Parameter and intermediate bytes are in `0x20..=0x3f`, the final byte is in `0x40..=0x7e`.
+    let Some(end) = bytes
+        .iter()
+        .skip(2)
+        .position(|b| !(0x20..=0x3f).contains(b))
+        .map(|i| i + 2)
+    else {
+        return Parsed::Incomplete;
+    };
+    if !(0x40..=0x7e).contains(&bytes[end]) {
+        // Malformed sequence, the byte breaking it is parsed on its own.
+        return Parsed::Event(None, end);
+    }
+    let len = end + 1;
+    if bytes[..len] == *PASTE_START {
+        return parse_paste(bytes);
+    }
+    // Parameter bytes are ASCII.
+    let seq = std::str::from_utf8(&bytes[..len]).expect("sequence is ASCII");
+
+    if seq.starts_with("\x1b[<") {
+        return Parsed::Event(parse_sgr_mouse(seq).map(TerminalEvent::Mouse), len);
+    }
+
+    let mut params = seq[2..end].split(';').map(|p| p.parse::<u16>().ok());
+    let first = params.next().flatten();
+    let modifiers = match params.next().flatten() {
+        Some(m) => {
+            let m = m.saturating_sub(1);
+            let mut modifiers = KeyModifiers::NONE;
+            modifiers.set(KeyModifiers::SHIFT, m & 1 != 0);
+            modifiers.set(KeyModifiers::ALT, m & 2 != 0);
+            modifiers.set(KeyModifiers::CONTROL, m & 4 != 0);
+            modifiers
+        }
+        None => KeyModifiers::NONE,
+    };
+
+    let code = match (bytes[end], first) {
+        (b'A', _) => KeyCode::Up,
+        (b'B', _) => KeyCode::Down,
+        (b'C', _) => KeyCode::Right,
+        (b'D', _) => KeyCode::Left,
+        (b'H', _) => KeyCode::Home,
+        (b'F', _) => KeyCode::End,
+        (b'Z', _) => return Parsed::Event(key(KeyCode::BackTab, KeyModifiers::SHIFT), len),
+        (b'I', None) => return Parsed::Event(Some(TerminalEvent::FocusGained), len),
+        (b'O', None) => return Parsed::Event(Some(TerminalEvent::FocusLost), len),
+        (b'~', Some(n)) => match n {
+            1 | 7 => KeyCode::Home,
+            2 => KeyCode::Insert,
+            3 => KeyCode::Delete,
+            4 | 8 => KeyCode::End,
+            5 => KeyCode::PageUp,
+            6 => KeyCode::PageDown,
+            11..=15 => KeyCode::F((n - 10) as u8),
+            17..=21 => KeyCode::F((n - 11) as u8),
+            23 | 24 => KeyCode::F((n - 12) as u8),
+            _ => return Parsed::Event(None, len),
+        },
+        _ => return Parsed::Event(None, len),
+    };
+
+    Parsed::Event(key(code, modifiers), len)
+}
+
+/// Text pasted with bracketed paste, `bytes` start with [`PASTE_START`].
+fn parse_paste(bytes: &[u8]) -> Parsed {
+    let text = &bytes[PASTE_START.len()..];
+    let Some(end) = text
+        .windows(PASTE_END.len())
+        .position(|window| window == PASTE_END)
+    else {
+        return Parsed::Incomplete;
+    };
+
+    let paste = String::from_utf8_lossy(&text[..end]).into_owned();
+    let len = PASTE_START.len() + end + PASTE_END.len();
+    Parsed::Event(Some(TerminalEvent::Paste(paste)), len)
+}
+
+fn parse_osc(bytes: &[u8]) -> Parsed {
+    // Operating system command is terminated by BEL or ST (`ESC \`).
+    let body = &bytes[2..];
+    for (i, b) in body.iter().enumerate() {
+        let terminator = match (b, body.get(i + 1)) {
+            (0x07, _) => 1,
+            (0x1b, Some(b'\\')) => 2,
+            (0x1b, None) => return Parsed::Incomplete,
+            // Malformed command, the escape starts the next sequence.
+            (0x1b, Some(_)) => return Parsed::Event(None, i + 2),
+            _ => continue,
+        };
+
+        let event = std::str::from_utf8(&body[..i])
+            .ok()
+            .and_then(decode_osc52)
+            .map(TerminalEvent::Clipboard);
+        return Parsed::Event(event, i + 2 + terminator);
+    }
+    Parsed::Incomplete
+}
+
+/// [`AnsiOutput`] that enables alternate screen and mouse reporting on creation and restores them when dropped.
+struct RemoteOutput<W: AnsiOutput>(W);
+
+impl<W: AnsiOutput> RemoteOutput<W> {
+    fn new(mut output: W) -> io::Result<Self> {
+        output.write_all(PUSH_TITLE.as_bytes())?;
+        output.write_all(b"\x1b[?1049h\x1b[?1000h\x1b[?1003h\x1b[?1006h\x1b[2J")?;
+        Ok(Self(output))
+    }
+}
+
+impl<W: AnsiOutput> io::Write for RemoteOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<W: AnsiOutput> AnsiOutput for RemoteOutput<W> {
+    fn size(&self) -> io::Result<(u16, u16)> {
+        self.0.size()
+    }
+}
+
+impl<W: AnsiOutput> Drop for RemoteOutput<W> {
+    fn drop(&mut self) {
+        _ = self
+            .0
+            .write_all(b"\x1b[?1006l\x1b[?1003l\x1b[?1000l\x1b[?25h\x1b[?1049l");
+        _ = self.0.write_all(RESET_CURSOR_STYLE.as_bytes());
+        _ = self.0.write_all(POP_TITLE.as_bytes());
+        _ = self.0.flush();
+    }
+}
+
+/// Remote terminal functions
+impl<S: 'static, E: 'static> Compositor<S, E> {
+    /// Runs the compositor on a remote terminal, e.g. an SSH channel, leaving the local terminal untouched.
+    /// `input` yields raw bytes typed by the remote user, frames are written to `output`.
+    /// Resizes of the remote terminal should be reported by `output` size and a [`TerminalEvent::Resize`] stream.
+    pub async fn serve<W: AnsiOutput>(
+        self,
+        input: impl Stream<Item = Vec<u8>> + 'static,
+        output: W,
+    ) -> Result<S, Error> {
+        self.serve_mirrored([(input, output)]).await
+    }
+
+    /// Same as [`Self::serve`] but for multiple clients, all of them see the same ui and their input is merged.
+    pub async fn serve_mirrored<I, W>(
+        mut self,
+        clients: impl IntoIterator<Item = (I, W)>,
+    ) -> Result<S, Error>
+    where
+        I: Stream<Item = Vec<u8>> + 'static,
+        W: AnsiOutput,
+    {
+        let mut backends = Vec::new();
+        for (input, output) in clients {
+            let mut parser = InputParser::new();
+            let events = input
+                .flat_map(move |bytes| stream::iter(parser.feed(&bytes)))
+                .map(Event::Terminal);
+
+            self = self.with_stream(events);
+            backends.push(AnsiBackend::new(RemoteOutput::new(output)?));
+        }
+
+        self.setup = Setup::None;
+        if self.detect_caps {
+            // Remote terminal is unknown, OSC sequences are ignored by terminals that don't support them.
+            self.caps = TerminalCaps {
+                osc52: true,
+                ..Default::default()
+            };
+        }
+        self.run_with(
+            backends,
+            Some(|backend, bytes| {
+                let output = backend.output_mut();
+                output.write_all(bytes)?;
+                output.flush()
+            }),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MouseButton, MouseEvent, MouseEventKind};
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> TerminalEvent {
+        TerminalEvent::Key(KeyEvent::new(code, modifiers))
+    }
+
+    fn char(ch: char) -> TerminalEvent {
+        press(KeyCode::Char(ch), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn sequences_split_across_reads() {
+        let mut parser = InputParser::new();
+        assert_eq!(parser.feed(b"\x1b[1;"), []);
+        assert_eq!(parser.feed(b"5"), []);
+        assert_eq!(
+            parser.feed(b"Aa"),
+            [press(KeyCode::Up, KeyModifiers::CONTROL), char('a')]
+        );
+
+        assert_eq!(parser.feed(&"é".as_bytes()[..1]), []);
+        assert_eq!(parser.feed(&"é".as_bytes()[1..]), [char('é')]);
+
+        assert_eq!(parser.feed(b"\x1b]52;c;aGk="), []);
+        assert_eq!(parser.feed(b"\x1b"), []);
+        assert_eq!(
+            parser.feed(b"\\"),
+            [TerminalEvent::Clipboard("hi".to_owned())]
+        );
+    }
+
+    #[test]
+    fn sgr_mouse() {
+        let mut parser = InputParser::new();
+        assert_eq!(
+            parser.feed(b"\x1b[<0;10;5M\x1b[<0;10;5m"),
+            [
+                TerminalEvent::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column: 9,
+                    row: 4,
+                    modifiers: KeyModifiers::NONE,
+                }),
+                TerminalEvent::Mouse(MouseEvent {
+                    kind: MouseEventKind::Up(MouseButton::Left),
+                    column: 9,
+                    row: 4,
+                    modifiers: KeyModifiers::NONE,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn bracketed_paste() {
+        let mut parser = InputParser::new();
+        assert_eq!(parser.feed(b"\x1b[200~line\r\n\x1b[A"), []);
+        assert_eq!(
+            parser.feed(b"\x1b[201~x"),
+            [TerminalEvent::Paste("line\r\n\x1b[A".to_owned()), char('x')]
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut parser = InputParser::new();
+        assert_eq!(parser.feed(b"\xc3(\x80\xffa"), [char('('), char('a')]);
+        assert_eq!(parser.feed(b"\xe2\x82"), []);
+        assert_eq!(parser.feed(b"b"), [char('b')]);
+    }
+
+    #[test]
+    fn truncated_csi() {
+        let mut parser = InputParser::new();
+        assert_eq!(
+            parser.feed(b"\x1b[1\r"),
+            [press(KeyCode::Enter, KeyModifiers::NONE)]
+        );
+        assert_eq!(
+            parser.feed(b"\x1b[\x1b[B"),
+            [press(KeyCode::Down, KeyModifiers::NONE)]
+        );
+        assert_eq!(
+            parser.feed(b"\x1b]52;\x1bOP"),
+            [press(KeyCode::F(1), KeyModifiers::NONE)]
+        );
+    }
+
+    #[test]
+    fn short_sequences_dont_panic() {
+        for prefix in [
+            &b"\x1b"[..],
+            b"\x1b[",
+            b"\x1b]",
+            b"\x1bO",
+            b"\x1b[<",
+            b"\x1b]5",
+        ] {
+            for a in 0..=u8::MAX {
+                for b in 0..=u8::MAX {
+                    let mut parser = InputParser::new();
+                    parser.feed(prefix);
+                    parser.feed(&[a]);
+                    parser.feed(&[b]);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+/// Services shared by all components that aren't part of the ui, e.g. database handles, http clients
+/// or configuration, keyed by their type. See [`Compositor::insert_resource`](crate::Compositor::insert_resource)
+/// and [`Context::resource`](crate::Context::resource).
+///
+/// ```
+/// # use gland::{Compositor, Component, Context, Event, Id, LayerId};
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// struct Config {
+///     greeting: String,
+/// }
+///
+/// struct Greeter;
+///
+/// impl Component for Greeter {
+///     fn id(&self) -> Id {
+///         Id::new("greeter")
+///     }
+///
+///     fn view(&self, _area: Rect, _buf: &mut Buffer, _state: &()) {}
+///
+///     fn handle_event(&mut self, _event: &mut Event, cx: &mut Context) {
+///         let config = cx.resource::<Config>().expect("config is inserted on start");
+///         println!("{}", config.greeting);
+///     }
+/// }
+///
+/// let mut comp = Compositor::new().with_resource(Config { greeting: "Hello".into() });
+/// _ = comp.insert_at(LayerId::MIDDLE, Greeter);
+/// ```
+#[derive(Default)]
+pub struct Resources {
+    values: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Resources {
+    /// Returns the resource of type `T`.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Returns the resource of type `T` mutably.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+
+    /// Returns `true` if there is a resource of type `T`.
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Adds `resource`, returns the previous resource of type `T`.
+    pub fn insert<T: 'static>(&mut self, resource: T) -> Option<T> {
+        let old = self.values.insert(TypeId::of::<T>(), Box::new(resource))?;
+        old.downcast().ok().map(|old| *old)
+    }
+
+    /// Removes the resource of type `T`.
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        let old = self.values.remove(&TypeId::of::<T>())?;
+        old.downcast().ok().map(|old| *old)
+    }
+}
@@ -0,0 +1,256 @@
+use futures_util::{
+    future::{BoxFuture, LocalBoxFuture},
+    stream::{self, BoxStream},
+    task::{waker, ArcWake},
+    StreamExt,
+};
+use std::{
+    cell::RefCell,
+    future::poll_fn,
+    mem::take,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+/// Async runtime used by the compositor to spawn jobs and drive timers.
+/// Implemented for tokio and smol behind the features of the same name.
+pub trait Runtime: Send + Sync + 'static {
+    /// Spawns a future in the background, the future's result is not awaited.
+    fn spawn(&self, future: BoxFuture<'static, ()>);
+
+    /// Spawns a `!Send` future on the thread driving [`Self::run_local`].
+    ///
+    /// # Panics
+    /// Panics by default, runtimes supporting local futures override this and [`Self::run_local`].
+    fn spawn_local(&self, future: LocalBoxFuture<'static, ()>) {
+        drop(future);
+        panic!("runtime doesn't support local jobs");
+    }
+
+    /// Drives `future` together with futures spawned with [`Self::spawn_local`].
+    /// The compositor runs its event loop inside of it.
+    fn run_local<'a>(&self, future: LocalBoxFuture<'a, ()>) -> LocalBoxFuture<'a, ()> {
+        future
+    }
+
+    /// Returns a stream that yields every `period`, the first time after one `period` has passed.
+    fn interval(&self, period: Duration) -> BoxStream<'static, ()>;
+
+    /// Returns a future that resolves after `duration`.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let mut interval = self.interval(duration);
+        Box::pin(async move {
+            interval.next().await;
+        })
+    }
+}
+
+/// Runtime used when none was set with [`Compositor::with_runtime`](crate::Compositor::with_runtime).
+/// Tokio is only used inside of a tokio runtime, e.g. jobs spawned from [`Compositor::dispatch`](crate::Compositor::dispatch)
+/// called from a plain `main` use other runtimes.
+pub(crate) fn default_runtime() -> Option<Arc<dyn Runtime>> {
+    #[cfg(feature = "tokio")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Some(Arc::new(TokioRuntime));
+    }
+    #[cfg(feature = "smol")]
+    return Some(Arc::new(SmolRuntime));
+    #[cfg(all(not(feature = "smol"), feature = "wasm"))]
+    return Some(Arc::new(crate::wasm::WasmRuntime));
+    #[allow(unreachable_code)]
+    None
+}
+
+/// Tokio runtime, jobs are spawned with `tokio::spawn`,
+/// local jobs on a `LocalSet` driving the compositor's event loop.
+#[cfg(feature = "tokio")]
+#[doc(cfg(feature = "tokio"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioRuntime;
+
+#[cfg(feature = "tokio")]
+impl Runtime for TokioRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        tokio::spawn(future);
+    }
+
+    fn spawn_local(&self, future: LocalBoxFuture<'static, ()>) {
+        tokio::task::spawn_local(future);
+    }
+
+    fn run_local<'a>(&self, future: LocalBoxFuture<'a, ()>) -> LocalBoxFuture<'a, ()> {
+        Box::pin(async move { tokio::task::LocalSet::new().run_until(future).await })
+    }
+
+    fn interval(&self, period: Duration) -> BoxStream<'static, ()> {
+        use tokio::time::{interval_at, Instant};
+        use tokio_stream::wrappers::IntervalStream;
+
+        IntervalStream::new(interval_at(Instant::now() + period, period))
+            .map(|_| ())
+            .boxed()
+    }
+}
+
+/// Smol runtime, jobs are spawned on smol's global executor,
+/// local jobs on a thread-local executor driving the compositor's event loop.
+#[cfg(feature = "smol")]
+#[doc(cfg(feature = "smol"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmolRuntime;
+
+#[cfg(feature = "smol")]
+thread_local! {
+    static SMOL_LOCAL: std::rc::Rc<smol::LocalExecutor<'static>> = Default::default();
+}
+
+#[cfg(feature = "smol")]
+impl Runtime for SmolRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        smol::spawn(future).detach();
+    }
+
+    fn spawn_local(&self, future: LocalBoxFuture<'static, ()>) {
+        SMOL_LOCAL.with(|executor| executor.spawn(future).detach());
+    }
+
+    fn run_local<'a>(&self, future: LocalBoxFuture<'a, ()>) -> LocalBoxFuture<'a, ()> {
+        let executor = SMOL_LOCAL.with(std::rc::Rc::clone);
+        Box::pin(async move { executor.run(future).await })
+    }
+
+    fn interval(&self, period: Duration) -> BoxStream<'static, ()> {
+        smol::Timer::interval(period).map(|_| ()).boxed()
+    }
+}
+
+/// Runtime without an async executor, jobs run on a thread pool. Used by `run_blocking`.
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub(crate) struct ThreadRuntime {
+    pool: futures_executor::ThreadPool,
+}
+
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+impl ThreadRuntime {
+    /// Starts a pool with a thread per CPU.
+    pub(crate) fn new() -> std::io::Result<Self> {
+        futures_executor::ThreadPool::builder()
+            .name_prefix("gland-job-")
+            .create()
+            .map(|pool| Self { pool })
+    }
+}
+
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+impl Runtime for ThreadRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        self.pool.spawn_ok(future);
+    }
+
+    fn interval(&self, period: Duration) -> BoxStream<'static, ()> {
+        let (tx, rx) = futures_channel::mpsc::unbounded();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(period);
+            if tx.unbounded_send(()).is_err() {
+                break;
+            }
+        });
+
+        rx.boxed()
+    }
+}
+
+/// Runtime polling jobs only when drained, used by the testing harness so jobs finish between steps
+/// deterministically, without threads or an async runtime. Timers are checked when drained.
+#[derive(Clone, Default)]
+pub(crate) struct InlineRuntime {
+    tasks: Arc<Mutex<Vec<BoxFuture<'static, ()>>>>,
+}
+
+thread_local! {
+    static INLINE_LOCAL: RefCell<Vec<LocalBoxFuture<'static, ()>>> = Default::default();
+}
+
+/// Set when a task polled by [`InlineRuntime::drain`] is woken.
+struct Woken(AtomicBool);
+
+impl ArcWake for Woken {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl InlineRuntime {
+    /// Polls spawned jobs until none of them can make progress, jobs waiting for timers
+    /// that haven't expired yet are polled again on the next call.
+    pub(crate) fn drain(&self) {
+        let woken = Arc::new(Woken(AtomicBool::new(true)));
+        let waker = waker(woken.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        while woken.0.swap(false, Ordering::Relaxed) {
+            let tasks = take(&mut *self.tasks.lock().unwrap());
+            let mut pending = tasks
+                .into_iter()
+                .filter_map(|mut task| task.as_mut().poll(&mut cx).is_pending().then_some(task))
+                .collect::<Vec<_>>();
+            let local = INLINE_LOCAL.with(|local| take(&mut *local.borrow_mut()));
+            let mut pending_local = local
+                .into_iter()
+                .filter_map(|mut task| task.as_mut().poll(&mut cx).is_pending().then_some(task))
+                .collect::<Vec<_>>();
+
+            // Jobs spawned by other jobs are polled in the next round.
+            let mut tasks = self.tasks.lock().unwrap();
+            let spawned = INLINE_LOCAL.with(|local| {
+                let mut local = local.borrow_mut();
+                let spawned = !tasks.is_empty() || !local.is_empty();
+                pending_local.append(&mut local);
+                *local = pending_local;
+                spawned
+            });
+            pending.append(&mut tasks);
+            *tasks = pending;
+            if spawned {
+                woken.0.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl Runtime for InlineRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        self.tasks.lock().unwrap().push(future);
+    }
+
+    fn spawn_local(&self, future: LocalBoxFuture<'static, ()>) {
+        INLINE_LOCAL.with(|local| local.borrow_mut().push(future));
+    }
+
+    fn interval(&self, period: Duration) -> BoxStream<'static, ()> {
+        let mut next = Instant::now() + period;
+        stream::poll_fn(move |_| {
+            if Instant::now() < next {
+                return Poll::Pending;
+            }
+            next += period;
+            Poll::Ready(Some(()))
+        })
+        .boxed()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let deadline = Instant::now() + duration;
+        Box::pin(poll_fn(move |_| {
+            if Instant::now() < deadline {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        }))
+    }
+}
@@ -0,0 +1,325 @@
+//! Scripted components written in [Rhai](https://rhai.rs), allowing applications to be extended by users.
+//!
+//! Every script is a component. Functions of the script are called with `this` bound to a map of
+//! state slices the application exposed with [`ScriptComponent::with_slice`], changes made to it
+//! are written back to the state, except in `view`.
+//!
+//! ```rhai
+//! bind("ctrl-n", "increment");
+//!
+//! fn increment() {
+//!     this.count += 1;
+//!     after(1000, "reset");
+//! }
+//!
+//! fn reset() {
+//!     this.count = 0;
+//! }
+//!
+//! fn view(canvas) {
+//!     canvas.text(0, 0, `count: ${this.count}`, #{ fg: "yellow", bold: true });
+//! }
+//! ```
+//!
+//! Available functions:
+//! - `bind(key, fn)` calls `fn` when `key` like `ctrl-s` is pressed, the key event is consumed.
+//! - `after(ms, fn)` spawns a job that calls `fn` after `ms` milliseconds.
+//! - `canvas.text(x, y, text)` and `canvas.text(x, y, text, style)` draw text relative to the component's area,
+//!   `style` is a map with optional `fg`, `bg` colors and `bold`, `dim`, `italic`, `underlined`, `reversed` flags.
+//! - `canvas.width` and `canvas.height` return the size of the area.
+
+use crate::{
+    Component, Compositor, Context, Event, Id, Jobs, KeyEvent, KeyEventKind, TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+};
+use std::{cell::RefCell, marker::PhantomData, rc::Rc, time::Duration};
+
+pub use rhai;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+
+/// Bindings and timers registered by the script while it runs.
+#[derive(Default)]
+struct Registry {
+    bindings: Vec<(KeyEvent, String)>,
+    timers: Vec<(u64, String)>,
+}
+
+/// Text drawn at `(x, y)` with a style.
+type TextOp = (i64, i64, String, Style);
+
+/// Drawing surface passed to the script's `view` function.
+#[derive(Clone)]
+struct Canvas {
+    width: i64,
+    height: i64,
+    ops: Rc<RefCell<Vec<TextOp>>>,
+}
+
+type SliceSetter<S> = Box<dyn Fn(&mut S, Dynamic)>;
+
+struct Slice<S> {
+    name: String,
+    get: Box<dyn Fn(&S) -> Dynamic>,
+    set: SliceSetter<S>,
+}
+
+/// Component driven by a Rhai script, see the [module](self) documentation.
+pub struct ScriptComponent<S = (), E = ()> {
+    id: Id,
+    engine: Engine,
+    ast: AST,
+    registry: Rc<RefCell<Registry>>,
+    slices: Vec<Slice<S>>,
+    jobs: Option<Jobs<S, E>>,
+    error: Option<String>,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<S: Send + 'static, E: Send + 'static> ScriptComponent<S, E> {
+    /// Compiles `source` and runs its top level statements, e.g. key bindings.
+    pub fn new(id: Id, source: &str) -> Result<Self, Box<EvalAltResult>> {
+        let registry = Rc::new(RefCell::new(Registry::default()));
+        let engine = engine(&registry);
+        let ast = engine.compile(source)?;
+        engine.run_ast(&ast)?;
+
+        Ok(Self {
+            id,
+            engine,
+            ast,
+            registry,
+            slices: Vec::new(),
+            jobs: None,
+            error: None,
+            _event: PhantomData,
+        })
+    }
+
+    /// Exposes a slice of the state to the script as `this.<name>`.
+    pub fn with_slice(
+        mut self,
+        name: impl Into<String>,
+        get: impl Fn(&S) -> Dynamic + 'static,
+        set: impl Fn(&mut S, Dynamic) + 'static,
+    ) -> Self {
+        self.slices.push(Slice {
+            name: name.into(),
+            get: Box::new(get),
+            set: Box::new(set),
+        });
+        self
+    }
+
+    /// Returns the last error raised by the script.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn this(&self, state: &S) -> Dynamic {
+        let map = self
+            .slices
+            .iter()
+            .map(|s| (s.name.as_str().into(), (s.get)(state)))
+            .collect::<Map>();
+        Dynamic::from_map(map)
+    }
+
+    fn call(
+        &self,
+        name: &str,
+        args: impl rhai::FuncArgs,
+        this: &mut Dynamic,
+    ) -> Result<(), Box<EvalAltResult>> {
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(this);
+        self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &self.ast, name, args)
+            .map(drop)
+    }
+
+    /// Calls script function `name`, writes state slices back and schedules timers it started.
+    fn run(&mut self, name: &str, state: &mut S) {
+        let mut this = self.this(state);
+        match self.call(name, (), &mut this) {
+            Ok(()) => {
+                if let Some(map) = this.try_cast::<Map>() {
+                    for slice in self.slices.iter() {
+                        if let Some(value) = map.get(slice.name.as_str()) {
+                            (slice.set)(state, value.clone());
+                        }
+                    }
+                }
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+
+        let Some(jobs) = self.jobs.as_ref() else {
+            return;
+        };
+        for (ms, name) in self.registry.borrow_mut().timers.drain(..) {
+            let (id, sleep) = (self.id, jobs.sleep(Duration::from_millis(ms)));
+            jobs.spawn(async move {
+                sleep.await;
+                move |comp: &mut Compositor<S, E>| {
+                    if let Some((script, state)) = comp.find_mut::<Self>(id) {
+                        script.run(&name, state);
+                    }
+                }
+            });
+        }
+    }
+}
+
+impl<S: Send + 'static, E: Send + 'static> Component<S, E> for ScriptComponent<S, E> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        let canvas = Canvas {
+            width: area.width.into(),
+            height: area.height.into(),
+            ops: Rc::default(),
+        };
+
+        let has_view = self.ast.iter_functions().any(|f| f.name == "view");
+        let result = if has_view {
+            self.call("view", (canvas.clone(),), &mut self.this(state))
+        } else {
+            Ok(())
+        };
+
+        for (x, y, text, style) in canvas.ops.borrow().iter() {
+            let (Ok(x), Ok(y)) = (u16::try_from(*x), u16::try_from(*y)) else {
+                continue;
+            };
+            if x < area.width && y < area.height {
+                buf.set_stringn(
+                    area.x + x,
+                    area.y + y,
+                    text,
+                    (area.width - x).into(),
+                    *style,
+                );
+            }
+        }
+
+        let error = result.err().map(|e| e.to_string());
+        if let Some(error) = error.as_deref().or(self.error.as_deref()) {
+            if area.height > 0 {
+                let style = Style::new().fg(Color::White).bg(Color::Red);
+                buf.set_stringn(area.x, area.bottom() - 1, error, area.width.into(), style);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        if self.jobs.is_none() {
+            self.jobs = Some(cx.jobs().clone());
+        }
+
+        let Some(TerminalEvent::Key(key)) = event.as_terminal() else {
+            return;
+        };
+        if key.kind == KeyEventKind::Release {
+            return;
+        }
+
+        let binding = self
+            .registry
+            .borrow()
+            .bindings
+            .iter()
+            .find(|(k, _)| (k.code, k.modifiers) == (key.code, key.modifiers))
+            .map(|(_, name)| name.clone());
+        if let Some(name) = binding {
+            event.consume();
+            self.run(&name, cx.state_mut());
+        }
+    }
+}
+
+fn engine(registry: &Rc<RefCell<Registry>>) -> Engine {
+    let mut engine = Engine::new();
+
+    let reg = registry.clone();
+    engine.register_fn(
+        "bind",
+        move |key: &str, name: &str| -> Result<(), Box<EvalAltResult>> {
+            let key = key.parse::<KeyEvent>().map_err(|e| e.to_string())?;
+            reg.borrow_mut().bindings.push((key, name.to_owned()));
+            Ok(())
+        },
+    );
+
+    let reg = registry.clone();
+    engine.register_fn("after", move |ms: i64, name: &str| {
+        reg.borrow_mut()
+            .timers
+            .push((ms.max(0) as u64, name.to_owned()));
+    });
+
+    engine
+        .register_type_with_name::<Canvas>("Canvas")
+        .register_get("width", |c: &mut Canvas| c.width)
+        .register_get("height", |c: &mut Canvas| c.height)
+        .register_fn("text", |c: &mut Canvas, x: i64, y: i64, text: &str| {
+            c.ops
+                .borrow_mut()
+                .push((x, y, text.to_owned(), Style::new()));
+        })
+        .register_fn(
+            "text",
+            |c: &mut Canvas,
+             x: i64,
+             y: i64,
+             text: &str,
+             style: Map|
+             -> Result<(), Box<EvalAltResult>> {
+                let style = parse_style(&style)?;
+                c.ops.borrow_mut().push((x, y, text.to_owned(), style));
+                Ok(())
+            },
+        );
+
+    engine
+}
+
+fn parse_style(map: &Map) -> Result<Style, Box<EvalAltResult>> {
+    let color = |key: &str| -> Result<Option<Color>, Box<EvalAltResult>> {
+        match map.get(key) {
+            Some(value) => {
+                let name = value.clone().into_string()?;
+                let color = name.parse().map_err(|_| format!("invalid color: {name}"))?;
+                Ok(Some(color))
+            }
+            None => Ok(None),
+        }
+    };
+
+    let mut style = Style::new();
+    if let Some(fg) = color("fg")? {
+        style = style.fg(fg);
+    }
+    if let Some(bg) = color("bg")? {
+        style = style.bg(bg);
+    }
+
+    const MODIFIERS: [(&str, Modifier); 5] = [
+        ("bold", Modifier::BOLD),
+        ("dim", Modifier::DIM),
+        ("italic", Modifier::ITALIC),
+        ("underlined", Modifier::UNDERLINED),
+        ("reversed", Modifier::REVERSED),
+    ];
+    for (key, modifier) in MODIFIERS {
+        if map.get(key).and_then(|v| v.as_bool().ok()) == Some(true) {
+            style = style.add_modifier(modifier);
+        }
+    }
+
+    Ok(style)
+}
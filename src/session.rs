@@ -0,0 +1,69 @@
+use crate::{Compositor, Event};
+use futures_channel::mpsc;
+use futures_util::{Stream, StreamExt};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Application state shared between multiple compositors, each driving its own terminal session
+/// with its own size and components.
+pub struct SharedState<T> {
+    state: Arc<Mutex<T>>,
+    subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<()>>>>,
+}
+
+impl<T> Clone for SharedState<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
+
+impl<T> SharedState<T> {
+    /// Creates new shared state.
+    pub fn new(state: T) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(state)),
+            subscribers: Arc::default(),
+        }
+    }
+
+    /// Locks the state without notifying other sessions.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Modifies the state and notifies all sessions so they re-render.
+    pub fn update<R>(&self, func: impl FnOnce(&mut T) -> R) -> R {
+        let out = func(&mut self.lock());
+        self.notify();
+        out
+    }
+
+    /// Notifies all sessions that the state has changed.
+    pub fn notify(&self) {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|tx| tx.unbounded_send(()).is_ok());
+    }
+
+    /// Returns a stream that yields every time the state is updated.
+    pub fn changes(&self) -> impl Stream<Item = ()> {
+        let (tx, rx) = mpsc::unbounded();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(tx);
+        rx
+    }
+}
+
+/// Shared state functions
+impl<T: 'static, E: 'static> Compositor<SharedState<T>, E> {
+    /// Re-renders the ui every time the shared state is updated by any session.
+    pub fn with_shared_updates(self) -> Self {
+        let changes = self.state().changes();
+        self.with_stream(changes.map(|_| Event::Tick))
+    }
+}
@@ -0,0 +1,208 @@
+use crate::Id;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Part of the [`StatusBar`] a segment is shown in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// Text shown in the [`StatusBar`], e.g. the current mode,
+/// see [`Context::set_status`](crate::Context::set_status).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusSegment {
+    pub align: StatusAlign,
+    pub line: Line<'static>,
+    /// Segments with lower priority are shown first within their part of the bar.
+    pub priority: i32,
+}
+
+impl StatusSegment {
+    pub fn new(align: StatusAlign, line: impl Into<Line<'static>>) -> Self {
+        Self {
+            align,
+            line: line.into(),
+            priority: 0,
+        }
+    }
+
+    pub fn left(line: impl Into<Line<'static>>) -> Self {
+        Self::new(StatusAlign::Left, line)
+    }
+
+    pub fn center(line: impl Into<Line<'static>>) -> Self {
+        Self::new(StatusAlign::Center, line)
+    }
+
+    pub fn right(line: impl Into<Line<'static>>) -> Self {
+        Self::new(StatusAlign::Right, line)
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// Row at the bottom of the screen showing segments registered by components and plugins,
+/// enabled with [`Compositor::with_status_bar`](crate::Compositor::with_status_bar).
+/// Layers are laid out above it, so no component overlaps it.
+///
+/// The left part is drawn first, then the right part if it fits next to it,
+/// then the center part if it fits between them.
+pub struct StatusBar {
+    style: Style,
+    separator: Span<'static>,
+    key_hints: bool,
+    job_progress: bool,
+    /// Segments with their owners and names, owned segments are removed with their components.
+    segments: Vec<(Option<Id>, &'static str, StatusSegment)>,
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder functions
+impl StatusBar {
+    pub fn new() -> Self {
+        Self {
+            style: Style::new().add_modifier(Modifier::REVERSED),
+            separator: Span::raw(" │ "),
+            key_hints: false,
+            job_progress: false,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Sets style of the whole row, reversed colors by default.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets text between segments in the same part of the bar, ` │ ` by default.
+    pub fn with_separator(mut self, separator: impl Into<Span<'static>>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Shows keys bound to actions of the focused component in the center,
+    /// see [`Component::actions`](crate::Component::actions).
+    pub fn with_key_hints(mut self) -> Self {
+        self.key_hints = true;
+        self
+    }
+
+    /// Shows the number of running jobs on the right while there are any.
+    pub fn with_job_progress(mut self) -> Self {
+        self.job_progress = true;
+        self
+    }
+
+    /// Shows `segment` under `name` until it is replaced or cleared.
+    pub fn with_segment(mut self, name: &'static str, segment: StatusSegment) -> Self {
+        self.set(None, name, segment);
+        self
+    }
+}
+
+/// Non-builder functions
+impl StatusBar {
+    /// Returns segments with their names in the order they are shown in their parts of the bar.
+    pub fn segments(&self) -> impl Iterator<Item = (&'static str, &StatusSegment)> + '_ {
+        self.segments.iter().map(|(_, name, s)| (*name, s))
+    }
+
+    pub(crate) fn key_hints(&self) -> bool {
+        self.key_hints
+    }
+
+    pub(crate) fn job_progress(&self) -> bool {
+        self.job_progress
+    }
+
+    /// Replaces the segment `owner` has under `name`, segments keep their place among equal priorities.
+    pub(crate) fn set(&mut self, owner: Option<Id>, name: &'static str, segment: StatusSegment) {
+        match self
+            .segments
+            .iter_mut()
+            .find(|(o, n, _)| *o == owner && *n == name)
+        {
+            Some((_, _, old)) => *old = segment,
+            None => self.segments.push((owner, name, segment)),
+        }
+        self.segments.sort_by_key(|(_, _, s)| s.priority);
+    }
+
+    pub(crate) fn clear(&mut self, owner: Option<Id>, name: &'static str) {
+        self.segments
+            .retain(|(o, n, _)| !(*o == owner && *n == name));
+    }
+
+    /// Removes segments of the component with `owner` id.
+    pub(crate) fn remove_owned(&mut self, owner: Id) {
+        self.segments.retain(|(o, _, _)| *o != Some(owner));
+    }
+
+    /// Draws segments in the one row high `area` along with the `extra` built-in segments.
+    pub(crate) fn render(&self, area: Rect, buf: &mut Buffer, extra: &[StatusSegment]) {
+        buf.set_style(area, self.style);
+        let mut segments = self
+            .segments
+            .iter()
+            .map(|(_, _, s)| s)
+            .chain(extra)
+            .collect::<Vec<_>>();
+        segments.sort_by_key(|s| s.priority);
+        let part = |align| {
+            let mut spans = Vec::new();
+            for segment in segments.iter().filter(|s| s.align == align) {
+                if !spans.is_empty() {
+                    spans.push(self.separator.clone());
+                }
+                spans.extend(segment.line.spans.iter().cloned());
+            }
+            Line::from(spans)
+        };
+
+        let (left, center, right) = (
+            part(StatusAlign::Left),
+            part(StatusAlign::Center),
+            part(StatusAlign::Right),
+        );
+        // Keeps a cell between parts.
+        let free = match left.width() {
+            0 => area.x,
+            _ => buf.set_line(area.x, area.y, &left, area.width).0 + 1,
+        }
+        .min(area.right());
+
+        let right_width = right.width() as u16;
+        let right_x = area.right().saturating_sub(right_width).max(free);
+        if right_width > 0 {
+            buf.set_line(right_x, area.y, &right, area.right() - right_x);
+        }
+        let end = match right_width {
+            0 => area.right(),
+            _ => right_x.saturating_sub(1),
+        };
+
+        let center_width = center.width() as u16;
+        let center_x = (area.x + area.width.saturating_sub(center_width) / 2)
+            .max(free)
+            .min(end.saturating_sub(center_width));
+        if center_width > 0 && center_x >= free && center_x + center_width <= end {
+            buf.set_line(center_x, area.y, &center, center_width);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{terminal::Setup, Compositor, Error};
+use futures_channel::mpsc;
+use futures_util::Stream;
+use ratatui::{
+    backend::{Backend, WindowSize},
+    buffer::{Buffer, Cell},
+    layout::Size,
+    prelude::Rect,
+    style::{Color, Modifier},
+};
+use std::{
+    io,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Styled cell of a [`FrameSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SnapshotCell {
+    /// Grapheme drawn in the cell, empty for cells covered by a preceding wide grapheme.
+    pub symbol: String,
+    pub fg: Color,
+    pub bg: Color,
+    pub modifier: Modifier,
+}
+
+impl From<&Cell> for SnapshotCell {
+    fn from(cell: &Cell) -> Self {
+        Self {
+            symbol: cell.symbol().to_owned(),
+            fg: cell.fg,
+            bg: cell.bg,
+            modifier: cell.modifier,
+        }
+    }
+}
+
+/// Grid of cells drawn by the compositor in one frame.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrameSnapshot {
+    frame: u64,
+    width: u16,
+    height: u16,
+    cells: Vec<SnapshotCell>,
+    changed: Vec<(u16, u16)>,
+}
+
+impl FrameSnapshot {
+    /// Number of the frame, increments by one with every drawn frame.
+    /// If it increased by more than one since the last snapshot the host has seen, [`Self::changed`]
+    /// is incomplete and the whole grid should be repainted.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns `(columns, rows)` of the grid.
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Returns cell at `(x, y)`, `None` if it is out of bounds.
+    pub fn cell(&self, x: u16, y: u16) -> Option<&SnapshotCell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells
+            .get(y as usize * self.width as usize + x as usize)
+    }
+
+    /// Returns rows of cells from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[SnapshotCell]> {
+        self.cells.chunks(self.width.max(1) as usize)
+    }
+
+    /// Returns `(x, y)` of cells that changed since the previous frame.
+    pub fn changed(&self) -> &[(u16, u16)] {
+        &self.changed
+    }
+}
+
+struct SurfaceInner {
+    size: (u16, u16),
+    snapshot: FrameSnapshot,
+    subscribers: Vec<mpsc::UnboundedSender<()>>,
+}
+
+/// Surface that a GUI frontend paints itself, e.g. with egui, iced or wgpu.
+/// Run the compositor on it with [`Compositor::run_hosted`] and feed input with [`Compositor::with_stream`].
+#[derive(Clone)]
+pub struct FrameSurface {
+    inner: Arc<Mutex<SurfaceInner>>,
+}
+
+impl FrameSurface {
+    /// Creates new surface of `(columns, rows)` cells.
+    pub fn new(size: (u16, u16)) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SurfaceInner {
+                size,
+                snapshot: FrameSnapshot::default(),
+                subscribers: Vec::new(),
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SurfaceInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the size of the surface in `(columns, rows)`.
+    pub fn size(&self) -> (u16, u16) {
+        self.lock().size
+    }
+
+    /// Resizes the surface, the next frame is drawn at the new size.
+    /// Components see it after a [`TerminalEvent::Resize`](crate::TerminalEvent::Resize) is sent to the compositor.
+    pub fn resize(&self, size: (u16, u16)) {
+        self.lock().size = size;
+    }
+
+    /// Returns the last drawn frame.
+    pub fn snapshot(&self) -> FrameSnapshot {
+        self.lock().snapshot.clone()
+    }
+
+    /// Returns a stream that yields every time a frame is drawn.
+    pub fn changes(&self) -> impl Stream<Item = ()> {
+        let (tx, rx) = mpsc::unbounded();
+        self.lock().subscribers.push(tx);
+        rx
+    }
+}
+
+/// Backend that draws into a [`FrameSurface`].
+struct SurfaceBackend {
+    surface: FrameSurface,
+    buffer: Buffer,
+    changed: Vec<(u16, u16)>,
+    /// Every cell changes after the buffer is cleared, even those that are not drawn again.
+    cleared: bool,
+    frame: u64,
+    cursor: (u16, u16),
+}
+
+impl Backend for SurfaceBackend {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let (width, height) = self.surface.size();
+        if (self.buffer.area.width, self.buffer.area.height) != (width, height) {
+            self.buffer = Buffer::empty(Rect::new(0, 0, width, height));
+            self.cleared = true;
+        }
+
+        for (x, y, cell) in content {
+            if x < width && y < height {
+                *self.buffer.get_mut(x, y) = cell.clone();
+                self.changed.push((x, y));
+            }
+        }
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+        Ok(self.cursor)
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.cursor = (x, y);
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.buffer.reset();
+        self.cleared = true;
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        let (width, height) = self.surface.size();
+        Ok(Rect::new(0, 0, width, height))
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        let (width, height) = self.surface.size();
+        Ok(WindowSize {
+            columns_rows: Size { width, height },
+            pixels: Size {
+                width: 0,
+                height: 0,
+            },
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.frame += 1;
+        let area = self.buffer.area;
+        if std::mem::take(&mut self.cleared) {
+            self.changed = (0..area.height)
+                .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+                .collect();
+        }
+        let snapshot = FrameSnapshot {
+            frame: self.frame,
+            width: area.width,
+            height: area.height,
+            cells: self.buffer.content.iter().map(SnapshotCell::from).collect(),
+            changed: std::mem::take(&mut self.changed),
+        };
+
+        let mut inner = self.surface.lock();
+        inner.snapshot = snapshot;
+        inner.subscribers.retain(|tx| tx.unbounded_send(()).is_ok());
+        Ok(())
+    }
+}
+
+/// GUI hosting functions
+impl<S: 'static, E: 'static> Compositor<S, E> {
+    /// Runs the compositor drawing into `surface` instead of a terminal, leaving the local terminal untouched.
+    pub async fn run_hosted(mut self, surface: FrameSurface) -> Result<S, Error> {
+        self.setup = Setup::None;
+        self.run(SurfaceBackend {
+            surface,
+            buffer: Buffer::default(),
+            changed: Vec::new(),
+            cleared: false,
+            frame: 0,
+            cursor: (0, 0),
+        })
+        .await
+    }
+}
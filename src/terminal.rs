@@ -0,0 +1,452 @@
+#[cfg(not(target_arch = "wasm32"))]
+use crate::window::{POP_TITLE, PUSH_TITLE, RESET_CURSOR_STYLE};
+use crate::KeyboardEnhancementFlags;
+#[cfg(feature = "termion")]
+use crate::{InputParser, TerminalEvent};
+#[cfg(not(target_arch = "wasm32"))]
+use crossterm::{
+    event::{
+        DisableMouseCapture, EnableMouseCapture, PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
+    },
+    execute,
+    style::Print,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
+    },
+};
+#[cfg(feature = "termion")]
+use futures_channel::mpsc;
+#[cfg(feature = "event-stream")]
+use futures_util::{Stream, StreamExt};
+use std::io;
+#[cfg(feature = "event-stream")]
+use std::{
+    cell::RefCell,
+    pin::Pin,
+    rc::Rc,
+    task::{self, Poll, Waker},
+};
+#[cfg(feature = "termion")]
+use std::{
+    fs::File,
+    io::Read,
+    os::fd::{AsFd, AsRawFd, RawFd},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Library used to set up the terminal before running the compositor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Setup {
+    /// Terminal is managed elsewhere, e.g. in the browser or by a remote client.
+    None,
+    #[cfg(not(target_arch = "wasm32"))]
+    Crossterm,
+    #[cfg(feature = "termion")]
+    Termion,
+}
+
+impl Default for Setup {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn default() -> Self {
+        Self::Crossterm
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl Setup {
+    /// Writes escape sequences directly to the terminal set up by this library.
+    /// Does nothing if the terminal is managed elsewhere.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    pub(crate) fn write_raw(self, bytes: &[u8]) -> io::Result<()> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self != Self::None {
+            use std::io::Write;
+
+            let mut stdout = io::stdout();
+            stdout.write_all(bytes)?;
+            stdout.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Begins or ends synchronized output, the terminal holds back rendering until it ends.
+    pub(crate) fn synchronized_update(self, begin: bool) {
+        let seq: &[u8] = if begin {
+            b"\x1b[?2026h"
+        } else {
+            b"\x1b[?2026l"
+        };
+        _ = self.write_raw(seq);
+    }
+}
+
+/// What the terminal guard sets up besides raw mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TerminalOptions {
+    pub(crate) mouse_capture: bool,
+    pub(crate) alternate_screen: bool,
+    pub(crate) clear_on_start: bool,
+    pub(crate) keyboard: KeyboardEnhancementFlags,
+}
+
+impl Default for TerminalOptions {
+    fn default() -> Self {
+        Self {
+            mouse_capture: true,
+            alternate_screen: true,
+            clear_on_start: true,
+            keyboard: KeyboardEnhancementFlags::empty(),
+        }
+    }
+}
+
+/// Puts terminal into raw mode and alternate screen, restores it when dropped.
+pub(crate) enum TerminalGuard {
+    None,
+    #[cfg(not(target_arch = "wasm32"))]
+    Crossterm {
+        mouse_capture: bool,
+        alternate_screen: bool,
+        keyboard: bool,
+    },
+    #[cfg(feature = "termion")]
+    Termion {
+        raw: termion::raw::RawTerminal<io::Stdout>,
+        options: TerminalOptions,
+        keyboard: bool,
+    },
+}
+
+impl TerminalGuard {
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    /// Keyboard enhancements are only pushed if `kitty_keyboard` is set, crossterm also queries the terminal.
+    pub(crate) fn new(
+        setup: Setup,
+        options: TerminalOptions,
+        kitty_keyboard: bool,
+    ) -> io::Result<Self> {
+        match setup {
+            Setup::None => Ok(Self::None),
+            #[cfg(not(target_arch = "wasm32"))]
+            Setup::Crossterm => {
+                use crossterm::QueueableCommand;
+                use std::io::Write;
+
+                enable_raw_mode()?;
+                let mut stdout = io::stdout();
+                stdout.queue(Print(PUSH_TITLE))?;
+                if options.alternate_screen {
+                    stdout.queue(EnterAlternateScreen)?;
+                }
+                let keyboard = !options.keyboard.is_empty()
+                    && kitty_keyboard
+                    && crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false);
+                if keyboard {
+                    stdout.queue(PushKeyboardEnhancementFlags(
+                        crossterm::event::KeyboardEnhancementFlags::from_bits_truncate(
+                            options.keyboard.bits(),
+                        ),
+                    ))?;
+                }
+                if options.clear_on_start {
+                    stdout.queue(crossterm::terminal::Clear(ClearType::All))?;
+                }
+                stdout.flush()?;
+
+                // Legacy Windows consoles (conhost with redirected or restricted input) may refuse
+                // the console mode required for mouse input, the ui is still usable without it.
+                let mouse_capture = options.mouse_capture
+                    && match execute!(io::stdout(), EnableMouseCapture) {
+                        Ok(()) => true,
+                        Err(_) if cfg!(windows) => false,
+                        Err(e) => return Err(e),
+                    };
+
+                Ok(Self::Crossterm {
+                    mouse_capture,
+                    alternate_screen: options.alternate_screen,
+                    keyboard,
+                })
+            }
+            #[cfg(feature = "termion")]
+            Setup::Termion => {
+                use std::io::Write;
+                use termion::{clear, raw::IntoRawMode, screen::ToAlternateScreen};
+
+                let mut raw = io::stdout().into_raw_mode()?;
+                write!(raw, "{PUSH_TITLE}")?;
+                if options.alternate_screen {
+                    write!(raw, "{ToAlternateScreen}")?;
+                }
+                if options.mouse_capture {
+                    write!(raw, "{ENTER_MOUSE}")?;
+                }
+                let keyboard = !options.keyboard.is_empty() && kitty_keyboard;
+                if keyboard {
+                    write!(raw, "\x1b[>{}u", options.keyboard.bits())?;
+                }
+                if options.clear_on_start {
+                    write!(raw, "{}", clear::All)?;
+                }
+                raw.flush()?;
+
+                Ok(Self::Termion {
+                    raw,
+                    options,
+                    keyboard,
+                })
+            }
+        }
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        match self {
+            Self::None => {}
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Crossterm {
+                mouse_capture,
+                alternate_screen,
+                keyboard,
+            } => {
+                if *mouse_capture {
+                    _ = execute!(io::stdout(), DisableMouseCapture);
+                }
+                if *keyboard {
+                    _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+                }
+                _ = execute!(io::stdout(), Print(RESET_CURSOR_STYLE));
+                if *alternate_screen {
+                    _ = execute!(io::stdout(), LeaveAlternateScreen);
+                }
+                _ = execute!(io::stdout(), Print(POP_TITLE));
+                _ = disable_raw_mode();
+            }
+            #[cfg(feature = "termion")]
+            Self::Termion {
+                raw,
+                options,
+                keyboard,
+            } => {
+                use std::io::Write;
+                use termion::screen::ToMainScreen;
+
+                if options.mouse_capture {
+                    _ = write!(raw, "{EXIT_MOUSE}");
+                }
+                if *keyboard {
+                    _ = write!(raw, "\x1b[<u");
+                }
+                _ = write!(raw, "{RESET_CURSOR_STYLE}");
+                if options.alternate_screen {
+                    _ = write!(raw, "{ToMainScreen}");
+                }
+                _ = write!(raw, "{POP_TITLE}");
+                _ = raw.flush();
+                // Raw mode is restored when `raw` is dropped.
+            }
+        }
+    }
+}
+
+#[cfg(feature = "termion")]
+const ENTER_MOUSE: &str = "\x1b[?1000h\x1b[?1003h\x1b[?1015h\x1b[?1006h";
+#[cfg(feature = "termion")]
+const EXIT_MOUSE: &str = "\x1b[?1006l\x1b[?1015l\x1b[?1003l\x1b[?1000l";
+
+/// Terminal events read with crossterm's `EventStream`. The stream is dropped while the compositor is
+/// suspended, so its reader thread doesn't take input meant for the program running meanwhile.
+#[cfg(feature = "event-stream")]
+#[derive(Clone, Default)]
+pub(crate) struct SuspendableInput(Rc<RefCell<InputState>>);
+
+#[cfg(feature = "event-stream")]
+#[derive(Default)]
+struct InputState {
+    /// Created on first poll, there is no terminal to read from in headless mode.
+    stream: Option<crossterm::event::EventStream>,
+    waker: Option<Waker>,
+}
+
+#[cfg(feature = "event-stream")]
+impl SuspendableInput {
+    pub(crate) fn suspend(&self) {
+        self.0.borrow_mut().stream = None;
+    }
+
+    /// Reads the terminal again after [`Self::suspend`].
+    pub(crate) fn resume(&self) {
+        if let Some(waker) = self.0.borrow_mut().waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(feature = "event-stream")]
+impl Stream for SuspendableInput {
+    type Item = io::Result<crossterm::event::Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.0.borrow_mut();
+        state.waker = Some(cx.waker().clone());
+        state
+            .stream
+            .get_or_insert_with(crossterm::event::EventStream::new)
+            .poll_next_unpin(cx)
+    }
+}
+
+/// Terminal events read with termion by a thread. The thread waits for input without reading it and
+/// stops reading while the compositor is suspended, so it doesn't take input meant for the program running meanwhile.
+#[cfg(feature = "termion")]
+#[derive(Clone, Default)]
+pub(crate) struct TermionInput {
+    paused: Arc<AtomicBool>,
+}
+
+#[cfg(feature = "termion")]
+impl TermionInput {
+    pub(crate) fn suspend(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Reads the terminal again after [`Self::suspend`].
+    pub(crate) fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Starts the reader thread, it stops after sending a read error or once the receiver is dropped.
+    pub(crate) fn spawn(&self) -> mpsc::UnboundedReceiver<io::Result<TerminalEvent>> {
+        let paused = self.paused.clone();
+        let (tx, rx) = mpsc::unbounded();
+        std::thread::spawn(move || {
+            // Stdin is buffered, so bytes read ahead wouldn't be reported by `poll`.
+            let stdin = io::stdin().as_fd().try_clone_to_owned().map(File::from);
+            if let Err(e) = stdin.and_then(|stdin| read_input(stdin, &paused, &tx)) {
+                _ = tx.unbounded_send(Err(e));
+            }
+        });
+        rx
+    }
+}
+
+/// Sends events read from `input` until it is closed or the receiver is dropped.
+/// Escape sequences split across reads are kept until the rest of them arrives.
+#[cfg(feature = "termion")]
+fn read_input(
+    mut input: File,
+    paused: &AtomicBool,
+    tx: &mpsc::UnboundedSender<io::Result<TerminalEvent>>,
+) -> io::Result<()> {
+    /// How often the thread checks whether it is paused or the receiver is dropped.
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    let mut parser = InputParser::new();
+    let mut buf = [0; 1024];
+    while !tx.is_closed() {
+        if paused.load(Ordering::SeqCst) {
+            std::thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        // Paused while waiting, the input is left for the program running meanwhile.
+        if !poll_readable(input.as_raw_fd(), POLL_INTERVAL)? || paused.load(Ordering::SeqCst) {
+            continue;
+        }
+        let len = input.read(&mut buf)?;
+        if len == 0 {
+            break;
+        }
+        for event in parser.feed(&buf[..len]) {
+            _ = tx.unbounded_send(Ok(event));
+        }
+    }
+    Ok(())
+}
+
+/// Waits up to `timeout` for `fd` to have input, returns `false` if it times out or is interrupted.
+#[cfg(feature = "termion")]
+fn poll_readable(fd: RawFd, timeout: Duration) -> io::Result<bool> {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout = timeout.as_millis().try_into().unwrap_or(libc::c_int::MAX);
+    // SAFETY: `pollfd` is a valid array of one element for the duration of the call.
+    match unsafe { libc::poll(&mut pollfd, 1, timeout) } {
+        -1 => {
+            let e = io::Error::last_os_error();
+            match e.kind() {
+                io::ErrorKind::Interrupted => Ok(false),
+                _ => Err(e),
+            }
+        }
+        0 => Ok(false),
+        _ => Ok(true),
+    }
+}
+
+#[cfg(all(test, feature = "termion"))]
+mod tests {
+    use super::{poll_readable, read_input};
+    use crate::{KeyCode, KeyEvent, KeyModifiers, TerminalEvent};
+    use futures_channel::mpsc;
+    use std::{
+        fs::File,
+        io::Write,
+        os::{fd::AsRawFd, fd::OwnedFd, unix::net::UnixStream},
+        sync::atomic::AtomicBool,
+        thread,
+        time::Duration,
+    };
+
+    #[test]
+    fn polls_without_reading() {
+        let (mut tx, rx) = UnixStream::pair().unwrap();
+        assert!(!poll_readable(rx.as_raw_fd(), Duration::ZERO).unwrap());
+
+        tx.write_all(b"q").unwrap();
+        assert!(poll_readable(rx.as_raw_fd(), Duration::from_secs(1)).unwrap());
+        // Input is left for whoever reads next.
+        assert!(poll_readable(rx.as_raw_fd(), Duration::ZERO).unwrap());
+    }
+
+    #[test]
+    fn sequences_split_across_reads() {
+        let (mut tx, rx) = UnixStream::pair().unwrap();
+        let (events_tx, mut events) = mpsc::unbounded();
+        let reader = thread::spawn(move || {
+            let input = File::from(OwnedFd::from(rx));
+            read_input(input, &AtomicBool::new(false), &events_tx)
+        });
+
+        tx.write_all(b"a\x1b[1;").unwrap();
+        thread::sleep(Duration::from_millis(100));
+        tx.write_all(b"5A").unwrap();
+        drop(tx);
+        reader.join().unwrap().unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(Some(event)) = events.try_next() {
+            received.push(event.unwrap());
+        }
+        let key = |code, modifiers| TerminalEvent::Key(KeyEvent::new(code, modifiers));
+        assert_eq!(
+            received,
+            [
+                key(KeyCode::Char('a'), KeyModifiers::NONE),
+                key(KeyCode::Up, KeyModifiers::CONTROL),
+            ]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Helpers for testing components without a terminal.
+
+use crate::{export, runtime::InlineRuntime, terminal::Setup, Compositor, Event, ExportFormat};
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+use std::{env, fmt::Write as _, fs, path::PathBuf, sync::Arc};
+
+/// Set to `1` to overwrite snapshots that don't match instead of failing.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "GLAND_UPDATE_SNAPSHOTS";
+
+/// Returns `buf` as text followed by its styled spans, see [`ExportFormat::Snapshot`].
+pub fn snapshot(buf: &Buffer) -> String {
+    export::export(buf, ExportFormat::Snapshot)
+}
+
+/// Compares `buf` to the golden file `tests/snapshots/<name>.snap` of the crate under test,
+/// panics with a line diff if they differ.
+///
+/// Missing snapshots are written and the assertion passes, review and commit them.
+/// Mismatching ones are overwritten if [`UPDATE_SNAPSHOTS_VAR`] is set to `1`.
+#[track_caller]
+pub fn assert_snapshot(buf: &Buffer, name: &str) {
+    let actual = snapshot(buf);
+    let dir = env::var_os("CARGO_MANIFEST_DIR").map_or_else(PathBuf::new, PathBuf::from);
+    let path = dir
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{name}.snap"));
+
+    let update = env::var(UPDATE_SNAPSHOTS_VAR).is_ok_and(|v| v == "1");
+    match fs::read_to_string(&path) {
+        Ok(expected) if expected == actual => {}
+        Ok(expected) if !update => panic!(
+            "snapshot `{name}` doesn't match {}, set {UPDATE_SNAPSHOTS_VAR}=1 to update it\n{}",
+            path.display(),
+            diff(&expected, &actual)
+        ),
+        _ => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).expect("failed to create snapshot directory");
+            }
+            fs::write(&path, actual).expect("failed to write snapshot");
+        }
+    }
+}
+
+/// Lists lines of `expected` and `actual` that differ, prefixed with `-` and `+`.
+fn diff(expected: &str, actual: &str) -> String {
+    let (expected, actual) = (
+        expected.lines().collect::<Vec<_>>(),
+        actual.lines().collect::<Vec<_>>(),
+    );
+
+    let mut out = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => _ = writeln!(out, "  {e}"),
+            (e, a) => {
+                if let Some(e) = e {
+                    _ = writeln!(out, "- {e}");
+                }
+                if let Some(a) = a {
+                    _ = writeln!(out, "+ {a}");
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Testing functions
+impl<S: 'static, E: 'static> Compositor<S, E> {
+    /// Draws the ui into `backend`, then dispatches `events` one by one drawing after each,
+    /// stops early if the compositor exits. Returns the last frame and the state.
+    ///
+    /// Unlike [`Self::run`] no tick is emitted at the start and the terminal is left untouched.
+    /// Unless a runtime is set with [`Self::with_runtime`], jobs run on the calling thread before
+    /// and after each event, so jobs that don't wait for timers or other threads finish and their
+    /// callbacks run before the next frame is drawn.
+    ///
+    /// ```
+    /// # use gland::{Component, Compositor, Context, Event, Id, LayerId};
+    /// # use ratatui::{backend::TestBackend, prelude::{Buffer, Rect}, style::Style};
+    /// struct Ticks;
+    ///
+    /// impl Component<u32> for Ticks {
+    ///     fn id(&self) -> Id {
+    ///         Id::new("ticks")
+    ///     }
+    ///
+    ///     fn view(&self, area: Rect, buf: &mut Buffer, state: &u32) {
+    ///         buf.set_string(area.x, area.y, format!("ticks: {state}"), Style::new());
+    ///     }
+    ///
+    ///     fn handle_event(&mut self, event: &mut Event, cx: &mut Context<u32>) {
+    ///         if let Event::Tick = event {
+    ///             *cx.state_mut() += 1;
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut comp = Compositor::with_state(0);
+    /// comp.insert_at(LayerId::FOREGROUND, Ticks).ok();
+    ///
+    /// let (frame, ticks) = comp.run_scripted(TestBackend::new(10, 1), [Event::Tick, Event::Tick]);
+    /// assert_eq!(ticks, 2);
+    /// assert_eq!(frame, Buffer::with_lines(vec!["ticks: 2  "]));
+    /// ```
+    pub fn run_scripted(
+        self,
+        backend: TestBackend,
+        events: impl IntoIterator<Item = Event<E>>,
+    ) -> (Buffer, S) {
+        let mut last = Buffer::default();
+        let state = self.script(backend, events, |frame| last = frame.clone());
+        (last, state)
+    }
+
+    /// Same as [`Self::run_scripted`] but returns every frame drawn as a [`snapshot`],
+    /// starting with the one before the first event.
+    pub fn run_recorded(
+        self,
+        backend: TestBackend,
+        events: impl IntoIterator<Item = Event<E>>,
+    ) -> (Vec<String>, S) {
+        let mut frames = Vec::new();
+        let state = self.script(backend, events, |frame| frames.push(snapshot(frame)));
+        (frames, state)
+    }
+
+    fn script(
+        mut self,
+        backend: TestBackend,
+        events: impl IntoIterator<Item = Event<E>>,
+        mut on_frame: impl FnMut(&Buffer),
+    ) -> S {
+        self.setup = Setup::None;
+        let inline = (self.runtime.is_none() && self.jobs.is_none()).then(InlineRuntime::default);
+        if let Some(inline) = &inline {
+            self.runtime = Some(Arc::new(inline.clone()));
+        }
+        let drain = || inline.iter().for_each(InlineRuntime::drain);
+        let mut terminal = Terminal::new(backend).expect("test backend is infallible");
+
+        self.render(&mut terminal)
+            .expect("test backend is infallible");
+        on_frame(terminal.backend().buffer());
+        for event in events {
+            if self.should_exit() {
+                break;
+            }
+            drain();
+            self.dispatch(event);
+            drain();
+            self.render(&mut terminal)
+                .expect("test backend is infallible");
+            on_frame(terminal.backend().buffer());
+        }
+
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Component, Compositor, Context, Event, Id, LayerId};
+    use ratatui::{backend::TestBackend, buffer::Buffer, layout::Rect, style::Style};
+
+    /// Loads the state in a job on the first event.
+    struct Loader;
+
+    impl Component<String> for Loader {
+        fn id(&self) -> Id {
+            Id::new("loader")
+        }
+
+        fn view(&self, area: Rect, buf: &mut Buffer, state: &String) {
+            buf.set_string(area.x, area.y, state, Style::new());
+        }
+
+        fn handle_event(&mut self, _event: &mut Event, cx: &mut Context<String>) {
+            if cx.state().is_empty() && cx.jobs().running() == 0 {
+                cx.jobs().spawn(async {
+                    let text = "loaded".to_owned();
+                    move |comp: &mut Compositor<String>| *comp.state_mut() = text
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn scripted_jobs_run_between_events() {
+        let mut comp = Compositor::with_state(String::new());
+        _ = comp.insert_at(LayerId::MIDDLE, Loader);
+
+        let (frames, state) = comp.run_recorded(TestBackend::new(6, 1), [Event::Tick]);
+        assert_eq!(state, "loaded");
+        assert!(frames[1].starts_with("loaded"));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::Id;
+use ratatui::style::Style;
+use std::collections::HashMap;
+
+/// Named styles resolved with a cascade, so widgets can be restyled without forking them.
+///
+/// Keys are dot separated paths like `list.selected`. Resolving a key patches the styles of all of
+/// its parents on top of each other, from the base style set with [`Self::with_base`] to the key
+/// itself, missing keys fall back to their parents. Overrides set for a component with
+/// [`Self::with_override`] are then patched on top the same way.
+///
+/// ```
+/// # use gland::{Id, Theme};
+/// # use ratatui::style::{Color, Modifier, Style};
+/// let theme = Theme::default()
+///     .with("list", Style::new().fg(Color::White))
+///     .with("list.selected", Style::new().add_modifier(Modifier::BOLD))
+///     .with_override(Id::new("files"), "list.selected", Style::new().fg(Color::Yellow));
+///
+/// assert_eq!(theme.style("list.selected"), Style::new().fg(Color::White).add_modifier(Modifier::BOLD));
+/// assert_eq!(
+///     theme.scope(Id::new("files")).style("list.selected"),
+///     Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Theme {
+    base: Style,
+    styles: HashMap<String, Style>,
+    overrides: HashMap<Id, HashMap<String, Style>>,
+}
+
+/// Builder functions
+impl Theme {
+    /// Sets the style all keys inherit from.
+    pub fn with_base(mut self, style: Style) -> Self {
+        self.base = style;
+        self
+    }
+
+    /// Sets the style of `key`.
+    pub fn with(mut self, key: impl Into<String>, style: Style) -> Self {
+        self.set(key, style);
+        self
+    }
+
+    /// Sets the style of `key` for the component with `id` only.
+    pub fn with_override(mut self, id: Id, key: impl Into<String>, style: Style) -> Self {
+        self.set_override(id, key, style);
+        self
+    }
+}
+
+/// Non-builder functions
+impl Theme {
+    /// Sets the style of `key`, returning the previous one.
+    pub fn set(&mut self, key: impl Into<String>, style: Style) -> Option<Style> {
+        self.styles.insert(key.into(), style)
+    }
+
+    /// Sets the style of `key` for the component with `id` only, returning the previous one.
+    pub fn set_override(&mut self, id: Id, key: impl Into<String>, style: Style) -> Option<Style> {
+        self.overrides
+            .entry(id)
+            .or_default()
+            .insert(key.into(), style)
+    }
+
+    /// Removes all overrides of the component with `id`.
+    pub fn clear_overrides(&mut self, id: Id) {
+        self.overrides.remove(&id);
+    }
+
+    /// Returns the style set for exactly `key`, without the cascade.
+    pub fn get(&self, key: &str) -> Option<Style> {
+        self.styles.get(key).copied()
+    }
+
+    /// Resolves the style of `key`, ignoring component overrides.
+    pub fn style(&self, key: &str) -> Style {
+        cascade(self.base, &self.styles, key)
+    }
+
+    /// Returns styles of the component with `id`, its overrides take precedence.
+    pub fn scope(&self, id: Id) -> ThemeScope<'_> {
+        ThemeScope { theme: self, id }
+    }
+}
+
+/// Styles of a single component, see [`Theme::scope`].
+#[derive(Debug, Clone, Copy)]
+pub struct ThemeScope<'t> {
+    theme: &'t Theme,
+    id: Id,
+}
+
+impl ThemeScope<'_> {
+    /// Resolves the style of `key` with overrides of the component patched on top.
+    pub fn style(&self, key: &str) -> Style {
+        let style = self.theme.style(key);
+        match self.theme.overrides.get(&self.id) {
+            Some(overrides) => cascade(style, overrides, key),
+            None => style,
+        }
+    }
+}
+
+/// Patches styles of `key` and its parents on top of `style`, outermost first.
+fn cascade(style: Style, styles: &HashMap<String, Style>, key: &str) -> Style {
+    let parents = key
+        .match_indices('.')
+        .map(|(i, _)| &key[..i])
+        .chain(Some(key).filter(|k| !k.is_empty()));
+
+    parents
+        .filter_map(|k| styles.get(k))
+        .fold(style, |style, patch| style.patch(*patch))
+}
@@ -0,0 +1,213 @@
+//! Browser frontend that renders into an [xterm.js](https://xtermjs.org) terminal.
+
+use crate::Runtime;
+use crate::{
+    ansi::parse_sgr_mouse, AnsiBackend, AnsiOutput, KeyCode, KeyEvent, KeyModifiers, TerminalEvent,
+};
+use futures_channel::{mpsc, oneshot};
+use futures_util::{
+    future::{BoxFuture, LocalBoxFuture},
+    stream::BoxStream,
+    FutureExt, Stream, StreamExt,
+};
+use std::{io, time::Duration};
+use wasm_bindgen::{prelude::*, JsCast};
+
+#[wasm_bindgen]
+extern "C" {
+    /// xterm.js `Terminal` instance.
+    #[derive(Clone)]
+    pub type Xterm;
+
+    #[wasm_bindgen(method)]
+    fn write(this: &Xterm, data: &str);
+    #[wasm_bindgen(method, getter)]
+    fn cols(this: &Xterm) -> u16;
+    #[wasm_bindgen(method, getter)]
+    fn rows(this: &Xterm) -> u16;
+    #[wasm_bindgen(method, js_name = onKey)]
+    fn on_key(this: &Xterm, listener: &Closure<dyn FnMut(XtermKey)>) -> JsValue;
+    #[wasm_bindgen(method, js_name = onData)]
+    fn on_data(this: &Xterm, listener: &Closure<dyn FnMut(String)>) -> JsValue;
+    #[wasm_bindgen(method, js_name = onResize)]
+    fn on_resize(this: &Xterm, listener: &Closure<dyn FnMut(XtermSize)>) -> JsValue;
+
+    type XtermKey;
+    #[wasm_bindgen(method, getter, js_name = domEvent)]
+    fn dom_event(this: &XtermKey) -> web_sys::KeyboardEvent;
+
+    type XtermSize;
+    #[wasm_bindgen(method, getter)]
+    fn cols(this: &XtermSize) -> u16;
+    #[wasm_bindgen(method, getter)]
+    fn rows(this: &XtermSize) -> u16;
+}
+
+/// Backend drawing into xterm.js terminal.
+pub type XtermBackend = AnsiBackend<XtermOutput>;
+
+/// Output that buffers escape sequences and writes them to xterm.js on flush.
+pub struct XtermOutput {
+    term: Xterm,
+    buf: Vec<u8>,
+}
+
+impl XtermOutput {
+    /// Creates new output, enables mouse reporting in the terminal.
+    pub fn new(term: Xterm) -> Self {
+        term.write("\x1b[?1000h\x1b[?1002h\x1b[?1006h\x1b[?25l\x1b[2J");
+        Self {
+            term,
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl io::Write for XtermOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.term.write(&String::from_utf8_lossy(&self.buf));
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+impl AnsiOutput for XtermOutput {
+    fn size(&self) -> io::Result<(u16, u16)> {
+        Ok((self.term.cols(), self.term.rows()))
+    }
+}
+
+/// Creates a stream of key, mouse and resize events coming from xterm.js terminal.
+/// Pass it to [`Compositor::with_stream`](crate::Compositor::with_stream) mapped into [`Event::Terminal`](crate::Event::Terminal).
+pub fn xterm_event_stream(term: &Xterm) -> impl Stream<Item = TerminalEvent> {
+    let (tx, rx) = mpsc::unbounded();
+
+    let sender = tx.clone();
+    let on_key = Closure::<dyn FnMut(XtermKey)>::new(move |key: XtermKey| {
+        if let Some(event) = key_event(&key.dom_event()) {
+            _ = sender.unbounded_send(TerminalEvent::Key(event));
+        }
+    });
+    term.on_key(&on_key);
+    on_key.forget();
+
+    let sender = tx.clone();
+    let on_data = Closure::<dyn FnMut(String)>::new(move |data: String| {
+        if let Some(mouse) = parse_sgr_mouse(&data) {
+            _ = sender.unbounded_send(TerminalEvent::Mouse(mouse));
+        }
+    });
+    term.on_data(&on_data);
+    on_data.forget();
+
+    let on_resize = Closure::<dyn FnMut(XtermSize)>::new(move |size: XtermSize| {
+        _ = tx.unbounded_send(TerminalEvent::Resize(size.cols(), size.rows()));
+    });
+    term.on_resize(&on_resize);
+    on_resize.forget();
+
+    rx
+}
+
+/// Translates browser keyboard event, returns `None` for lone modifier keys.
+pub fn key_event(event: &web_sys::KeyboardEvent) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    modifiers.set(KeyModifiers::CONTROL, event.ctrl_key());
+    modifiers.set(KeyModifiers::ALT, event.alt_key());
+    modifiers.set(KeyModifiers::SHIFT, event.shift_key());
+    modifiers.set(KeyModifiers::META, event.meta_key());
+
+    let key = event.key();
+    let code = match key.as_str() {
+        "Backspace" => KeyCode::Backspace,
+        "Enter" => KeyCode::Enter,
+        "ArrowLeft" => KeyCode::Left,
+        "ArrowRight" => KeyCode::Right,
+        "ArrowUp" => KeyCode::Up,
+        "ArrowDown" => KeyCode::Down,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Tab" if event.shift_key() => KeyCode::BackTab,
+        "Tab" => KeyCode::Tab,
+        "Delete" => KeyCode::Delete,
+        "Insert" => KeyCode::Insert,
+        "Escape" => KeyCode::Esc,
+        "CapsLock" => KeyCode::CapsLock,
+        "ScrollLock" => KeyCode::ScrollLock,
+        "NumLock" => KeyCode::NumLock,
+        "PrintScreen" => KeyCode::PrintScreen,
+        "Pause" => KeyCode::Pause,
+        "ContextMenu" => KeyCode::Menu,
+        "Shift" | "Control" | "Alt" | "Meta" => return None,
+        f if f.starts_with('F') && f.len() > 1 => KeyCode::F(f[1..].parse().ok()?),
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => KeyCode::Null,
+            }
+        }
+    };
+
+    Some(KeyEvent::new(code, modifiers))
+}
+
+/// Browser runtime, jobs are spawned with `wasm_bindgen_futures::spawn_local` and timers use `setInterval`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WasmRuntime;
+
+impl Runtime for WasmRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        wasm_bindgen_futures::spawn_local(future);
+    }
+
+    fn spawn_local(&self, future: LocalBoxFuture<'static, ()>) {
+        wasm_bindgen_futures::spawn_local(future);
+    }
+
+    fn interval(&self, period: Duration) -> BoxStream<'static, ()> {
+        interval(period).boxed()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let (tx, rx) = oneshot::channel();
+
+        let done = Closure::<dyn FnMut()>::once(move || _ = tx.send(()));
+        web_sys::window()
+            .expect("no window")
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                done.as_ref().unchecked_ref(),
+                duration.as_millis().try_into().unwrap_or(i32::MAX),
+            )
+            .expect("failed to set timeout");
+        done.forget();
+
+        async move {
+            _ = rx.await;
+        }
+        .boxed()
+    }
+}
+
+fn interval(period: Duration) -> impl Stream<Item = ()> {
+    let (tx, rx) = mpsc::unbounded();
+
+    let tick = Closure::<dyn FnMut()>::new(move || _ = tx.unbounded_send(()));
+    web_sys::window()
+        .expect("no window")
+        .set_interval_with_callback_and_timeout_and_arguments_0(
+            tick.as_ref().unchecked_ref(),
+            period.as_millis().try_into().unwrap_or(i32::MAX),
+        )
+        .expect("failed to set interval");
+    tick.forget();
+
+    rx
+}
@@ -0,0 +1,74 @@
+use crate::Id;
+use std::collections::HashMap;
+
+/// Selector registered with [`Context::watch`](crate::Context::watch),
+/// returns `true` if its output has changed since the last call.
+pub(crate) type Watch<S> = Box<dyn FnMut(&S) -> bool>;
+
+/// Wraps `selector` to compare its output with the previous one, the first call always reports a change.
+pub(crate) fn selector<S, T: PartialEq + 'static>(
+    selector: impl Fn(&S) -> T + 'static,
+) -> Watch<S> {
+    let mut last = None;
+    Box::new(move |state| {
+        let value = selector(state);
+        let changed = last.as_ref() != Some(&value);
+        last = Some(value);
+        changed
+    })
+}
+
+/// Selectors of components only drawn when their outputs change.
+pub(crate) struct Watches<S> {
+    components: HashMap<Id, Watched<S>>,
+}
+
+struct Watched<S> {
+    selectors: Vec<Watch<S>>,
+    /// Drawn in the next frame regardless of the selectors, see [`Context::invalidate`](crate::Context::invalidate).
+    invalidated: bool,
+}
+
+impl<S> Default for Watches<S> {
+    fn default() -> Self {
+        Self {
+            components: HashMap::new(),
+        }
+    }
+}
+
+impl<S> Watches<S> {
+    pub(crate) fn add(&mut self, id: Id, watch: Watch<S>) {
+        self.components
+            .entry(id)
+            .or_insert_with(|| Watched {
+                selectors: Vec::new(),
+                invalidated: true,
+            })
+            .selectors
+            .push(watch);
+    }
+
+    pub(crate) fn remove(&mut self, id: Id) {
+        self.components.remove(&id);
+    }
+
+    pub(crate) fn invalidate(&mut self, id: Id) {
+        if let Some(watched) = self.components.get_mut(&id) {
+            watched.invalidated = true;
+        }
+    }
+
+    /// Returns `false` if component `id` watches the state and none of its selectors' outputs changed.
+    pub(crate) fn changed(&mut self, id: Id, state: &S) -> bool {
+        let Some(watched) = self.components.get_mut(&id) else {
+            return true;
+        };
+        // All selectors run so each of them remembers the current output.
+        let changed = watched
+            .selectors
+            .iter_mut()
+            .fold(false, |changed, watch| watch(state) | changed);
+        std::mem::take(&mut watched.invalidated) | changed
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{Component, Id};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    widgets::{StatefulWidget, Widget},
+};
+
+/// Component drawing a ratatui widget, see [`widget_component`] and [`stateful_widget_component`].
+pub(crate) struct WidgetComponent<A, F> {
+    pub(crate) id: Id,
+    pub(crate) area: A,
+    /// Builds the widget and draws it.
+    pub(crate) widget: F,
+}
+
+/// Returns component drawing the [`Widget`] built by `widget` from the state every frame,
+/// in the area returned by `area` for the area assigned by the layout, see [`Component::layout`].
+///
+/// ```
+/// # use gland::{widget_component, Compositor, Id, LayerId};
+/// # use ratatui::widgets::Paragraph;
+/// let mut comp = Compositor::<String>::with_state(String::from("Hello"));
+/// let greeting = widget_component(Id::new("greeting"), |area| area, |text: &String| Paragraph::new(text.clone()));
+/// _ = comp.insert_at(LayerId::MIDDLE, greeting);
+/// ```
+pub fn widget_component<S, E, W>(
+    id: Id,
+    area: impl Fn(Rect) -> Rect + 'static,
+    widget: impl Fn(&S) -> W + 'static,
+) -> impl Component<S, E>
+where
+    S: 'static,
+    E: 'static,
+    W: Widget,
+{
+    WidgetComponent {
+        id,
+        area,
+        widget: move |state: &S, area, buf: &mut Buffer| widget(state).render(area, buf),
+    }
+}
+
+/// Same as [`widget_component`] for a [`StatefulWidget`], `widget` returns it along with its state.
+pub fn stateful_widget_component<S, E, W>(
+    id: Id,
+    area: impl Fn(Rect) -> Rect + 'static,
+    widget: impl Fn(&S) -> (W, W::State) + 'static,
+) -> impl Component<S, E>
+where
+    S: 'static,
+    E: 'static,
+    W: StatefulWidget,
+{
+    WidgetComponent {
+        id,
+        area,
+        widget: move |state: &S, area, buf: &mut Buffer| {
+            let (widget, mut widget_state) = widget(state);
+            widget.render(area, buf, &mut widget_state);
+        },
+    }
+}
+
+impl<S, E, A, F> Component<S, E> for WidgetComponent<A, F>
+where
+    S: 'static,
+    E: 'static,
+    A: Fn(Rect) -> Rect + 'static,
+    F: Fn(&S, Rect, &mut Buffer) + 'static,
+{
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn layout(&self, area: Rect) -> Rect {
+        (self.area)(area)
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        (self.widget)(state, area, buf);
+    }
+}
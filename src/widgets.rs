@@ -0,0 +1,16 @@
+//! Components for common parts of interfaces, usable as they are or as a starting point.
+
+mod file_browser;
+mod list;
+mod scroll_view;
+mod split_pane;
+mod table;
+mod tabs;
+mod text_area;
+pub use file_browser::{FileBrowser, FileEntry, SortBy};
+pub use list::List;
+pub use scroll_view::ScrollView;
+pub use split_pane::SplitPane;
+pub use table::{Column, Table};
+pub use tabs::Tabs;
+pub use text_area::TextArea;
@@ -0,0 +1,546 @@
+use crate::{
+    Component, Compositor, Context, Event, Id, KeyCode, KeyEventKind, KeyModifiers, MouseButton,
+    MouseEventKind, TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Widget},
+};
+use std::{
+    any::Any,
+    cell::Cell,
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Builds user event from a file entry.
+type EntryEvent<E> = Box<dyn Fn(&FileEntry) -> E>;
+
+/// File or directory listed by the [`FileBrowser`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub is_dir: bool,
+    /// Size in bytes, zero for directories.
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl FileEntry {
+    /// Returns `true` for dotfiles.
+    pub fn is_hidden(&self) -> bool {
+        self.name.starts_with('.')
+    }
+}
+
+/// Order of entries in the [`FileBrowser`], directories are always listed first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SortBy {
+    #[default]
+    Name,
+    Size,
+    Modified,
+}
+
+impl SortBy {
+    fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Size,
+            Self::Size => Self::Modified,
+            Self::Modified => Self::Name,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Size => "size",
+            Self::Modified => "modified",
+        }
+    }
+}
+
+/// Result of a listing job, sent to the browser as a message.
+struct Listing {
+    generation: u64,
+    entries: io::Result<Vec<FileEntry>>,
+}
+
+/// Lists a directory, reading it in a job so large or slow directories don't block the interface.
+///
+/// While focused, `Up`, `Down`, `j`, `k`, `PageUp`, `PageDown`, `Home` and `End` move the selection,
+/// `Enter`, `Right` and `l` open the selected directory, `Backspace`, `Left` and `h` go to the parent,
+/// `.` toggles hidden files, `s` cycles the sort order and `r` reverses it. `Enter` on a file submits it.
+/// Clicking an entry selects it, clicking the selected one opens or submits it.
+pub struct FileBrowser<E = ()> {
+    id: Id,
+    dir: PathBuf,
+    entries: Vec<FileEntry>,
+    /// Indices of entries that aren't hidden by the hidden file toggle.
+    shown: Vec<usize>,
+    selected: Option<usize>,
+    show_hidden: bool,
+    sort: SortBy,
+    reverse: bool,
+    loading: bool,
+    error: Option<String>,
+    /// Incremented for every listing so results for a directory left already are dropped.
+    generation: u64,
+    /// Name of the entry to select once the listing arrives, e.g. the directory just left.
+    reselect: Option<String>,
+    highlight: Style,
+    block: Option<Block<'static>>,
+    on_select: Option<EntryEvent<E>>,
+    on_submit: Option<EntryEvent<E>>,
+    offset: Cell<usize>,
+    follow: Cell<bool>,
+    viewport: Cell<Rect>,
+}
+
+/// Builder functions
+impl<E> FileBrowser<E> {
+    /// Creates browser of `dir`, which is listed once the browser is mounted.
+    pub fn new(id: Id, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            id,
+            dir: dir.into(),
+            entries: vec![],
+            shown: vec![],
+            selected: None,
+            show_hidden: false,
+            sort: SortBy::Name,
+            reverse: false,
+            loading: false,
+            error: None,
+            generation: 0,
+            reselect: None,
+            highlight: Style::new().add_modifier(Modifier::REVERSED),
+            block: None,
+            on_select: None,
+            on_submit: None,
+            offset: Cell::new(0),
+            follow: Cell::new(true),
+            viewport: Cell::new(Rect::default()),
+        }
+    }
+
+    /// Lists hidden files too, they are hidden by default.
+    pub fn with_hidden(mut self, show: bool) -> Self {
+        self.show_hidden = show;
+        self
+    }
+
+    /// Sets the order of entries, by name by default.
+    pub fn with_sort(mut self, sort: SortBy, reverse: bool) -> Self {
+        self.sort = sort;
+        self.reverse = reverse;
+        self
+    }
+
+    /// Sets style applied over the selected entry, reversed colors by default.
+    pub fn with_highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Draws entries inside `block`.
+    pub fn with_block(mut self, block: Block<'static>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Emits the user event returned by `f` when the selection changes, see [`Context::emit`].
+    pub fn with_select_event(mut self, f: impl Fn(&FileEntry) -> E + 'static) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    /// Emits the user event returned by `f` when a file is submitted.
+    pub fn with_submit_event(mut self, f: impl Fn(&FileEntry) -> E + 'static) -> Self {
+        self.on_submit = Some(Box::new(f));
+        self
+    }
+}
+
+/// Non-builder functions
+impl<E> FileBrowser<E> {
+    /// Returns the listed directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns shown entries in their order.
+    pub fn entries(&self) -> impl Iterator<Item = &FileEntry> {
+        self.shown.iter().map(|&i| &self.entries[i])
+    }
+
+    pub fn selected_entry(&self) -> Option<&FileEntry> {
+        self.selected.map(|i| &self.entries[self.shown[i]])
+    }
+
+    /// Returns `true` while the directory is being read.
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
+    pub fn show_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    /// Shows or hides dotfiles, keeping the selected entry if it is still shown.
+    pub fn set_show_hidden(&mut self, show: bool) {
+        self.show_hidden = show;
+        self.refresh();
+    }
+
+    pub fn sort(&self) -> (SortBy, bool) {
+        (self.sort, self.reverse)
+    }
+
+    /// Sorts entries by `sort`, directories stay first.
+    pub fn set_sort(&mut self, sort: SortBy, reverse: bool) {
+        self.sort = sort;
+        self.reverse = reverse;
+        self.refresh();
+    }
+
+    /// Starts listing `dir`, the current entries are shown until it is read.
+    pub fn open<S>(&mut self, dir: impl Into<PathBuf>, cx: &mut Context<S, E>)
+    where
+        S: Send + 'static,
+        E: Send + 'static,
+    {
+        let dir = dir.into();
+        if dir != self.dir {
+            self.selected = None;
+            self.dir = dir;
+        }
+        self.reload(cx);
+    }
+
+    /// Lists the directory again, e.g. after files were changed.
+    pub fn reload<S>(&mut self, cx: &mut Context<S, E>)
+    where
+        S: Send + 'static,
+        E: Send + 'static,
+    {
+        self.generation += 1;
+        self.loading = true;
+        let (id, generation, dir) = (self.id, self.generation, self.dir.clone());
+        cx.jobs().spawn_owned(id, async move {
+            let entries = read_dir(&dir);
+            move |comp: &mut Compositor<S, E>| {
+                comp.send_to(
+                    id,
+                    Listing {
+                        generation,
+                        entries,
+                    },
+                );
+            }
+        });
+    }
+
+    /// Sorts and filters entries, keeping the selected entry or the one to reselect.
+    fn refresh(&mut self) {
+        let name = self
+            .reselect
+            .take()
+            .or_else(|| self.selected_entry().map(|e| e.name.clone()));
+
+        let (sort, reverse) = (self.sort, self.reverse);
+        self.entries.sort_by(|a, b| {
+            let by_name = a.name.to_lowercase().cmp(&b.name.to_lowercase());
+            let order = match sort {
+                SortBy::Name => by_name,
+                SortBy::Size => a.size.cmp(&b.size).then(by_name),
+                SortBy::Modified => a.modified.cmp(&b.modified).then(by_name),
+            };
+            let order = if reverse { order.reverse() } else { order };
+            b.is_dir.cmp(&a.is_dir).then(order)
+        });
+        self.shown = (0..self.entries.len())
+            .filter(|&i| self.show_hidden || !self.entries[i].is_hidden())
+            .collect();
+
+        self.selected = name
+            .and_then(|name| self.entries().position(|e| e.name == name))
+            .or((!self.shown.is_empty()).then_some(0));
+        self.follow.set(true);
+    }
+
+    fn page(&self) -> usize {
+        (self.viewport.get().height as usize).max(1)
+    }
+
+    fn select_emit<S>(&mut self, index: usize, cx: &mut Context<S, E>)
+    where
+        S: 'static,
+        E: 'static,
+    {
+        let index = index.min(self.shown.len().saturating_sub(1));
+        if self.shown.is_empty() || self.selected == Some(index) {
+            return;
+        }
+        self.selected = Some(index);
+        self.follow.set(true);
+        if let (Some(on_select), Some(entry)) = (&self.on_select, self.selected_entry()) {
+            cx.emit(on_select(entry));
+        }
+    }
+
+    /// Opens the selected directory or submits the selected file.
+    fn activate<S>(&mut self, cx: &mut Context<S, E>)
+    where
+        S: Send + 'static,
+        E: Send + 'static,
+    {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        if entry.is_dir {
+            let path = entry.path.clone();
+            self.open(path, cx);
+        } else if let Some(on_submit) = &self.on_submit {
+            cx.emit(on_submit(entry));
+        }
+    }
+
+    fn open_parent<S>(&mut self, cx: &mut Context<S, E>)
+    where
+        S: Send + 'static,
+        E: Send + 'static,
+    {
+        let Some(parent) = self.dir.parent().map(Path::to_path_buf) else {
+            return;
+        };
+        self.reselect = self
+            .dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        self.open(parent, cx);
+    }
+}
+
+fn read_dir(dir: &Path) -> io::Result<Vec<FileEntry>> {
+    let mut entries = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        // Follows symlinks, broken ones are listed as files.
+        let metadata = fs::metadata(entry.path()).or_else(|_| entry.metadata())?;
+        entries.push(FileEntry {
+            path: entry.path(),
+            name: entry.file_name().to_string_lossy().into_owned(),
+            is_dir: metadata.is_dir(),
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: metadata.modified().ok(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Formats `size` in bytes with a binary unit, e.g. `4.2K`.
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut size = size as f64;
+    for unit in UNITS {
+        size /= 1024.;
+        if size < 1024. || unit == "P" {
+            return if size < 10. {
+                format!("{size:.1}{unit}")
+            } else {
+                format!("{size:.0}{unit}")
+            };
+        }
+    }
+    unreachable!()
+}
+
+impl<S: Send + 'static, E: Send + 'static> Component<S, E> for FileBrowser<E> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        self.reload(cx);
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        let area = match &self.block {
+            Some(block) => {
+                block.clone().render(area, buf);
+                block.inner(area)
+            }
+            None => area,
+        };
+        if area.height == 0 {
+            return;
+        }
+
+        let arrow = if self.reverse { "↑" } else { "↓" };
+        let status = format!(" {}{arrow}", self.sort.label());
+        let status_width = (status.len() as u16).min(area.width);
+        buf.set_stringn(
+            area.x,
+            area.y,
+            self.dir.to_string_lossy(),
+            (area.width - status_width) as usize,
+            Style::new().add_modifier(Modifier::BOLD),
+        );
+        buf.set_string(area.right() - status_width, area.y, status, Style::new());
+
+        let area = Rect {
+            y: area.y + 1,
+            height: area.height - 1,
+            ..area
+        };
+        self.viewport.set(area);
+
+        let message = match (&self.error, self.loading) {
+            (Some(error), _) => Some(error.as_str()),
+            (None, true) if self.shown.is_empty() => Some("Loading…"),
+            (None, false) if self.shown.is_empty() => Some("Empty directory"),
+            _ => None,
+        };
+        if let Some(message) = message {
+            let style = Style::new().add_modifier(Modifier::DIM);
+            buf.set_stringn(area.x, area.y, message, area.width as usize, style);
+            return;
+        }
+
+        let page = self.page();
+        let mut offset = self.offset.get().min(self.shown.len().saturating_sub(1));
+        if let Some(selected) = self.selected.filter(|_| self.follow.get()) {
+            offset = offset.clamp((selected + 1).saturating_sub(page), selected);
+        }
+        self.offset.set(offset);
+
+        for (y, (i, entry)) in (area.y..area.bottom()).zip(self.entries().enumerate().skip(offset))
+        {
+            let row = Rect {
+                y,
+                height: 1,
+                ..area
+            };
+            let (name, size, style) = if entry.is_dir {
+                let bold = Style::new().add_modifier(Modifier::BOLD);
+                (format!("{}/", entry.name), String::new(), bold)
+            } else {
+                (entry.name.clone(), format_size(entry.size), Style::new())
+            };
+            let size_width = (size.len() as u16).min(row.width);
+            let name_width = row.width.saturating_sub(size_width + 1);
+            buf.set_stringn(row.x, y, name, name_width as usize, style);
+            if size_width > 0 {
+                let x = row.right() - size_width;
+                buf.set_stringn(x, y, size, size_width as usize, Style::new());
+            }
+            if self.selected == Some(i) {
+                buf.set_style(row, self.highlight);
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: Box<dyn Any>, cx: &mut Context<S, E>) {
+        let Ok(listing) = message.downcast::<Listing>() else {
+            return;
+        };
+        if listing.generation != self.generation {
+            return;
+        }
+        self.loading = false;
+        match listing.entries {
+            Ok(entries) => {
+                self.entries = entries;
+                self.error = None;
+                self.offset.set(0);
+                self.refresh();
+            }
+            Err(error) => {
+                self.reselect = None;
+                self.error = Some(format!("{}: {error}", self.dir.display()));
+            }
+        }
+        cx.request_redraw();
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        match event {
+            Event::Terminal(TerminalEvent::Key(key))
+                if cx.focused() == Some(self.id) && key.kind != KeyEventKind::Release =>
+            {
+                if key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                {
+                    return;
+                }
+                let selected = self.selected.unwrap_or(0);
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        self.select_emit(selected.saturating_sub(1), cx)
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => self.select_emit(selected + 1, cx),
+                    KeyCode::PageUp => self.select_emit(selected.saturating_sub(self.page()), cx),
+                    KeyCode::PageDown => self.select_emit(selected + self.page(), cx),
+                    KeyCode::Home => self.select_emit(0, cx),
+                    KeyCode::End => self.select_emit(usize::MAX, cx),
+                    KeyCode::Enter => self.activate(cx),
+                    KeyCode::Right | KeyCode::Char('l') => {
+                        if self.selected_entry().is_some_and(|e| e.is_dir) {
+                            self.activate(cx);
+                        }
+                    }
+                    KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => self.open_parent(cx),
+                    KeyCode::Char('.') => self.set_show_hidden(!self.show_hidden),
+                    KeyCode::Char('s') => self.set_sort(self.sort.next(), self.reverse),
+                    KeyCode::Char('r') => self.set_sort(self.sort, !self.reverse),
+                    _ => return,
+                }
+            }
+            Event::Terminal(TerminalEvent::Mouse(mouse)) => {
+                let viewport = self.viewport.get();
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        cx.focus(self.id);
+                        if mouse.row < viewport.y {
+                            return;
+                        }
+                        let index = self.offset.get() + (mouse.row - viewport.y) as usize;
+                        if index >= self.shown.len() {
+                            return;
+                        }
+                        if self.selected == Some(index) {
+                            self.activate(cx);
+                        } else {
+                            self.select_emit(index, cx);
+                        }
+                    }
+                    MouseEventKind::ScrollUp => {
+                        self.offset.set(self.offset.get().saturating_sub(3));
+                        self.follow.set(false);
+                    }
+                    MouseEventKind::ScrollDown => {
+                        let max = self.shown.len().saturating_sub(self.page());
+                        self.offset.set((self.offset.get() + 3).min(max));
+                        self.follow.set(false);
+                    }
+                    _ => return,
+                }
+            }
+            _ => return,
+        }
+        _ = event.consume();
+    }
+}
@@ -0,0 +1,273 @@
+use crate::{
+    Component, Context, Event, Id, KeyCode, KeyEventKind, KeyModifiers, MouseButton,
+    MouseEventKind, TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Widget},
+};
+use std::cell::Cell;
+
+/// Draws an item in its area, `true` if it is selected.
+type RenderItem<T> = Box<dyn Fn(&T, Rect, &mut Buffer, bool)>;
+/// Builds user event from the index of an item and the item.
+type ItemEvent<T, E> = Box<dyn Fn(usize, &T) -> E>;
+
+/// List of items where only the visible ones are drawn, so it stays fast with many thousands of them.
+///
+/// While focused, `Up`, `Down`, `j`, `k`, `PageUp`, `PageDown`, `Home` and `End` move the selection and
+/// `Enter` submits the selected item. Clicking an item selects it, clicking the selected one submits it.
+pub struct List<T, E = ()> {
+    id: Id,
+    items: Vec<T>,
+    render: RenderItem<T>,
+    selected: Option<usize>,
+    item_height: u16,
+    highlight: Style,
+    block: Option<Block<'static>>,
+    on_select: Option<ItemEvent<T, E>>,
+    on_submit: Option<ItemEvent<T, E>>,
+    /// First item shown, updated when drawing to keep the selection in view.
+    offset: Cell<usize>,
+    follow: Cell<bool>,
+    /// Area items were drawn in last, used to page and map clicks.
+    viewport: Cell<Rect>,
+}
+
+/// Builder functions
+impl<T, E> List<T, E> {
+    /// Creates list of `items` drawn with `render`, e.g.
+    /// `|item, area, buf, _| buf.set_stringn(area.x, area.y, item, area.width as usize, Style::new())`.
+    pub fn new(
+        id: Id,
+        items: Vec<T>,
+        render: impl Fn(&T, Rect, &mut Buffer, bool) + 'static,
+    ) -> Self {
+        let selected = (!items.is_empty()).then_some(0);
+        Self {
+            id,
+            items,
+            render: Box::new(render),
+            selected,
+            item_height: 1,
+            highlight: Style::new().add_modifier(Modifier::REVERSED),
+            block: None,
+            on_select: None,
+            on_submit: None,
+            offset: Cell::new(0),
+            follow: Cell::new(true),
+            viewport: Cell::new(Rect::default()),
+        }
+    }
+
+    /// Sets the number of rows every item takes, `1` by default.
+    pub fn with_item_height(mut self, height: u16) -> Self {
+        self.item_height = height.max(1);
+        self
+    }
+
+    /// Sets style applied over the selected item after it is drawn, reversed colors by default.
+    pub fn with_highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Draws items inside `block`.
+    pub fn with_block(mut self, block: Block<'static>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Emits the user event returned by `f` when the selection changes, see [`Context::emit`].
+    pub fn with_select_event(mut self, f: impl Fn(usize, &T) -> E + 'static) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    /// Emits the user event returned by `f` when the selected item is submitted.
+    pub fn with_submit_event(mut self, f: impl Fn(usize, &T) -> E + 'static) -> Self {
+        self.on_submit = Some(Box::new(f));
+        self
+    }
+}
+
+/// Non-builder functions
+impl<T, E> List<T, E> {
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Replaces items, the selection is kept if it is still in the list.
+    pub fn set_items(&mut self, items: Vec<T>) {
+        self.items = items;
+        self.selected = match self.items.len() {
+            0 => None,
+            len => Some(self.selected.unwrap_or(0).min(len - 1)),
+        };
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+        self.selected.get_or_insert(0);
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the index of the selected item.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn selected_item(&self) -> Option<&T> {
+        self.items.get(self.selected?)
+    }
+
+    /// Selects item at `index` and scrolls to it, `None` clears the selection.
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index.filter(|i| *i < self.items.len());
+        self.follow.set(true);
+    }
+
+    /// Returns the number of items that fit in the area drawn last.
+    fn page(&self) -> usize {
+        (self.viewport.get().height / self.item_height).max(1) as usize
+    }
+
+    /// Selects item at `index`, emitting the select event if it changed.
+    fn select_emit<S>(&mut self, index: usize, cx: &mut Context<S, E>)
+    where
+        S: 'static,
+        E: 'static,
+    {
+        let index = index.min(self.items.len().saturating_sub(1));
+        if self.items.is_empty() || self.selected == Some(index) {
+            return;
+        }
+        self.select(Some(index));
+        if let Some(on_select) = &self.on_select {
+            cx.emit(on_select(index, &self.items[index]));
+        }
+    }
+
+    fn submit<S>(&self, cx: &mut Context<S, E>)
+    where
+        S: 'static,
+        E: 'static,
+    {
+        let Some(index) = self.selected else {
+            return;
+        };
+        if let Some(on_submit) = &self.on_submit {
+            cx.emit(on_submit(index, &self.items[index]));
+        }
+    }
+}
+
+impl<T: 'static, S: 'static, E: 'static> Component<S, E> for List<T, E> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        let area = match &self.block {
+            Some(block) => {
+                block.clone().render(area, buf);
+                block.inner(area)
+            }
+            None => area,
+        };
+        self.viewport.set(area);
+
+        let page = self.page();
+        let mut offset = self.offset.get().min(self.items.len().saturating_sub(1));
+        if let Some(selected) = self.selected.filter(|_| self.follow.get()) {
+            offset = offset.clamp((selected + 1).saturating_sub(page), selected);
+        }
+        self.offset.set(offset);
+
+        let rows = (area.y..area.bottom()).step_by(self.item_height as usize);
+        for (y, (i, item)) in rows.zip(self.items.iter().enumerate().skip(offset)) {
+            let item_area = Rect {
+                y,
+                height: self.item_height.min(area.bottom() - y),
+                ..area
+            };
+            let selected = self.selected == Some(i);
+            (self.render)(item, item_area, buf, selected);
+            if selected {
+                buf.set_style(item_area, self.highlight);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        match event {
+            Event::Terminal(TerminalEvent::Key(key))
+                if cx.focused() == Some(self.id) && key.kind != KeyEventKind::Release =>
+            {
+                if key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                {
+                    return;
+                }
+                let selected = self.selected.unwrap_or(0);
+                let last = self.items.len().saturating_sub(1);
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        self.select_emit(selected.saturating_sub(1), cx)
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => self.select_emit(selected + 1, cx),
+                    KeyCode::PageUp => self.select_emit(selected.saturating_sub(self.page()), cx),
+                    KeyCode::PageDown => self.select_emit(selected + self.page(), cx),
+                    KeyCode::Home => self.select_emit(0, cx),
+                    KeyCode::End => self.select_emit(last, cx),
+                    KeyCode::Enter => self.submit(cx),
+                    _ => return,
+                }
+            }
+            Event::Terminal(TerminalEvent::Mouse(mouse)) => {
+                let viewport = self.viewport.get();
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) if mouse.row >= viewport.y => {
+                        cx.focus(self.id);
+                        let row = (mouse.row - viewport.y) / self.item_height;
+                        let index = self.offset.get() + row as usize;
+                        if index >= self.items.len() {
+                            return;
+                        }
+                        if self.selected == Some(index) {
+                            self.submit(cx);
+                        } else {
+                            self.select_emit(index, cx);
+                        }
+                    }
+                    MouseEventKind::ScrollUp => {
+                        self.offset.set(self.offset.get().saturating_sub(3));
+                        self.follow.set(false);
+                    }
+                    MouseEventKind::ScrollDown => {
+                        let max = self.items.len().saturating_sub(self.page());
+                        self.offset.set((self.offset.get() + 3).min(max));
+                        self.follow.set(false);
+                    }
+                    _ => return,
+                }
+            }
+            _ => return,
+        }
+        _ = event.consume();
+    }
+}
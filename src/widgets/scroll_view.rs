@@ -0,0 +1,486 @@
+use crate::{
+    Action, Component, Context, CursorStyle, Event, Id, KeyCode, KeyEventKind, KeyModifiers,
+    MouseButton, MouseEvent, MouseEventKind, TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    symbols,
+    widgets::{Block, Widget},
+};
+use std::cell::Cell;
+
+/// Rows or columns scrolled by a turn of the mouse wheel.
+const WHEEL_STEP: u16 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Orientation {
+    Vertical,
+    Horizontal,
+}
+
+/// Scrollbar thumb being dragged, `grab` is the offset of the cursor from the start of the thumb.
+#[derive(Clone, Copy)]
+struct Drag {
+    orientation: Orientation,
+    grab: u16,
+}
+
+/// Areas of the scroll view computed from its assigned area.
+#[derive(Clone, Copy, Default)]
+struct Parts {
+    viewport: Rect,
+    vertical: Option<Rect>,
+    horizontal: Option<Rect>,
+}
+
+/// Wraps `child` drawn at its full content size, showing the part of it that fits in the assigned area.
+///
+/// The child is drawn into a separate buffer of the content size every frame, which can't hold
+/// more than `u16::MAX` cells, larger content is clipped. Mouse events are passed to the child with
+/// coordinates relative to its content, and other events while the child sees itself as focused
+/// if the scroll view is.
+///
+/// Scrollbars are shown on the right and bottom edges when the content overflows. They can be dragged,
+/// the mouse wheel scrolls vertically, or horizontally with `Shift`. While focused, arrows, `PageUp`,
+/// `PageDown`, `Home` and `End` scroll when the child doesn't consume them.
+pub struct ScrollView<C> {
+    id: Id,
+    child: C,
+    content: (u16, u16),
+    block: Option<Block<'static>>,
+    scrollbar_style: Style,
+    drag: Option<Drag>,
+    /// Horizontal and vertical offset of the content, clamped when drawing.
+    scroll: Cell<(u16, u16)>,
+    /// Areas drawn in last, used to map mouse events.
+    parts: Cell<Parts>,
+}
+
+/// Builder functions
+impl<C> ScrollView<C> {
+    /// Creates scroll view of `child`, whose content is `width` columns by `height` rows.
+    pub fn new(id: Id, child: C, width: u16, height: u16) -> Self {
+        Self {
+            id,
+            child,
+            content: content_size(width, height),
+            block: None,
+            scrollbar_style: Style::new(),
+            drag: None,
+            scroll: Cell::new((0, 0)),
+            parts: Cell::new(Parts::default()),
+        }
+    }
+
+    /// Draws the content and scrollbars inside `block`.
+    pub fn with_block(mut self, block: Block<'static>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Sets style of the scrollbars.
+    pub fn with_scrollbar_style(mut self, style: Style) -> Self {
+        self.scrollbar_style = style;
+        self
+    }
+}
+
+/// Non-builder functions
+impl<C> ScrollView<C> {
+    pub fn child(&self) -> &C {
+        &self.child
+    }
+
+    pub fn child_mut(&mut self) -> &mut C {
+        &mut self.child
+    }
+
+    /// Returns width and height of the content.
+    pub fn content_size(&self) -> (u16, u16) {
+        self.content
+    }
+
+    /// Resizes the content, e.g. after the child has grown.
+    pub fn set_content_size(&mut self, width: u16, height: u16) {
+        self.content = content_size(width, height);
+    }
+
+    /// Returns the horizontal and vertical offset of the content.
+    pub fn scroll(&self) -> (u16, u16) {
+        self.scroll.get()
+    }
+
+    /// Scrolls so column `x` and row `y` of the content are in the top left corner, as far as possible.
+    pub fn scroll_to(&mut self, x: u16, y: u16) {
+        self.scroll.set((x, y));
+        self.clamp_scroll();
+    }
+
+    /// Scrolls by `dx` columns and `dy` rows.
+    pub fn scroll_by(&mut self, dx: i32, dy: i32) {
+        let (x, y) = self.scroll.get();
+        let x = (x as i32 + dx).clamp(0, u16::MAX as i32) as u16;
+        let y = (y as i32 + dy).clamp(0, u16::MAX as i32) as u16;
+        self.scroll_to(x, y);
+    }
+
+    fn parts(&self, area: Rect) -> Parts {
+        let inner = match &self.block {
+            Some(block) => block.inner(area),
+            None => area,
+        };
+        let (width, height) = self.content;
+
+        // Either scrollbar takes space from the other direction, so it may make the other one needed.
+        let mut vertical = height > inner.height;
+        let mut horizontal = width > inner.width.saturating_sub(vertical as u16);
+        vertical = height > inner.height.saturating_sub(horizontal as u16);
+        horizontal = width > inner.width.saturating_sub(vertical as u16);
+
+        let viewport = Rect {
+            width: inner.width.saturating_sub(vertical as u16),
+            height: inner.height.saturating_sub(horizontal as u16),
+            ..inner
+        };
+        Parts {
+            viewport,
+            vertical: vertical.then_some(Rect::new(
+                viewport.right(),
+                viewport.y,
+                1,
+                viewport.height,
+            )),
+            horizontal: horizontal.then_some(Rect::new(
+                viewport.x,
+                viewport.bottom(),
+                viewport.width,
+                1,
+            )),
+        }
+    }
+
+    /// Returns the largest horizontal and vertical offset for the viewport drawn last.
+    fn max_scroll(&self) -> (u16, u16) {
+        let viewport = self.parts.get().viewport;
+        (
+            self.content.0.saturating_sub(viewport.width),
+            self.content.1.saturating_sub(viewport.height),
+        )
+    }
+
+    fn clamp_scroll(&self) {
+        let (x, y) = self.scroll.get();
+        let (max_x, max_y) = self.max_scroll();
+        self.scroll.set((x.min(max_x), y.min(max_y)));
+    }
+
+    /// Returns the area of the child within its content buffer.
+    fn content_area(&self) -> Rect {
+        Rect::new(0, 0, self.content.0, self.content.1)
+    }
+
+    /// Returns the track, content and viewport length and the offset along `orientation`.
+    fn axis(&self, orientation: Orientation) -> Option<(Rect, u16, u16, u16)> {
+        let parts = self.parts.get();
+        let (x, y) = self.scroll.get();
+        match orientation {
+            Orientation::Vertical => parts
+                .vertical
+                .map(|track| (track, self.content.1, parts.viewport.height, y)),
+            Orientation::Horizontal => parts
+                .horizontal
+                .map(|track| (track, self.content.0, parts.viewport.width, x)),
+        }
+    }
+
+    fn draw_scrollbar(&self, buf: &mut Buffer, orientation: Orientation) {
+        let Some((track, content, viewport, offset)) = self.axis(orientation) else {
+            return;
+        };
+        let (len, symbol) = match orientation {
+            Orientation::Vertical => (track.height, symbols::line::VERTICAL),
+            Orientation::Horizontal => (track.width, symbols::line::HORIZONTAL),
+        };
+        let (start, thumb) = thumb(len, content, viewport, offset);
+        for i in 0..len {
+            let (x, y) = match orientation {
+                Orientation::Vertical => (track.x, track.y + i),
+                Orientation::Horizontal => (track.x + i, track.y),
+            };
+            let symbol = if (start..start + thumb).contains(&i) {
+                symbols::block::FULL
+            } else {
+                symbol
+            };
+            buf.get_mut(x, y)
+                .set_symbol(symbol)
+                .set_style(self.scrollbar_style);
+        }
+    }
+
+    /// Scrolls so the thumb starts at `pos` along the track.
+    fn drag_to(&mut self, orientation: Orientation, pos: u16) {
+        let Some((track, content, viewport, offset)) = self.axis(orientation) else {
+            return;
+        };
+        let (len, start) = match orientation {
+            Orientation::Vertical => (track.height, track.y),
+            Orientation::Horizontal => (track.width, track.x),
+        };
+        let (_, thumb) = thumb(len, content, viewport, offset);
+        let free = len.saturating_sub(thumb);
+        let max = content.saturating_sub(viewport) as u32;
+        let offset = match free {
+            0 => 0,
+            free => {
+                let pos = pos.saturating_sub(start).min(free) as u32;
+                ((pos * max + free as u32 / 2) / free as u32) as u16
+            }
+        };
+        let (x, y) = self.scroll.get();
+        match orientation {
+            Orientation::Vertical => self.scroll_to(x, offset),
+            Orientation::Horizontal => self.scroll_to(offset, y),
+        }
+    }
+
+    /// Handles scrolling with the mouse, returns `true` if `mouse` was used.
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> bool {
+        let parts = self.parts.get();
+        let hit = |area: Option<Rect>| {
+            area.is_some_and(|a| a.intersects(Rect::new(mouse.column, mouse.row, 1, 1)))
+        };
+        let step = WHEEL_STEP as i32;
+        match mouse.kind {
+            MouseEventKind::ScrollUp if mouse.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.scroll_by(-step, 0)
+            }
+            MouseEventKind::ScrollDown if mouse.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.scroll_by(step, 0)
+            }
+            MouseEventKind::ScrollUp => self.scroll_by(0, -step),
+            MouseEventKind::ScrollDown => self.scroll_by(0, step),
+            MouseEventKind::ScrollLeft => self.scroll_by(-step, 0),
+            MouseEventKind::ScrollRight => self.scroll_by(step, 0),
+            MouseEventKind::Down(MouseButton::Left) if hit(parts.vertical) => {
+                self.start_drag(Orientation::Vertical, mouse.row)
+            }
+            MouseEventKind::Down(MouseButton::Left) if hit(parts.horizontal) => {
+                self.start_drag(Orientation::Horizontal, mouse.column)
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.drag.is_some() => {
+                let Drag { orientation, grab } = self.drag.unwrap();
+                let pos = match orientation {
+                    Orientation::Vertical => mouse.row,
+                    Orientation::Horizontal => mouse.column,
+                };
+                self.drag_to(orientation, pos.saturating_sub(grab));
+            }
+            MouseEventKind::Up(MouseButton::Left) if self.drag.is_some() => self.drag = None,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Starts dragging the thumb, a click beside it first moves its middle under the cursor.
+    fn start_drag(&mut self, orientation: Orientation, pos: u16) {
+        let Some((track, content, viewport, offset)) = self.axis(orientation) else {
+            return;
+        };
+        let (len, track_start) = match orientation {
+            Orientation::Vertical => (track.height, track.y),
+            Orientation::Horizontal => (track.width, track.x),
+        };
+        let (start, thumb) = thumb(len, content, viewport, offset);
+        let at = pos - track_start;
+        let grab = if (start..start + thumb).contains(&at) {
+            at - start
+        } else {
+            self.drag_to(orientation, pos.saturating_sub(thumb / 2));
+            thumb / 2
+        };
+        self.drag = Some(Drag { orientation, grab });
+    }
+
+    fn handle_key<S: 'static, E: 'static>(&mut self, event: &mut Event<E>, cx: &Context<S, E>) {
+        let Event::Terminal(TerminalEvent::Key(key)) = event else {
+            return;
+        };
+        if cx.focused() != Some(self.id)
+            || key.kind == KeyEventKind::Release
+            || key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return;
+        }
+        let page = self.parts.get().viewport.height.max(1) as i32;
+        match key.code {
+            KeyCode::Up => self.scroll_by(0, -1),
+            KeyCode::Down => self.scroll_by(0, 1),
+            KeyCode::Left => self.scroll_by(-1, 0),
+            KeyCode::Right => self.scroll_by(1, 0),
+            KeyCode::PageUp => self.scroll_by(0, -page),
+            KeyCode::PageDown => self.scroll_by(0, page),
+            KeyCode::Home => self.scroll_to(0, 0),
+            KeyCode::End => self.scroll_to(0, u16::MAX),
+            _ => return,
+        }
+        _ = event.consume();
+    }
+}
+
+/// Returns the content size clipped to the largest buffer, keeping its aspect ratio like [`Rect::new`].
+fn content_size(width: u16, height: u16) -> (u16, u16) {
+    let area = Rect::new(0, 0, width, height);
+    (area.width, area.height)
+}
+
+/// Returns the start and length of a scrollbar thumb on a track `len` cells long.
+fn thumb(len: u16, content: u16, viewport: u16, offset: u16) -> (u16, u16) {
+    let thumb =
+        ((len as u32 * viewport as u32) / content.max(1) as u32).clamp(1, len.max(1) as u32) as u16;
+    let max = content.saturating_sub(viewport) as u32;
+    let start = match max {
+        0 => 0,
+        max => {
+            (((len.saturating_sub(thumb)) as u32 * offset.min(max as u16) as u32 + max / 2) / max)
+                as u16
+        }
+    };
+    (start, thumb)
+}
+
+impl<S: 'static, E: 'static, C: Component<S, E>> Component<S, E> for ScrollView<C> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        if let Some(block) = &self.block {
+            block.clone().render(area, buf);
+        }
+        let parts = self.parts(area);
+        self.parts.set(parts);
+        self.clamp_scroll();
+
+        let mut content = Buffer::empty(self.content_area());
+        self.child
+            .view(self.child.layout(self.content_area()), &mut content, state);
+
+        let (sx, sy) = self.scroll.get();
+        let viewport = parts.viewport;
+        let width = viewport.width.min(self.content.0 - sx);
+        let height = viewport.height.min(self.content.1 - sy);
+        for y in 0..height {
+            for x in 0..width {
+                *buf.get_mut(viewport.x + x, viewport.y + y) = content.get(sx + x, sy + y).clone();
+            }
+        }
+
+        self.draw_scrollbar(buf, Orientation::Vertical);
+        self.draw_scrollbar(buf, Orientation::Horizontal);
+    }
+
+    fn cursor(&self, _area: Rect, state: &S) -> Option<(u16, u16)> {
+        let (x, y) = self
+            .child
+            .cursor(self.child.layout(self.content_area()), state)?;
+        let (sx, sy) = self.scroll.get();
+        let viewport = self.parts.get().viewport;
+        let (x, y) = (x.checked_sub(sx)?, y.checked_sub(sy)?);
+        (x < viewport.width && y < viewport.height).then_some((viewport.x + x, viewport.y + y))
+    }
+
+    fn cursor_style(&self, state: &S) -> Option<CursorStyle> {
+        self.child.cursor_style(state)
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        self.child.on_mount(cx);
+    }
+
+    fn on_unmount(&mut self, cx: &mut Context<S, E>) {
+        self.child.on_unmount(cx);
+    }
+
+    fn title(&self) -> Option<&str> {
+        self.child.title()
+    }
+
+    fn actions(&self) -> &[Action] {
+        self.child.actions()
+    }
+
+    fn handle_action(&mut self, action: &Action, cx: &mut Context<S, E>) -> bool {
+        let child_id = self.child.id();
+        let child = &mut self.child;
+        let handled = cx.with_inner_focus(self.id, Some(child_id), |cx| {
+            child.handle_action(action, cx)
+        });
+        cx.redirect_focus(self.id, |id| id == child_id);
+        handled
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        // Errors of the child can only be reported through `try_handle_event`.
+        _ = self.try_handle_event(event, cx);
+    }
+
+    fn try_handle_event(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let child = &mut self.child;
+        let child_id = child.id();
+
+        let Event::Terminal(TerminalEvent::Mouse(mouse)) = event else {
+            let result = cx.with_inner_focus(self.id, Some(child_id), |cx| {
+                child.try_handle_event(event, cx)
+            });
+            cx.redirect_focus(self.id, |id| id == child_id);
+            result?;
+            if !event.is_consumed() {
+                self.handle_key(event, cx);
+            }
+            return Ok(());
+        };
+
+        let mouse = *mouse;
+        if matches!(mouse.kind, MouseEventKind::Down(_)) {
+            cx.focus(self.id);
+        }
+        if self.handle_mouse(mouse) {
+            _ = event.consume();
+            return Ok(());
+        }
+
+        let viewport = self.parts.get().viewport;
+        if !viewport.intersects(Rect::new(mouse.column, mouse.row, 1, 1)) {
+            return Ok(());
+        }
+        let (sx, sy) = self.scroll.get();
+        let mut inner = Event::Terminal(TerminalEvent::Mouse(MouseEvent {
+            column: mouse.column - viewport.x + sx,
+            row: mouse.row - viewport.y + sy,
+            ..mouse
+        }));
+        let child = &mut self.child;
+        let result = cx.with_inner_focus(self.id, Some(child_id), |cx| {
+            child.try_handle_event(&mut inner, cx)
+        });
+        cx.redirect_focus(self.id, |id| id == child_id);
+        result?;
+        if inner.is_consumed() {
+            _ = event.consume();
+        }
+        Ok(())
+    }
+}
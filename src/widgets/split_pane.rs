@@ -0,0 +1,444 @@
+use crate::{
+    Action, Component, Context, CursorStyle, Event, Id, KeyCode, KeyEventKind, KeyModifiers,
+    MouseButton, MouseEventKind, PointerShape, TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Direction, Rect},
+    style::Style,
+    symbols,
+};
+use std::{any::Any, cell::Cell};
+
+/// Builds user event from the ratios of panes.
+type RatiosEvent<E> = Box<dyn Fn(&[f32]) -> E>;
+
+struct Pane<S, E> {
+    child: Box<dyn Component<S, E>>,
+    weight: f32,
+}
+
+/// Panes laid out side by side or on top of each other, separated by dividers that can be dragged
+/// with the mouse to resize neighbouring panes.
+///
+/// Panes keep their ratios of the available space when the split is resized, e.g. along with
+/// the terminal. Ratios can be saved with [`Self::with_resize_event`] and restored with
+/// [`Self::with_ratios`].
+///
+/// Mouse events go to the pane under the cursor, other events go to the focused pane first.
+/// The child of the focused pane sees itself as focused while the split is focused. Pane is focused
+/// when it is clicked or its child requests focus, `F6` and `Shift-F6` focus the next or the previous one.
+pub struct SplitPane<S = (), E = ()> {
+    id: Id,
+    direction: Direction,
+    panes: Vec<Pane<S, E>>,
+    focused: usize,
+    min_size: u16,
+    divider_style: Style,
+    on_resize: Option<RatiosEvent<E>>,
+    /// Divider being dragged.
+    dragging: Option<usize>,
+    /// Area the split was drawn in last.
+    area: Cell<Rect>,
+}
+
+/// Builder functions
+impl<S, E> SplitPane<S, E> {
+    /// Creates split with panes side by side for [`Direction::Horizontal`]
+    /// or on top of each other for [`Direction::Vertical`].
+    pub fn new(id: Id, direction: Direction) -> Self {
+        Self {
+            id,
+            direction,
+            panes: Vec::new(),
+            focused: 0,
+            min_size: 1,
+            divider_style: Style::new(),
+            on_resize: None,
+            dragging: None,
+            area: Cell::new(Rect::default()),
+        }
+    }
+
+    /// Adds pane showing `child` after the other panes, panes share the space equally by default.
+    pub fn with_pane(mut self, child: impl Component<S, E>) -> Self {
+        self.panes.push(Pane {
+            child: Box::new(child),
+            weight: 1.0,
+        });
+        self
+    }
+
+    /// Sets shares of the space of panes in order, e.g. `[1.0, 2.0]` or `[0.25, 0.75]`,
+    /// see [`Self::set_ratios`].
+    pub fn with_ratios(mut self, ratios: &[f32]) -> Self {
+        self.set_ratios(ratios);
+        self
+    }
+
+    /// Sets minimum size of a pane along the split when dragging dividers, 1 by default.
+    pub fn with_min_size(mut self, min_size: u16) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    pub fn with_divider_style(mut self, style: Style) -> Self {
+        self.divider_style = style;
+        self
+    }
+
+    /// Emits the user event returned by `f` with ratios of panes when a divider is released,
+    /// see [`Context::emit`].
+    pub fn with_resize_event(mut self, f: impl Fn(&[f32]) -> E + 'static) -> Self {
+        self.on_resize = Some(Box::new(f));
+        self
+    }
+}
+
+/// Non-builder functions
+impl<S, E> SplitPane<S, E> {
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn len(&self) -> usize {
+        self.panes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.panes.is_empty()
+    }
+
+    /// Returns the index of the focused pane.
+    pub fn focused(&self) -> usize {
+        self.focused
+    }
+
+    /// Returns shares of the space of panes, they add up to 1.
+    pub fn ratios(&self) -> Vec<f32> {
+        let total = self.panes.iter().map(|p| p.weight).sum::<f32>();
+        self.panes
+            .iter()
+            .map(|p| if total > 0.0 { p.weight / total } else { 0.0 })
+            .collect()
+    }
+
+    /// Sets shares of the space of panes in order, they are scaled to add up to 1.
+    /// Missing ratios leave panes as they are, negative and non-finite ratios are ignored.
+    pub fn set_ratios(&mut self, ratios: &[f32]) {
+        for (pane, &ratio) in self.panes.iter_mut().zip(ratios) {
+            if ratio.is_finite() && ratio >= 0.0 {
+                pane.weight = ratio;
+            }
+        }
+    }
+}
+
+impl<S: 'static, E: 'static> SplitPane<S, E> {
+    /// Returns the child with `id` if it is a `C`.
+    pub fn child<C: Component<S, E>>(&self, id: Id) -> Option<&C> {
+        let pane = self.panes.iter().find(|p| p.child.id() == id)?;
+        (&*pane.child as &dyn Any).downcast_ref()
+    }
+
+    /// Returns the child with `id` if it is a `C`.
+    pub fn child_mut<C: Component<S, E>>(&mut self, id: Id) -> Option<&mut C> {
+        let pane = self.panes.iter_mut().find(|p| p.child.id() == id)?;
+        (&mut *pane.child as &mut dyn Any).downcast_mut()
+    }
+
+    /// Focuses pane at `index`, the split itself is focused too.
+    pub fn focus(&mut self, index: usize, cx: &mut Context<S, E>) {
+        if index < self.panes.len() {
+            self.focused = index;
+            cx.focus(self.id);
+        }
+    }
+
+    /// Returns areas of panes in `area`, a divider follows each pane but the last one.
+    fn areas(&self, area: Rect) -> Vec<Rect> {
+        let (start, length) = match self.direction {
+            Direction::Horizontal => (area.x, area.width),
+            Direction::Vertical => (area.y, area.height),
+        };
+        let dividers = self.panes.len().saturating_sub(1) as u16;
+        let space = length.saturating_sub(dividers) as f32;
+        let total = self.panes.iter().map(|p| p.weight).sum::<f32>();
+
+        // Rounds boundaries rather than sizes, so panes always fill the space.
+        let (mut sum, mut pos) = (0.0, start);
+        let mut areas = Vec::with_capacity(self.panes.len());
+        for (i, pane) in self.panes.iter().enumerate() {
+            sum += pane.weight;
+            let end = match total > 0.0 {
+                true => start + (sum / total * space).round() as u16 + i as u16,
+                false => start + i as u16,
+            }
+            .clamp(pos, start + length);
+            areas.push(match self.direction {
+                Direction::Horizontal => Rect {
+                    x: pos,
+                    width: end - pos,
+                    ..area
+                },
+                Direction::Vertical => Rect {
+                    y: pos,
+                    height: end - pos,
+                    ..area
+                },
+            });
+            pos = end.saturating_add(1).min(start + length);
+        }
+        areas
+    }
+
+    /// Returns the divider following pane with `pane` area.
+    fn divider(&self, pane: Rect, area: Rect) -> Rect {
+        match self.direction {
+            Direction::Horizontal => Rect {
+                x: pane.right(),
+                width: 1,
+                ..area
+            },
+            Direction::Vertical => Rect {
+                y: pane.bottom(),
+                height: 1,
+                ..area
+            },
+        }
+        .intersection(area)
+    }
+
+    /// Moves divider after pane at `index` to `pos` along the split.
+    fn drag(&mut self, index: usize, pos: u16) {
+        let areas = self.areas(self.area.get());
+        let (first, second) = match self.direction {
+            Direction::Horizontal => ((areas[index].x, areas[index].width), areas[index + 1].width),
+            Direction::Vertical => (
+                (areas[index].y, areas[index].height),
+                areas[index + 1].height,
+            ),
+        };
+        let combined = first.1 + second;
+        if combined == 0 {
+            return;
+        }
+        let min = self.min_size.min(combined / 2);
+        let size = pos.saturating_sub(first.0).clamp(min, combined - min);
+
+        let weight = self.panes[index].weight + self.panes[index + 1].weight;
+        self.panes[index].weight = weight * size as f32 / combined as f32;
+        self.panes[index + 1].weight = weight - self.panes[index].weight;
+    }
+
+    fn pointer_shape(&self) -> PointerShape {
+        match self.direction {
+            Direction::Horizontal => PointerShape::EwResize,
+            Direction::Vertical => PointerShape::NsResize,
+        }
+    }
+
+    /// Passes `event` to pane at `index`, its child sees itself focused if the pane is focused.
+    fn forward(
+        &mut self,
+        index: usize,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let inner = self.panes.get(self.focused).map(|p| p.child.id());
+        let child = &mut self.panes[index].child;
+        let result = cx.with_inner_focus(self.id, inner, |cx| child.try_handle_event(event, cx));
+        self.redirect_focus(cx);
+        result
+    }
+
+    /// Focuses pane whose child requested focus.
+    fn redirect_focus(&mut self, cx: &mut Context<S, E>) {
+        let panes = &self.panes;
+        if let Some(id) = cx.redirect_focus(self.id, |id| panes.iter().any(|p| p.child.id() == id))
+        {
+            self.focused = self.panes.iter().position(|p| p.child.id() == id).unwrap();
+        }
+    }
+
+    fn handle_mouse(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Event::Terminal(TerminalEvent::Mouse(mouse)) = *event else {
+            return Ok(());
+        };
+        let (point, pos) = (
+            Rect::new(mouse.column, mouse.row, 1, 1),
+            match self.direction {
+                Direction::Horizontal => mouse.column,
+                Direction::Vertical => mouse.row,
+            },
+        );
+        let area = self.area.get();
+        let areas = self.areas(area);
+        let divider = areas
+            .iter()
+            .take(areas.len().saturating_sub(1))
+            .position(|&pane| self.divider(pane, area).intersects(point));
+
+        match (mouse.kind, divider, self.dragging) {
+            (MouseEventKind::Down(MouseButton::Left), Some(index), _) => {
+                self.dragging = Some(index);
+                cx.focus(self.id);
+            }
+            (MouseEventKind::Drag(MouseButton::Left), _, Some(index)) => self.drag(index, pos),
+            (MouseEventKind::Up(MouseButton::Left), _, Some(_)) => {
+                self.dragging = None;
+                if let Some(on_resize) = &self.on_resize {
+                    cx.emit(on_resize(&self.ratios()));
+                }
+            }
+            (MouseEventKind::Moved, Some(_), _) => cx.set_pointer_shape(self.pointer_shape()),
+            _ => {
+                let Some(index) = areas.iter().position(|pane| pane.intersects(point)) else {
+                    return Ok(());
+                };
+                if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                    cx.focus(self.id);
+                    if self.panes[index].child.focusable() {
+                        self.focused = index;
+                    }
+                }
+                return self.forward(index, event, cx);
+            }
+        }
+        cx.set_pointer_shape(self.pointer_shape());
+        _ = event.consume();
+        Ok(())
+    }
+
+    fn handle_key(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        let Event::Terminal(TerminalEvent::Key(key)) = event else {
+            return;
+        };
+        if cx.focused() != Some(self.id)
+            || key.kind == KeyEventKind::Release
+            || key.code != KeyCode::F(6)
+            || self.panes.is_empty()
+        {
+            return;
+        }
+        let len = self.panes.len();
+        self.focused = match key.modifiers.contains(KeyModifiers::SHIFT) {
+            true => (self.focused + len - 1) % len,
+            false => (self.focused + 1) % len,
+        };
+        cx.request_redraw();
+        _ = event.consume();
+    }
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for SplitPane<S, E> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        for pane in &mut self.panes {
+            pane.child.on_mount(cx);
+        }
+    }
+
+    fn on_unmount(&mut self, cx: &mut Context<S, E>) {
+        for pane in &mut self.panes {
+            pane.child.on_unmount(cx);
+        }
+    }
+
+    fn on_enter(&mut self, cx: &mut Context<S, E>) {
+        for pane in &mut self.panes {
+            pane.child.on_enter(cx);
+        }
+    }
+
+    fn on_leave(&mut self, cx: &mut Context<S, E>) {
+        for pane in &mut self.panes {
+            pane.child.on_leave(cx);
+        }
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        self.area.set(area);
+        let areas = self.areas(area);
+        let symbol = match self.direction {
+            Direction::Horizontal => symbols::line::VERTICAL,
+            Direction::Vertical => symbols::line::HORIZONTAL,
+        };
+        for (i, (pane, &pane_area)) in self.panes.iter().zip(&areas).enumerate() {
+            pane.child.view(
+                pane.child.layout(pane_area).intersection(pane_area),
+                buf,
+                state,
+            );
+            if i + 1 == areas.len() {
+                continue;
+            }
+            let divider = self.divider(pane_area, area);
+            for x in divider.left()..divider.right() {
+                for y in divider.top()..divider.bottom() {
+                    buf.get_mut(x, y)
+                        .set_symbol(symbol)
+                        .set_style(self.divider_style);
+                }
+            }
+        }
+    }
+
+    fn cursor(&self, _area: Rect, state: &S) -> Option<(u16, u16)> {
+        let pane = self.panes.get(self.focused)?;
+        let area = *self.areas(self.area.get()).get(self.focused)?;
+        pane.child
+            .cursor(pane.child.layout(area).intersection(area), state)
+    }
+
+    fn cursor_style(&self, state: &S) -> Option<CursorStyle> {
+        self.panes.get(self.focused)?.child.cursor_style(state)
+    }
+
+    fn handle_action(&mut self, action: &Action, cx: &mut Context<S, E>) -> bool {
+        let inner = self.panes.get(self.focused).map(|p| p.child.id());
+        let Some(pane) = self.panes.get_mut(self.focused) else {
+            return false;
+        };
+        let handled =
+            cx.with_inner_focus(self.id, inner, |cx| pane.child.handle_action(action, cx));
+        self.redirect_focus(cx);
+        handled
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        // Errors of children can only be reported through `try_handle_event`.
+        _ = self.try_handle_event(event, cx);
+    }
+
+    fn try_handle_event(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Event::Terminal(TerminalEvent::Mouse(_)) = event {
+            return self.handle_mouse(event, cx);
+        }
+
+        let order = (self.focused..self.panes.len()).chain(0..self.focused);
+        for index in order.collect::<Vec<_>>() {
+            self.forward(index, event, cx)?;
+            if event.is_consumed() {
+                return Ok(());
+            }
+        }
+        self.handle_key(event, cx);
+        Ok(())
+    }
+}
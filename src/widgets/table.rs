@@ -0,0 +1,428 @@
+use crate::{
+    Component, Context, Event, Id, KeyCode, KeyEventKind, KeyModifiers, MouseButton,
+    MouseEventKind, TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Widget},
+};
+use std::{cell::Cell, cmp::Ordering};
+use unicode_width::UnicodeWidthChar;
+
+/// Builds user event from the index of a row and its cells.
+type RowEvent<E> = Box<dyn Fn(usize, &[String]) -> E>;
+
+/// Column of a [`Table`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    pub title: String,
+    /// Width in cells, without the separator.
+    pub width: u16,
+}
+
+impl Column {
+    pub fn new(title: impl Into<String>, width: u16) -> Self {
+        Self {
+            title: title.into(),
+            width,
+        }
+    }
+}
+
+/// Table with a header, row selection, sorting, horizontal scrolling and columns resized with the mouse.
+///
+/// While focused, `Up`, `Down`, `PageUp`, `PageDown`, `Home` and `End` move the selection, `Enter` submits
+/// the selected row, `Left` and `Right` scroll horizontally and `1` to `9` sort by a column, pressing
+/// it again reverses the order. Clicking a header sorts by its column, dragging the separator after it
+/// resizes it. Cells that look like numbers are sorted as numbers.
+pub struct Table<E = ()> {
+    id: Id,
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    /// Indices of rows in the order they are shown.
+    order: Vec<usize>,
+    /// Column sorted by and whether the order is descending.
+    sort: Option<(usize, bool)>,
+    /// Position of the selected row in `order`.
+    selected: Option<usize>,
+    highlight: Style,
+    block: Option<Block<'static>>,
+    on_select: Option<RowEvent<E>>,
+    on_submit: Option<RowEvent<E>>,
+    scroll_x: u16,
+    /// Column whose separator is dragged.
+    resizing: Option<usize>,
+    /// First row shown, updated when drawing to keep the selection in view.
+    offset: Cell<usize>,
+    follow: Cell<bool>,
+    /// Area the header and rows were drawn in last.
+    viewport: Cell<Rect>,
+}
+
+/// Builder functions
+impl<E> Table<E> {
+    pub fn new(id: Id, columns: Vec<Column>, rows: Vec<Vec<String>>) -> Self {
+        let mut table = Self {
+            id,
+            columns,
+            rows: Vec::new(),
+            order: Vec::new(),
+            sort: None,
+            selected: None,
+            highlight: Style::new().add_modifier(Modifier::REVERSED),
+            block: None,
+            on_select: None,
+            on_submit: None,
+            scroll_x: 0,
+            resizing: None,
+            offset: Cell::new(0),
+            follow: Cell::new(true),
+            viewport: Cell::new(Rect::default()),
+        };
+        table.set_rows(rows);
+        table
+    }
+
+    /// Sets style applied over the selected row, reversed colors by default.
+    pub fn with_highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Draws the table inside `block`.
+    pub fn with_block(mut self, block: Block<'static>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Emits the user event returned by `f` with the index of the row in the rows passed in and its cells
+    /// when the selection changes, see [`Context::emit`].
+    pub fn with_select_event(mut self, f: impl Fn(usize, &[String]) -> E + 'static) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    /// Emits the user event returned by `f` when the selected row is submitted.
+    pub fn with_submit_event(mut self, f: impl Fn(usize, &[String]) -> E + 'static) -> Self {
+        self.on_submit = Some(Box::new(f));
+        self
+    }
+}
+
+/// Non-builder functions
+impl<E> Table<E> {
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Returns rows in the order they were passed in.
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    /// Replaces rows, keeping the sort order. The first row shown is selected.
+    pub fn set_rows(&mut self, rows: Vec<Vec<String>>) {
+        self.rows = rows;
+        self.order = (0..self.rows.len()).collect();
+        self.selected = None;
+        if let Some((column, descending)) = self.sort {
+            self.sort_by(column, descending);
+        }
+        self.selected = (!self.rows.is_empty()).then_some(0);
+    }
+
+    /// Sets width of the column at `index` in cells.
+    pub fn set_column_width(&mut self, index: usize, width: u16) {
+        if let Some(column) = self.columns.get_mut(index) {
+            column.width = width.max(1);
+        }
+    }
+
+    /// Returns the index of the selected row in [`Self::rows`].
+    pub fn selected(&self) -> Option<usize> {
+        Some(self.order[self.selected?])
+    }
+
+    pub fn selected_row(&self) -> Option<&[String]> {
+        Some(&self.rows[self.selected()?])
+    }
+
+    /// Returns the column rows are sorted by and whether the order is descending.
+    pub fn sort(&self) -> Option<(usize, bool)> {
+        self.sort
+    }
+
+    /// Sorts rows by cells in `column`, the selected row stays selected.
+    pub fn sort_by(&mut self, column: usize, descending: bool) {
+        if column >= self.columns.len() {
+            return;
+        }
+        let selected = self.selected();
+        let rows = &self.rows;
+        let cell = |row: usize| rows[row].get(column).map_or("", String::as_str);
+        self.order.sort_by(|a, b| {
+            let ordering = compare_cells(cell(*a), cell(*b));
+            match descending {
+                true => ordering.reverse(),
+                false => ordering,
+            }
+        });
+        self.sort = Some((column, descending));
+        self.selected = selected.and_then(|s| self.order.iter().position(|r| *r == s));
+        self.follow.set(true);
+    }
+
+    /// Returns the number of rows that fit below the header in the area drawn last.
+    fn page(&self) -> usize {
+        self.viewport.get().height.saturating_sub(1).max(1) as usize
+    }
+
+    /// Returns the horizontal position of the column at `index` before scrolling.
+    fn column_x(&self, index: usize) -> u16 {
+        self.columns[..index].iter().map(|c| c.width + 1).sum()
+    }
+
+    /// Returns the total width of columns with separators.
+    fn total_width(&self) -> u16 {
+        self.column_x(self.columns.len())
+    }
+
+    fn select_emit<S>(&mut self, position: usize, cx: &mut Context<S, E>)
+    where
+        S: 'static,
+        E: 'static,
+    {
+        let position = position.min(self.order.len().saturating_sub(1));
+        if self.order.is_empty() || self.selected == Some(position) {
+            return;
+        }
+        self.selected = Some(position);
+        self.follow.set(true);
+        if let Some(on_select) = &self.on_select {
+            let row = self.order[position];
+            cx.emit(on_select(row, &self.rows[row]));
+        }
+    }
+
+    fn submit<S>(&self, cx: &mut Context<S, E>)
+    where
+        S: 'static,
+        E: 'static,
+    {
+        if let (Some(row), Some(on_submit)) = (self.selected(), &self.on_submit) {
+            cx.emit(on_submit(row, &self.rows[row]));
+        }
+    }
+
+    /// Sorts by `column`, reversing the order if already sorted by it.
+    fn toggle_sort(&mut self, column: usize) {
+        let descending = self.sort == Some((column, false));
+        self.sort_by(column, descending);
+    }
+
+    /// Draws a row of cells at `y`, clipped to `area` and scrolled horizontally.
+    fn draw_row<'a>(
+        &self,
+        cells: impl Iterator<Item = &'a str>,
+        area: Rect,
+        y: u16,
+        buf: &mut Buffer,
+        style: Style,
+    ) {
+        let dim = style.add_modifier(Modifier::DIM);
+        for (i, (column, text)) in self.columns.iter().zip(cells).enumerate() {
+            let x = self.column_x(i) as i32 - self.scroll_x as i32;
+            draw_clipped(buf, area, x, y, text, column.width, style);
+            draw_clipped(buf, area, x + column.width as i32, y, "│", 1, dim);
+        }
+    }
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for Table<E> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        let area = match &self.block {
+            Some(block) => {
+                block.clone().render(area, buf);
+                block.inner(area)
+            }
+            None => area,
+        };
+        self.viewport.set(area);
+        if area.is_empty() {
+            return;
+        }
+
+        let titles = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| match self.sort {
+                Some((sorted, descending)) if sorted == i => {
+                    format!("{} {}", column.title, if descending { '▼' } else { '▲' })
+                }
+                _ => column.title.clone(),
+            })
+            .collect::<Vec<_>>();
+        let header = Style::new().add_modifier(Modifier::BOLD);
+        self.draw_row(titles.iter().map(String::as_str), area, area.y, buf, header);
+
+        let page = self.page();
+        let mut offset = self.offset.get().min(self.order.len().saturating_sub(1));
+        if let Some(selected) = self.selected.filter(|_| self.follow.get()) {
+            offset = offset.clamp((selected + 1).saturating_sub(page), selected);
+        }
+        self.offset.set(offset);
+
+        let rows = self.order.iter().enumerate().skip(offset);
+        for (y, (position, row)) in (area.y + 1..area.bottom()).zip(rows) {
+            let cells = self.rows[*row].iter().map(String::as_str);
+            self.draw_row(cells, area, y, buf, Style::new());
+            if self.selected == Some(position) {
+                buf.set_style(
+                    Rect {
+                        y,
+                        height: 1,
+                        ..area
+                    },
+                    self.highlight,
+                );
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        let viewport = self.viewport.get();
+        match event {
+            Event::Terminal(TerminalEvent::Key(key))
+                if cx.focused() == Some(self.id) && key.kind != KeyEventKind::Release =>
+            {
+                if key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                {
+                    return;
+                }
+                let selected = self.selected.unwrap_or(0);
+                let max_scroll = self.total_width().saturating_sub(viewport.width);
+                match key.code {
+                    KeyCode::Up => self.select_emit(selected.saturating_sub(1), cx),
+                    KeyCode::Down => self.select_emit(selected + 1, cx),
+                    KeyCode::PageUp => self.select_emit(selected.saturating_sub(self.page()), cx),
+                    KeyCode::PageDown => self.select_emit(selected + self.page(), cx),
+                    KeyCode::Home => self.select_emit(0, cx),
+                    KeyCode::End => self.select_emit(usize::MAX, cx),
+                    KeyCode::Enter => self.submit(cx),
+                    KeyCode::Left => self.scroll_x = self.scroll_x.saturating_sub(4),
+                    KeyCode::Right => self.scroll_x = (self.scroll_x + 4).min(max_scroll),
+                    KeyCode::Char(c @ '1'..='9') => {
+                        self.toggle_sort(c as usize - '1' as usize);
+                    }
+                    _ => return,
+                }
+            }
+            Event::Terminal(TerminalEvent::Mouse(mouse)) => {
+                let x = mouse.column.saturating_sub(viewport.x) + self.scroll_x;
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) if mouse.row == viewport.y => {
+                        cx.focus(self.id);
+                        let column = (0..self.columns.len())
+                            .find(|i| x < self.column_x(*i) + self.columns[*i].width + 1);
+                        let Some(column) = column else {
+                            return;
+                        };
+                        let separator = self.column_x(column) + self.columns[column].width;
+                        if x == separator {
+                            self.resizing = Some(column);
+                        } else {
+                            self.toggle_sort(column);
+                        }
+                    }
+                    MouseEventKind::Down(MouseButton::Left) if mouse.row > viewport.y => {
+                        cx.focus(self.id);
+                        let position = self.offset.get() + (mouse.row - viewport.y - 1) as usize;
+                        if position >= self.order.len() {
+                            return;
+                        }
+                        if self.selected == Some(position) {
+                            self.submit(cx);
+                        } else {
+                            self.select_emit(position, cx);
+                        }
+                    }
+                    MouseEventKind::Drag(MouseButton::Left) => {
+                        let Some(column) = self.resizing else {
+                            return;
+                        };
+                        let width = x.saturating_sub(self.column_x(column));
+                        self.set_column_width(column, width);
+                    }
+                    MouseEventKind::Up(MouseButton::Left) if self.resizing.is_some() => {
+                        self.resizing = None;
+                    }
+                    MouseEventKind::ScrollUp => {
+                        self.offset.set(self.offset.get().saturating_sub(3));
+                        self.follow.set(false);
+                    }
+                    MouseEventKind::ScrollDown => {
+                        let max = self.order.len().saturating_sub(self.page());
+                        self.offset.set((self.offset.get() + 3).min(max));
+                        self.follow.set(false);
+                    }
+                    MouseEventKind::ScrollLeft => self.scroll_x = self.scroll_x.saturating_sub(4),
+                    MouseEventKind::ScrollRight => {
+                        let max_scroll = self.total_width().saturating_sub(viewport.width);
+                        self.scroll_x = (self.scroll_x + 4).min(max_scroll);
+                    }
+                    _ => return,
+                }
+            }
+            _ => return,
+        }
+        _ = event.consume();
+    }
+}
+
+/// Compares cells as numbers if both are, as text otherwise.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Draws `text` cut to `width` cells at `x` relative to `area`, skipping the part left of `area`.
+fn draw_clipped(
+    buf: &mut Buffer,
+    area: Rect,
+    x: i32,
+    y: u16,
+    text: &str,
+    width: u16,
+    style: Style,
+) {
+    let mut offset = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0) as i32;
+        let at = x + offset;
+        if offset + w > width as i32 || at + w > area.width as i32 {
+            break;
+        }
+        offset += w;
+        if at < 0 || w == 0 {
+            continue;
+        }
+        buf.get_mut(area.x + at as u16, y)
+            .set_char(c)
+            .set_style(style);
+    }
+}
@@ -0,0 +1,390 @@
+use crate::{
+    Action, Component, Context, CursorStyle, Event, Id, KeyCode, KeyEventKind, KeyModifiers,
+    MouseButton, MouseEventKind, TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    symbols,
+};
+use std::{any::Any, cell::Cell};
+use unicode_width::UnicodeWidthStr;
+
+/// Builds user event from the index and title of a tab.
+type TabEvent<E> = Box<dyn Fn(usize, &str) -> E>;
+
+struct Tab<S, E> {
+    title: String,
+    children: Vec<Box<dyn Component<S, E>>>,
+    /// Child that sees itself as focused while the tabs are.
+    focused: Option<Id>,
+}
+
+impl<S: 'static, E: 'static> Tab<S, E> {
+    /// Returns the child focused last, or the topmost focusable one.
+    fn focused(&self) -> Option<Id> {
+        self.focused.or_else(|| {
+            let child = self.children.iter().rev().find(|c| c.focusable())?;
+            Some(child.id())
+        })
+    }
+}
+
+/// Tab bar over sets of child components, only children of the active tab are drawn and receive events.
+///
+/// Children of all tabs are mounted along with the tabs, so inactive tabs keep their state and jobs.
+/// Children of the active tab get [`Component::on_leave`] and [`Component::on_enter`] when it is switched
+/// and when the tabs themselves are covered or uncovered as a screen.
+/// While the tabs are focused, the child of the active tab clicked last or that requested focus
+/// sees itself as focused, the topmost focusable child by default.
+///
+/// While focused, `Alt` with a digit selects a tab, `Alt-Left`, `Alt-Right`, `Ctrl-PageUp` and
+/// `Ctrl-PageDown` select the previous or the next one if a child doesn't consume them.
+/// Clicking a title selects its tab.
+pub struct Tabs<S = (), E = ()> {
+    id: Id,
+    tabs: Vec<Tab<S, E>>,
+    active: usize,
+    style: Style,
+    highlight: Style,
+    on_select: Option<TabEvent<E>>,
+    /// Area of the tab bar drawn last.
+    bar: Cell<Rect>,
+    /// Area children were drawn in last.
+    content: Cell<Rect>,
+}
+
+/// Builder functions
+impl<S, E> Tabs<S, E> {
+    pub fn new(id: Id) -> Self {
+        Self {
+            id,
+            tabs: Vec::new(),
+            active: 0,
+            style: Style::new(),
+            highlight: Style::new().add_modifier(Modifier::REVERSED),
+            on_select: None,
+            bar: Cell::new(Rect::default()),
+            content: Cell::new(Rect::default()),
+        }
+    }
+
+    /// Adds tab titled `title` showing `child`, more children are added with [`Self::with_child`].
+    pub fn with_tab(mut self, title: impl Into<String>, child: impl Component<S, E>) -> Self {
+        self.tabs.push(Tab {
+            title: title.into(),
+            children: vec![Box::new(child)],
+            focused: None,
+        });
+        self
+    }
+
+    /// Adds `child` to the tab added last, it is drawn on top of the tab's other children.
+    ///
+    /// # Panics
+    /// Panics if no tab has been added.
+    pub fn with_child(mut self, child: impl Component<S, E>) -> Self {
+        let tab = self.tabs.last_mut().expect("no tab to add the child to");
+        tab.children.push(Box::new(child));
+        self
+    }
+
+    /// Makes tab at `index` active initially, the first one by default.
+    pub fn with_active(mut self, index: usize) -> Self {
+        self.active = index.min(self.tabs.len().saturating_sub(1));
+        self
+    }
+
+    /// Sets style of the tab bar.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets style of the active tab's title, reversed colors by default.
+    pub fn with_highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Emits the user event returned by `f` when another tab is selected, see [`Context::emit`].
+    pub fn with_select_event(mut self, f: impl Fn(usize, &str) -> E + 'static) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+}
+
+/// Non-builder functions
+impl<S: 'static, E: 'static> Tabs<S, E> {
+    /// Returns the index of the active tab.
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    pub fn titles(&self) -> impl Iterator<Item = &str> + '_ {
+        self.tabs.iter().map(|t| t.title.as_str())
+    }
+
+    /// Returns the child with `id` from any tab if it is a `C`.
+    pub fn child<C: Component<S, E>>(&self, id: Id) -> Option<&C> {
+        let child = self.children().find(|c| c.id() == id)?;
+        (&**child as &dyn Any).downcast_ref()
+    }
+
+    /// Returns the child with `id` from any tab if it is a `C`.
+    pub fn child_mut<C: Component<S, E>>(&mut self, id: Id) -> Option<&mut C> {
+        let child = self
+            .tabs
+            .iter_mut()
+            .flat_map(|t| &mut t.children)
+            .find(|c| c.id() == id)?;
+        (&mut **child as &mut dyn Any).downcast_mut()
+    }
+
+    /// Selects tab at `index`, emitting the select event if it changed.
+    pub fn select(&mut self, index: usize, cx: &mut Context<S, E>) {
+        if index >= self.tabs.len() || index == self.active {
+            return;
+        }
+        self.notify(cx, false);
+        self.active = index;
+        self.notify(cx, true);
+        if let Some(on_select) = &self.on_select {
+            cx.emit(on_select(index, &self.tabs[index].title));
+        }
+        cx.request_redraw();
+    }
+
+    fn children(&self) -> impl Iterator<Item = &Box<dyn Component<S, E>>> + '_ {
+        self.tabs.iter().flat_map(|t| &t.children)
+    }
+
+    /// Calls [`Component::on_enter`] or [`Component::on_leave`] of the active tab's children.
+    fn notify(&mut self, cx: &mut Context<S, E>, enter: bool) {
+        let Some(tab) = self.tabs.get_mut(self.active) else {
+            return;
+        };
+        for child in &mut tab.children {
+            if enter {
+                child.on_enter(cx);
+            } else {
+                child.on_leave(cx);
+            }
+        }
+    }
+
+    /// Returns columns the titles span in the bar, each is padded with a space on both sides.
+    fn title_ranges(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        let bar = self.bar.get();
+        let mut x = bar.x;
+        self.tabs.iter().map(move |tab| {
+            let start = x;
+            let end = start.saturating_add(tab.title.width() as u16 + 2);
+            x = end.saturating_add(1);
+            (start, end)
+        })
+    }
+
+    /// Remembers a child that requested focus in the active tab.
+    fn redirect_focus(&mut self, cx: &mut Context<S, E>) {
+        let tab = &mut self.tabs[self.active];
+        if let Some(id) = cx.redirect_focus(self.id, |id| tab.children.iter().any(|c| c.id() == id))
+        {
+            tab.focused = Some(id);
+        }
+    }
+
+    fn handle_key(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        let Event::Terminal(TerminalEvent::Key(key)) = event else {
+            return;
+        };
+        if cx.focused() != Some(self.id) || key.kind == KeyEventKind::Release {
+            return;
+        }
+        let last = self.tabs.len().saturating_sub(1);
+        let (alt, ctrl) = (
+            key.modifiers.contains(KeyModifiers::ALT),
+            key.modifiers.contains(KeyModifiers::CONTROL),
+        );
+        let index = match key.code {
+            KeyCode::Char(c @ '1'..='9') if alt => c as usize - '1' as usize,
+            KeyCode::Left if alt => self.active.checked_sub(1).unwrap_or(last),
+            KeyCode::PageUp if ctrl => self.active.checked_sub(1).unwrap_or(last),
+            KeyCode::Right if alt => (self.active + 1) % self.tabs.len().max(1),
+            KeyCode::PageDown if ctrl => (self.active + 1) % self.tabs.len().max(1),
+            _ => return,
+        };
+        self.select(index, cx);
+        _ = event.consume();
+    }
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for Tabs<S, E> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        for tab in &mut self.tabs {
+            for child in &mut tab.children {
+                child.on_mount(cx);
+            }
+        }
+    }
+
+    fn on_unmount(&mut self, cx: &mut Context<S, E>) {
+        for tab in &mut self.tabs {
+            for child in &mut tab.children {
+                child.on_unmount(cx);
+            }
+        }
+    }
+
+    fn on_enter(&mut self, cx: &mut Context<S, E>) {
+        self.notify(cx, true);
+    }
+
+    fn on_leave(&mut self, cx: &mut Context<S, E>) {
+        self.notify(cx, false);
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        let bar = Rect { height: 1, ..area }.intersection(area);
+        let content = Rect {
+            y: area.y + bar.height,
+            height: area.height - bar.height,
+            ..area
+        };
+        self.bar.set(bar);
+        self.content.set(content);
+
+        buf.set_style(bar, self.style);
+        for (i, (tab, (start, end))) in self.tabs.iter().zip(self.title_ranges()).enumerate() {
+            if start >= bar.right() {
+                break;
+            }
+            let style = if i == self.active {
+                self.style.patch(self.highlight)
+            } else {
+                self.style
+            };
+            let width = (end.min(bar.right()) - start) as usize;
+            buf.set_stringn(start, bar.y, format!(" {} ", tab.title), width, style);
+            if end < bar.right() && i + 1 < self.tabs.len() {
+                buf.set_string(end, bar.y, symbols::line::VERTICAL, self.style);
+            }
+        }
+
+        let Some(tab) = self.tabs.get(self.active) else {
+            return;
+        };
+        for child in &tab.children {
+            child.view(child.layout(content).intersection(content), buf, state);
+        }
+    }
+
+    fn cursor(&self, _area: Rect, state: &S) -> Option<(u16, u16)> {
+        let tab = self.tabs.get(self.active)?;
+        let child = tab
+            .children
+            .iter()
+            .find(|c| Some(c.id()) == tab.focused())?;
+        let content = self.content.get();
+        child.cursor(child.layout(content).intersection(content), state)
+    }
+
+    fn cursor_style(&self, state: &S) -> Option<CursorStyle> {
+        let tab = self.tabs.get(self.active)?;
+        let child = tab
+            .children
+            .iter()
+            .find(|c| Some(c.id()) == tab.focused())?;
+        child.cursor_style(state)
+    }
+
+    fn handle_action(&mut self, action: &Action, cx: &mut Context<S, E>) -> bool {
+        let Some(tab) = self.tabs.get_mut(self.active) else {
+            return false;
+        };
+        let focused = tab.focused();
+        let handled = tab.children.iter_mut().rev().any(|child| {
+            cx.with_inner_focus(self.id, focused, |cx| child.handle_action(action, cx))
+        });
+        self.redirect_focus(cx);
+        handled
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        // Errors of children can only be reported through `try_handle_event`.
+        _ = self.try_handle_event(event, cx);
+    }
+
+    fn try_handle_event(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mouse = match event {
+            Event::Terminal(TerminalEvent::Mouse(mouse)) => Some(*mouse),
+            _ => None,
+        };
+        let clicked = mouse.filter(|m| m.kind == MouseEventKind::Down(MouseButton::Left));
+        if let Some(mouse) = clicked {
+            cx.focus(self.id);
+            let bar = self.bar.get();
+            if bar.intersects(Rect::new(mouse.column, mouse.row, 1, 1)) {
+                let index = self
+                    .title_ranges()
+                    .position(|(start, end)| (start..end).contains(&mouse.column));
+                if let Some(index) = index {
+                    self.select(index, cx);
+                }
+                _ = event.consume();
+                return Ok(());
+            }
+        }
+
+        let content = self.content.get();
+        let Some(tab) = self.tabs.get_mut(self.active) else {
+            return Ok(());
+        };
+        let mut focused = tab.focused();
+        let mut result = Ok(());
+        for child in tab.children.iter_mut().rev() {
+            if let Some(mouse) = mouse {
+                let area = child.layout(content).intersection(content);
+                if !area.intersects(Rect::new(mouse.column, mouse.row, 1, 1)) {
+                    continue;
+                }
+                if clicked.is_some() && child.focusable() {
+                    focused = Some(child.id());
+                    tab.focused = focused;
+                }
+            }
+            result = cx.with_inner_focus(self.id, focused, |cx| child.try_handle_event(event, cx));
+            if result.is_err() || event.is_consumed() {
+                break;
+            }
+        }
+        self.redirect_focus(cx);
+        result?;
+
+        if !event.is_consumed() {
+            self.handle_key(event, cx);
+        }
+        Ok(())
+    }
+}
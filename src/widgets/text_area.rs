@@ -0,0 +1,700 @@
+use crate::{
+    Component, Context, Event, Id, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton,
+    MouseEventKind, TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Widget},
+};
+use std::cell::Cell;
+use unicode_width::UnicodeWidthChar;
+
+/// Position in the text, `col` counts characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Pos {
+    row: usize,
+    col: usize,
+}
+
+/// Part of a line drawn on one row of the screen, `start` and `end` count characters.
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    line: usize,
+    start: usize,
+    end: usize,
+}
+
+/// Builds user event emitted after an edit, see [`TextArea::with_change_event`].
+type ChangeEvent<E> = Box<dyn Fn(&str) -> E>;
+
+/// Multi-line text editor with word wrap, scrolling, line numbers and selection.
+///
+/// While focused, it edits text with the usual keys: arrows move the cursor, with `Shift` they select,
+/// with `Ctrl` left and right jump over words. `Ctrl+A` selects everything, `Ctrl+C`, `Ctrl+X` and `Ctrl+V`
+/// use the [`Clipboard`](crate::Clipboard), pasted text is inserted. Clicking places the cursor, dragging selects.
+/// `Tab` and `Esc` are left to other components.
+pub struct TextArea<E = ()> {
+    id: Id,
+    lines: Vec<String>,
+    cursor: Pos,
+    /// Other end of the selection.
+    anchor: Option<Pos>,
+    /// Column the cursor returns to when moving vertically over shorter rows.
+    goal: Option<usize>,
+    wrap: bool,
+    line_numbers: bool,
+    block: Option<Block<'static>>,
+    on_change: Option<ChangeEvent<E>>,
+    /// First row shown, updated when drawing to keep the cursor in view.
+    scroll: Cell<usize>,
+    follow: Cell<bool>,
+    /// Area the text was drawn in last, used to move by rows and map clicks.
+    text_area: Cell<Rect>,
+}
+
+/// Builder functions
+impl<E> TextArea<E> {
+    pub fn new(id: Id) -> Self {
+        Self {
+            id,
+            lines: vec![String::new()],
+            cursor: Pos { row: 0, col: 0 },
+            anchor: None,
+            goal: None,
+            wrap: true,
+            line_numbers: false,
+            block: None,
+            on_change: None,
+            scroll: Cell::new(0),
+            follow: Cell::new(true),
+            text_area: Cell::new(Rect::default()),
+        }
+    }
+
+    /// Sets the initial text, the cursor is placed at its end.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    /// Wraps long lines at word boundaries, enabled by default.
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Shows line numbers in front of lines.
+    pub fn with_line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Draws the text inside `block`.
+    pub fn with_block(mut self, block: Block<'static>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Emits the user event returned by `f` with the text after every edit, see [`Context::emit`].
+    pub fn with_change_event(mut self, f: impl Fn(&str) -> E + 'static) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+}
+
+/// Non-builder functions
+impl<E> TextArea<E> {
+    /// Returns the text with lines joined by `\n`.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Returns lines of the text.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Replaces the text, the cursor is placed at its end and the selection is cleared.
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = split_lines(text);
+        let row = self.lines.len() - 1;
+        self.cursor = Pos {
+            row,
+            col: char_len(&self.lines[row]),
+        };
+        self.anchor = None;
+        self.goal = None;
+        self.follow.set(true);
+    }
+
+    /// Returns the line and the column of the cursor, both counted from zero.
+    pub fn cursor_position(&self) -> (usize, usize) {
+        (self.cursor.row, self.cursor.col)
+    }
+
+    /// Returns the selected text.
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        let mut text = String::new();
+        for row in start.row..=end.row {
+            let line = &self.lines[row];
+            let from = if row == start.row { start.col } else { 0 };
+            let to = if row == end.row {
+                end.col
+            } else {
+                char_len(line)
+            };
+            text.push_str(&line[byte_index(line, from)..byte_index(line, to)]);
+            if row != end.row {
+                text.push('\n');
+            }
+        }
+        Some(text)
+    }
+
+    /// Inserts `text` at the cursor, replacing the selection.
+    pub fn insert_str(&mut self, text: &str) {
+        self.delete_selection();
+        let line = &mut self.lines[self.cursor.row];
+        let at = byte_index(line, self.cursor.col);
+        let rest = line.split_off(at);
+
+        let mut inserted = split_lines(text);
+        let last = inserted.len() - 1;
+        let col = char_len(&inserted[last]);
+        inserted[last].push_str(&rest);
+        line.push_str(&inserted.remove(0));
+
+        let row = self.cursor.row;
+        self.cursor = match inserted.is_empty() {
+            true => Pos {
+                row,
+                col: self.cursor.col + col,
+            },
+            false => Pos {
+                row: row + inserted.len(),
+                col,
+            },
+        };
+        self.lines.splice(row + 1..row + 1, inserted);
+    }
+
+    fn selection(&self) -> Option<(Pos, Pos)> {
+        let anchor = self.anchor.filter(|a| *a != self.cursor)?;
+        Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    /// Removes selected text, returns `false` if nothing was selected.
+    fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        self.anchor = None;
+        let Some((start, end)) = selection else {
+            return false;
+        };
+
+        let tail = {
+            let line = &self.lines[end.row];
+            line[byte_index(line, end.col)..].to_owned()
+        };
+        let line = &mut self.lines[start.row];
+        line.truncate(byte_index(line, start.col));
+        line.push_str(&tail);
+        self.lines.drain(start.row + 1..=end.row);
+        self.cursor = start;
+        true
+    }
+
+    fn backspace(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+        let Pos { row, col } = self.cursor;
+        if col > 0 {
+            let line = &mut self.lines[row];
+            line.remove(byte_index(line, col - 1));
+            self.cursor.col -= 1;
+        } else if row > 0 {
+            let line = self.lines.remove(row);
+            let previous = &mut self.lines[row - 1];
+            self.cursor = Pos {
+                row: row - 1,
+                col: char_len(previous),
+            };
+            previous.push_str(&line);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+        let Pos { row, col } = self.cursor;
+        if col < char_len(&self.lines[row]) {
+            let line = &mut self.lines[row];
+            line.remove(byte_index(line, col));
+        } else if row + 1 < self.lines.len() {
+            let next = self.lines.remove(row + 1);
+            self.lines[row].push_str(&next);
+        }
+    }
+
+    /// Moves the cursor to `pos`, extending the selection if `select` is `true`.
+    fn move_to(&mut self, pos: Pos, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = pos;
+    }
+
+    fn left(&self, word: bool) -> Pos {
+        let Pos { row, col } = self.cursor;
+        if col == 0 {
+            return match row {
+                0 => self.cursor,
+                row => Pos {
+                    row: row - 1,
+                    col: char_len(&self.lines[row - 1]),
+                },
+            };
+        }
+        if !word {
+            return Pos { row, col: col - 1 };
+        }
+
+        let chars = self.lines[row].chars().take(col).collect::<Vec<_>>();
+        let spaces = chars.iter().rev().take_while(|c| c.is_whitespace()).count();
+        let word = chars[..col - spaces]
+            .iter()
+            .rev()
+            .take_while(|c| !c.is_whitespace())
+            .count();
+        Pos {
+            row,
+            col: col - spaces - word,
+        }
+    }
+
+    fn right(&self, word: bool) -> Pos {
+        let Pos { row, col } = self.cursor;
+        let len = char_len(&self.lines[row]);
+        if col == len {
+            return match row + 1 < self.lines.len() {
+                true => Pos {
+                    row: row + 1,
+                    col: 0,
+                },
+                false => self.cursor,
+            };
+        }
+        if !word {
+            return Pos { row, col: col + 1 };
+        }
+
+        let mut chars = self.lines[row].chars().skip(col).peekable();
+        let mut skipped = 0;
+        while chars.next_if(|c| !c.is_whitespace()).is_some() {
+            skipped += 1;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {
+            skipped += 1;
+        }
+        Pos {
+            row,
+            col: col + skipped,
+        }
+    }
+
+    /// Returns position `rows` screen rows above or below the cursor.
+    fn vertical(&mut self, rows: isize) -> Pos {
+        let layout = self.layout(self.text_area.get().width);
+        let current = row_of(&layout, self.cursor);
+        let goal = *self
+            .goal
+            .get_or_insert(self.cursor.col - layout[current].start);
+
+        let target = current.saturating_add_signed(rows).min(layout.len() - 1);
+        if target == current {
+            return match rows < 0 {
+                true => Pos {
+                    row: self.cursor.row,
+                    col: layout[current].start,
+                },
+                false => Pos {
+                    row: self.cursor.row,
+                    col: layout[current].end,
+                },
+            };
+        }
+        position_in_row(&layout, target, goal)
+    }
+
+    /// Splits lines into rows of the screen `width` cells wide.
+    fn layout(&self, width: u16) -> Vec<Row> {
+        let width = width.max(1) as usize;
+        self.lines
+            .iter()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                let rows = match self.wrap {
+                    true => wrap(line, width),
+                    false => vec![(0, char_len(line))],
+                };
+                rows.into_iter().map(move |(start, end)| Row {
+                    line: i,
+                    start,
+                    end,
+                })
+            })
+            .collect()
+    }
+
+    /// Splits `area` into the gutter with line numbers and the text.
+    fn split(&self, area: Rect) -> (Rect, Rect) {
+        let area = self.block.as_ref().map_or(area, |b| b.inner(area));
+        let gutter = match self.line_numbers {
+            true => (self.lines.len().ilog10() as u16 + 2).min(area.width),
+            false => 0,
+        };
+        let (gutter, text) = (
+            Rect {
+                width: gutter,
+                ..area
+            },
+            Rect {
+                x: area.x + gutter,
+                width: area.width - gutter,
+                ..area
+            },
+        );
+        (gutter, text)
+    }
+
+    /// Returns position of the character drawn at `(x, y)`.
+    fn position_at(&self, x: u16, y: u16) -> Pos {
+        let area = self.text_area.get();
+        let layout = self.layout(area.width);
+        let row = (self.scroll.get() + y.saturating_sub(area.y) as usize).min(layout.len() - 1);
+        let Row { line, start, end } = layout[row];
+
+        let target = x.saturating_sub(area.x) as usize;
+        let mut width = 0;
+        let mut col = start;
+        for c in self.lines[line].chars().skip(start).take(end - start) {
+            width += c.width().unwrap_or(0);
+            if width > target {
+                break;
+            }
+            col += 1;
+        }
+        Pos { row: line, col }
+    }
+
+    fn changed<S>(&self, cx: &mut Context<S, E>)
+    where
+        S: 'static,
+        E: 'static,
+    {
+        if let Some(on_change) = &self.on_change {
+            cx.emit(on_change(&self.text()));
+        }
+    }
+
+    /// Handles a key, returns `None` if it isn't used and whether the text was edited otherwise.
+    fn key<S>(&mut self, key: KeyEvent, cx: &mut Context<S, E>) -> Option<bool>
+    where
+        S: 'static,
+        E: 'static,
+    {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        let page = self.text_area.get().height.max(1) as isize;
+
+        let vertical = matches!(
+            key.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown
+        );
+        if !vertical {
+            self.goal = None;
+        }
+        self.follow.set(true);
+
+        let pos = match key.code {
+            KeyCode::Char('a') if ctrl => {
+                self.anchor = Some(Pos { row: 0, col: 0 });
+                let row = self.lines.len() - 1;
+                self.cursor = Pos {
+                    row,
+                    col: char_len(&self.lines[row]),
+                };
+                return Some(false);
+            }
+            KeyCode::Char('c') if ctrl => {
+                cx.clipboard().set(&self.selected_text()?);
+                return Some(false);
+            }
+            KeyCode::Char('x') if ctrl => {
+                cx.clipboard().set(&self.selected_text()?);
+                return Some(self.delete_selection());
+            }
+            // Contents arrive as `TerminalEvent::Clipboard`.
+            KeyCode::Char('v') if ctrl => {
+                cx.clipboard().request();
+                return Some(false);
+            }
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+                self.insert_str(c.encode_utf8(&mut [0; 4]));
+                return Some(true);
+            }
+            KeyCode::Enter => {
+                self.insert_str("\n");
+                return Some(true);
+            }
+            KeyCode::Backspace => {
+                self.backspace();
+                return Some(true);
+            }
+            KeyCode::Delete => {
+                self.delete();
+                return Some(true);
+            }
+            KeyCode::Left => self.left(ctrl),
+            KeyCode::Right => self.right(ctrl),
+            KeyCode::Up => self.vertical(-1),
+            KeyCode::Down => self.vertical(1),
+            KeyCode::PageUp => self.vertical(-page),
+            KeyCode::PageDown => self.vertical(page),
+            KeyCode::Home if ctrl => Pos { row: 0, col: 0 },
+            KeyCode::End if ctrl => {
+                let row = self.lines.len() - 1;
+                Pos {
+                    row,
+                    col: char_len(&self.lines[row]),
+                }
+            }
+            KeyCode::Home => Pos {
+                row: self.cursor.row,
+                col: 0,
+            },
+            KeyCode::End => Pos {
+                row: self.cursor.row,
+                col: char_len(&self.lines[self.cursor.row]),
+            },
+            _ => return None,
+        };
+        self.move_to(pos, shift);
+        Some(false)
+    }
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for TextArea<E> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, _state: &S) {
+        if let Some(block) = &self.block {
+            block.clone().render(area, buf);
+        }
+        let (gutter, text) = self.split(area);
+        self.text_area.set(text);
+        if text.is_empty() {
+            return;
+        }
+
+        let layout = self.layout(text.width);
+        let height = text.height as usize;
+        let mut scroll = self.scroll.get().min(layout.len() - 1);
+        if self.follow.get() {
+            let row = row_of(&layout, self.cursor);
+            scroll = scroll.clamp((row + 1).saturating_sub(height), row);
+        }
+        self.scroll.set(scroll);
+
+        let dim = Style::new().add_modifier(Modifier::DIM);
+        let selected = Style::new().add_modifier(Modifier::REVERSED);
+        let selection = self.selection();
+        for (y, row) in (text.y..text.bottom()).zip(layout.iter().skip(scroll)) {
+            let first = row.start == 0;
+            if self.line_numbers && first && gutter.width > 0 {
+                let number = format!("{:>1$} ", row.line + 1, gutter.width as usize - 1);
+                buf.set_stringn(gutter.x, y, number, gutter.width as usize, dim);
+            }
+
+            let line = &self.lines[row.line];
+            let (from, to) = match selection {
+                Some((start, end)) if start.row <= row.line && row.line <= end.row => {
+                    let from = if start.row == row.line { start.col } else { 0 };
+                    let to = if end.row == row.line {
+                        end.col
+                    } else {
+                        usize::MAX
+                    };
+                    (from.clamp(row.start, row.end), to.clamp(row.start, row.end))
+                }
+                _ => (row.end, row.end),
+            };
+            let slice = |a: usize, b: usize| &line[byte_index(line, a)..byte_index(line, b)];
+            let spans = Line::from(vec![
+                Span::raw(slice(row.start, from)),
+                Span::styled(slice(from, to), selected),
+                Span::raw(slice(to, row.end)),
+            ]);
+            buf.set_line(text.x, y, &spans, text.width);
+        }
+    }
+
+    fn cursor(&self, area: Rect, _state: &S) -> Option<(u16, u16)> {
+        let (_, text) = self.split(area);
+        let layout = self.layout(text.width);
+        let row = row_of(&layout, self.cursor);
+        let y = row.checked_sub(self.scroll.get())?;
+        if y >= text.height as usize {
+            return None;
+        }
+
+        let Row { line, start, .. } = layout[row];
+        let x = self.lines[line]
+            .chars()
+            .skip(start)
+            .take(self.cursor.col - start)
+            .map(|c| c.width().unwrap_or(0))
+            .sum::<usize>();
+        let x = (x as u16).min(text.width.saturating_sub(1));
+        Some((text.x + x, text.y + y as u16))
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        let focused = cx.focused() == Some(self.id);
+        let edited = match event {
+            Event::Terminal(TerminalEvent::Key(key))
+                if focused && key.kind != KeyEventKind::Release =>
+            {
+                match self.key(*key, cx) {
+                    Some(edited) => edited,
+                    None => return,
+                }
+            }
+            Event::Terminal(TerminalEvent::Paste(text) | TerminalEvent::Clipboard(text))
+                if focused =>
+            {
+                self.follow.set(true);
+                let text = text.clone();
+                self.insert_str(&text);
+                true
+            }
+            Event::Terminal(TerminalEvent::Mouse(mouse)) => {
+                let (column, row) = (mouse.column, mouse.row);
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        cx.focus(self.id);
+                        self.cursor = self.position_at(column, row);
+                        self.anchor = Some(self.cursor);
+                        self.goal = None;
+                    }
+                    MouseEventKind::Drag(MouseButton::Left) => {
+                        let pos = self.position_at(column, row);
+                        self.move_to(pos, true);
+                        self.follow.set(true);
+                    }
+                    MouseEventKind::ScrollUp => {
+                        self.scroll.set(self.scroll.get().saturating_sub(3));
+                        self.follow.set(false);
+                    }
+                    MouseEventKind::ScrollDown => {
+                        self.scroll.set(self.scroll.get() + 3);
+                        self.follow.set(false);
+                    }
+                    _ => return,
+                }
+                false
+            }
+            _ => return,
+        };
+
+        _ = event.consume();
+        if edited {
+            self.changed(cx);
+        }
+    }
+}
+
+fn split_lines(text: &str) -> Vec<String> {
+    text.split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line).to_owned())
+        .collect()
+}
+
+fn char_len(line: &str) -> usize {
+    line.chars().count()
+}
+
+/// Returns byte index of the character at `col`, or the length of `line` if it is past the end.
+fn byte_index(line: &str, col: usize) -> usize {
+    line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+}
+
+/// Splits `line` into rows at most `width` cells wide, breaking after whitespace where possible.
+fn wrap(line: &str, width: usize) -> Vec<(usize, usize)> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut rows = Vec::new();
+    let (mut start, mut used, mut space) = (0, 0, None);
+    let mut i = 0;
+    while i < chars.len() {
+        let w = chars[i].width().unwrap_or(0);
+        if used + w > width && i > start {
+            let end = space.filter(|s| *s > start).unwrap_or(i);
+            rows.push((start, end));
+            start = end;
+            used = chars[start..i].iter().map(|c| c.width().unwrap_or(0)).sum();
+            space = None;
+            continue;
+        }
+
+        used += w;
+        i += 1;
+        if chars[i - 1].is_whitespace() {
+            space = Some(i);
+        }
+    }
+    rows.push((start, chars.len()));
+    rows
+}
+
+/// Returns the index of the row the cursor at `pos` is drawn on.
+fn row_of(layout: &[Row], pos: Pos) -> usize {
+    let first = layout.partition_point(|r| r.line < pos.row);
+    let rows = layout[first..]
+        .iter()
+        .take_while(|r| r.line == pos.row)
+        .count();
+    // End of a wrapped row is the start of the next one.
+    let offset = layout[first..first + rows]
+        .iter()
+        .rposition(|r| r.start <= pos.col)
+        .unwrap_or(0);
+    first + offset
+}
+
+/// Returns position `goal` characters into the row at `index`, within the row.
+fn position_in_row(layout: &[Row], index: usize, goal: usize) -> Pos {
+    let Row { line, start, end } = layout[index];
+    let last = layout.get(index + 1).is_none_or(|r| r.line != line);
+    // Cursor at the end of a wrapped row would be drawn on the next one.
+    let end = match last || end == start {
+        true => end,
+        false => end - 1,
+    };
+    Pos {
+        row: line,
+        col: (start + goal).min(end),
+    }
+}
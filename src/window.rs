@@ -0,0 +1,186 @@
+use crate::{NotificationProtocol, TerminalCaps};
+
+/// Progress of a long running task shown in the terminal tab or taskbar with OSC 9;4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Progress {
+    /// Removes the progress indicator.
+    None,
+    /// Task is running, percentage from 0 to 100.
+    Normal(u8),
+    /// Task has failed, percentage from 0 to 100.
+    Error(u8),
+    /// Task is running but its progress is unknown.
+    Indeterminate,
+    /// Task is paused, percentage from 0 to 100.
+    Paused(u8),
+}
+
+impl Progress {
+    pub(crate) fn sequence(self) -> String {
+        let (state, percent) = match self {
+            Self::None => (0, 0),
+            Self::Normal(p) => (1, p),
+            Self::Error(p) => (2, p),
+            Self::Indeterminate => (3, 0),
+            Self::Paused(p) => (4, p),
+        };
+        format!("\x1b]9;4;{state};{}\x07", percent.min(100))
+    }
+}
+
+/// Shape of the mouse pointer set with OSC 22, named after CSS cursors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PointerShape {
+    #[default]
+    Default,
+    /// Text beam, e.g. over inputs.
+    Text,
+    /// Hand, e.g. over links and buttons.
+    Pointer,
+    Crosshair,
+    Move,
+    /// Horizontal resize arrows, e.g. over vertical split dividers.
+    EwResize,
+    /// Vertical resize arrows, e.g. over horizontal split dividers.
+    NsResize,
+    NotAllowed,
+    Wait,
+    Help,
+    Grab,
+    Grabbing,
+}
+
+impl PointerShape {
+    /// Returns CSS name of the shape.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Text => "text",
+            Self::Pointer => "pointer",
+            Self::Crosshair => "crosshair",
+            Self::Move => "move",
+            Self::EwResize => "ew-resize",
+            Self::NsResize => "ns-resize",
+            Self::NotAllowed => "not-allowed",
+            Self::Wait => "wait",
+            Self::Help => "help",
+            Self::Grab => "grab",
+            Self::Grabbing => "grabbing",
+        }
+    }
+
+    pub(crate) fn sequence(self) -> String {
+        format!("\x1b]22;{}\x07", self.name())
+    }
+}
+
+/// Shape and blinking of the text cursor set with DECSCUSR.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CursorStyle {
+    /// Style configured by the user in the terminal.
+    #[default]
+    Default,
+    BlinkingBlock,
+    SteadyBlock,
+    BlinkingUnderline,
+    SteadyUnderline,
+    BlinkingBar,
+    SteadyBar,
+}
+
+/// Shape of the text cursor, see [`CursorStyle::new`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CursorShape {
+    Block,
+    Underline,
+    Bar,
+}
+
+impl CursorStyle {
+    /// Creates style with `shape` that blinks if `blinking` is `true`.
+    pub fn new(shape: CursorShape, blinking: bool) -> Self {
+        match (shape, blinking) {
+            (CursorShape::Block, true) => Self::BlinkingBlock,
+            (CursorShape::Block, false) => Self::SteadyBlock,
+            (CursorShape::Underline, true) => Self::BlinkingUnderline,
+            (CursorShape::Underline, false) => Self::SteadyUnderline,
+            (CursorShape::Bar, true) => Self::BlinkingBar,
+            (CursorShape::Bar, false) => Self::SteadyBar,
+        }
+    }
+
+    /// Returns the shape of the cursor, `None` for the user's style.
+    pub fn shape(self) -> Option<CursorShape> {
+        match self {
+            Self::Default => None,
+            Self::BlinkingBlock | Self::SteadyBlock => Some(CursorShape::Block),
+            Self::BlinkingUnderline | Self::SteadyUnderline => Some(CursorShape::Underline),
+            Self::BlinkingBar | Self::SteadyBar => Some(CursorShape::Bar),
+        }
+    }
+
+    /// Returns `true` if the cursor blinks, `false` for the user's style.
+    pub fn is_blinking(self) -> bool {
+        matches!(
+            self,
+            Self::BlinkingBlock | Self::BlinkingUnderline | Self::BlinkingBar
+        )
+    }
+
+    pub(crate) fn sequence(self) -> String {
+        let n = match self {
+            Self::Default => 0,
+            Self::BlinkingBlock => 1,
+            Self::SteadyBlock => 2,
+            Self::BlinkingUnderline => 3,
+            Self::SteadyUnderline => 4,
+            Self::BlinkingBar => 5,
+            Self::SteadyBar => 6,
+        };
+        format!("\x1b[{n} q")
+    }
+}
+
+/// Sets the title of the terminal window or tab.
+pub(crate) fn title_sequence(title: &str) -> String {
+    format!("\x1b]2;{}\x07", strip_controls(title))
+}
+
+/// Shows desktop notification, `None` if the terminal doesn't support notifications.
+pub(crate) fn notification_sequence(
+    caps: &TerminalCaps,
+    title: &str,
+    body: &str,
+) -> Option<String> {
+    let seq = match caps.notifications? {
+        NotificationProtocol::Osc9 if title.is_empty() => {
+            format!("\x1b]9;{}\x07", strip_controls(body))
+        }
+        NotificationProtocol::Osc9 => {
+            format!(
+                "\x1b]9;{}: {}\x07",
+                strip_controls(title),
+                strip_controls(body)
+            )
+        }
+        NotificationProtocol::Osc777 => format!(
+            "\x1b]777;notify;{};{}\x07",
+            // Title is delimited by `;`.
+            strip_controls(title).replace(';', ","),
+            strip_controls(body)
+        ),
+    };
+    Some(caps.wrap_passthrough(&seq))
+}
+
+/// Control characters would terminate the sequence early.
+fn strip_controls(text: &str) -> String {
+    text.replace(|c: char| c.is_control(), "")
+}
+
+/// Saves the current window title on the terminal's title stack.
+pub(crate) const PUSH_TITLE: &str = "\x1b[22;0t";
+/// Restores the window title saved with [`PUSH_TITLE`].
+pub(crate) const POP_TITLE: &str = "\x1b[23;0t";
+/// Restores the cursor style configured by the user.
+pub(crate) const RESET_CURSOR_STYLE: &str = "\x1b[0 q";
@@ -0,0 +1,448 @@
+use crate::{
+    Action, Component, Context, CursorStyle, Event, Id, MouseButton, MouseEventKind, PointerShape,
+    TerminalEvent,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Widget},
+};
+use std::{any::Any, cell::Cell};
+
+/// Component wrapped in a bordered frame with a title bar, shown by a [`WindowManager`].
+pub struct Window<S = (), E = ()> {
+    id: Id,
+    child: Box<dyn Component<S, E>>,
+    title: String,
+    area: Rect,
+    min_size: (u16, u16),
+    resizable: bool,
+}
+
+/// Builder functions
+impl<S, E> Window<S, E> {
+    /// Creates window showing `child` in `area` of the screen, borders included.
+    pub fn new(id: Id, child: impl Component<S, E>, area: Rect) -> Self {
+        Self {
+            id,
+            child: Box::new(child),
+            title: String::new(),
+            area,
+            min_size: (10, 3),
+            resizable: true,
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets minimum size of the window when resizing, borders included, 10 by 3 by default.
+    pub fn with_min_size(mut self, width: u16, height: u16) -> Self {
+        self.min_size = (width.max(2), height.max(2));
+        self
+    }
+
+    /// Makes the window resizable by dragging its right and bottom borders, enabled by default.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+}
+
+/// Non-builder functions
+impl<S, E> Window<S, E> {
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    /// Returns area of the window on the screen, borders included.
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+    }
+
+    /// Returns area the child is drawn in.
+    fn inner(&self) -> Rect {
+        Block::new().borders(Borders::ALL).inner(self.area)
+    }
+}
+
+impl<S: 'static, E: 'static> Window<S, E> {
+    /// Returns the child if it is a `C`.
+    pub fn child<C: Component<S, E>>(&self) -> Option<&C> {
+        (&*self.child as &dyn Any).downcast_ref()
+    }
+
+    /// Returns the child if it is a `C`.
+    pub fn child_mut<C: Component<S, E>>(&mut self) -> Option<&mut C> {
+        (&mut *self.child as &mut dyn Any).downcast_mut()
+    }
+}
+
+/// What a mouse drag does to the window it started on.
+#[derive(Debug, Clone, Copy)]
+enum Grab {
+    /// Moves the window, keeping the grabbed cell under the cursor.
+    Move(u16, u16),
+    Resize {
+        right: bool,
+        bottom: bool,
+    },
+}
+
+/// Overlapping [`Window`]s that can be moved by dragging their title bars and resized by dragging
+/// their right and bottom borders, usually inserted on [`LayerId::POPUP`](crate::LayerId::POPUP).
+///
+/// Clicking a window raises it above the others and focuses it. The child of the top window sees
+/// itself as focused while the manager is focused. Mouse events outside of windows pass to
+/// layers below. Windows can be opened by sending them to the manager with [`Context::send_to`].
+pub struct WindowManager<S = (), E = ()> {
+    id: Id,
+    /// Windows from the bottom to the top.
+    windows: Vec<Window<S, E>>,
+    border_style: Style,
+    focused_border_style: Style,
+    mounted: bool,
+    grab: Option<(Id, Grab)>,
+    /// Area the manager was drawn in last.
+    area: Cell<Rect>,
+}
+
+/// Builder functions
+impl<S, E> WindowManager<S, E> {
+    pub fn new(id: Id) -> Self {
+        Self {
+            id,
+            windows: Vec::new(),
+            border_style: Style::new(),
+            focused_border_style: Style::new().add_modifier(Modifier::BOLD),
+            mounted: false,
+            grab: None,
+            area: Cell::new(Rect::default()),
+        }
+    }
+
+    /// Adds `window` above the others.
+    pub fn with_window(mut self, window: Window<S, E>) -> Self {
+        self.windows.push(window);
+        self
+    }
+
+    pub fn with_border_style(mut self, style: Style) -> Self {
+        self.border_style = style;
+        self
+    }
+
+    /// Sets style of borders of the top window, bold by default.
+    pub fn with_focused_border_style(mut self, style: Style) -> Self {
+        self.focused_border_style = style;
+        self
+    }
+}
+
+/// Non-builder functions
+impl<S, E> WindowManager<S, E> {
+    /// Returns windows from the bottom to the top.
+    pub fn windows(&self) -> impl Iterator<Item = &Window<S, E>> + '_ {
+        self.windows.iter()
+    }
+
+    pub fn window(&self, id: Id) -> Option<&Window<S, E>> {
+        self.windows.iter().find(|w| w.id == id)
+    }
+
+    pub fn window_mut(&mut self, id: Id) -> Option<&mut Window<S, E>> {
+        self.windows.iter_mut().find(|w| w.id == id)
+    }
+
+    /// Returns the id of the top window.
+    pub fn top(&self) -> Option<Id> {
+        self.windows.last().map(|w| w.id)
+    }
+
+    /// Moves window with `id` above the others, returns `false` if there is no such window.
+    pub fn raise(&mut self, id: Id) -> bool {
+        let Some(index) = self.windows.iter().position(|w| w.id == id) else {
+            return false;
+        };
+        let window = self.windows.remove(index);
+        self.windows.push(window);
+        true
+    }
+}
+
+impl<S: 'static, E: 'static> WindowManager<S, E> {
+    /// Shows `window` above the others and focuses the manager.
+    /// Replaces the window with the same id if there is one.
+    pub fn open(&mut self, mut window: Window<S, E>, cx: &mut Context<S, E>) {
+        self.close(window.id, cx);
+        if self.mounted {
+            window.child.on_mount(cx);
+        }
+        self.windows.push(window);
+        cx.focus(self.id);
+        cx.request_redraw();
+    }
+
+    /// Removes window with `id`, returns `false` if there is no such window.
+    pub fn close(&mut self, id: Id, cx: &mut Context<S, E>) -> bool {
+        let Some(index) = self.windows.iter().position(|w| w.id == id) else {
+            return false;
+        };
+        let mut window = self.windows.remove(index);
+        if self.mounted {
+            window.child.on_unmount(cx);
+        }
+        if self.grab.is_some_and(|(grabbed, _)| grabbed == id) {
+            self.grab = None;
+        }
+        cx.request_redraw();
+        true
+    }
+
+    /// Returns what dragging from `(x, y)` on `window` does, `None` if it only raises the window.
+    fn grab_at(window: &Window<S, E>, x: u16, y: u16) -> Option<Grab> {
+        let area = window.area;
+        let (right, bottom) = (
+            window.resizable && x + 1 == area.right(),
+            window.resizable && y + 1 == area.bottom(),
+        );
+        if y == area.y && !right {
+            Some(Grab::Move(x - area.x, y - area.y))
+        } else if right || bottom {
+            Some(Grab::Resize { right, bottom })
+        } else {
+            None
+        }
+    }
+
+    /// Applies `grab` of window at `index` dragged to `(x, y)`, windows are kept within the screen.
+    fn drag(&mut self, index: usize, grab: Grab, x: u16, y: u16) {
+        let screen = self.area.get();
+        let window = &mut self.windows[index];
+        let area = &mut window.area;
+        match grab {
+            Grab::Move(dx, dy) => {
+                let max_x = screen.right().saturating_sub(area.width).max(screen.x);
+                let max_y = screen.bottom().saturating_sub(area.height).max(screen.y);
+                area.x = x.saturating_sub(dx).clamp(screen.x, max_x);
+                area.y = y.saturating_sub(dy).clamp(screen.y, max_y);
+            }
+            Grab::Resize { right, bottom } => {
+                let (min_width, min_height) = window.min_size;
+                if right {
+                    let width = (x + 1).saturating_sub(area.x).max(min_width);
+                    area.width = width.min(screen.right().saturating_sub(area.x));
+                }
+                if bottom {
+                    let height = (y + 1).saturating_sub(area.y).max(min_height);
+                    area.height = height.min(screen.bottom().saturating_sub(area.y));
+                }
+            }
+        }
+    }
+
+    /// Passes `event` to the child of window at `index`, the child of the top window sees itself focused.
+    fn forward(
+        &mut self,
+        index: usize,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let inner = self.windows.last().map(|w| w.child.id());
+        let child = &mut self.windows[index].child;
+        let result = cx.with_inner_focus(self.id, inner, |cx| child.try_handle_event(event, cx));
+        self.redirect_focus(cx);
+        result
+    }
+
+    /// Raises window whose child requested focus.
+    fn redirect_focus(&mut self, cx: &mut Context<S, E>) {
+        let windows = &self.windows;
+        if let Some(id) =
+            cx.redirect_focus(self.id, |id| windows.iter().any(|w| w.child.id() == id))
+        {
+            let index = self.windows.iter().position(|w| w.child.id() == id);
+            let window = self.windows.remove(index.unwrap());
+            self.windows.push(window);
+        }
+    }
+
+    fn handle_mouse(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Event::Terminal(TerminalEvent::Mouse(mouse)) = *event else {
+            return Ok(());
+        };
+        let (x, y) = (mouse.column, mouse.row);
+
+        // Drags continue outside of the window until the button is released.
+        if let Some((id, grab)) = self.grab {
+            let index = self.windows.iter().position(|w| w.id == id);
+            match (mouse.kind, index) {
+                (MouseEventKind::Drag(MouseButton::Left), Some(index)) => {
+                    self.drag(index, grab, x, y);
+                    cx.set_pointer_shape(PointerShape::Grabbing);
+                    cx.request_redraw();
+                    _ = event.consume();
+                    return Ok(());
+                }
+                (MouseEventKind::Up(MouseButton::Left), _) => {
+                    self.grab = None;
+                    _ = event.consume();
+                    return Ok(());
+                }
+                _ => self.grab = None,
+            }
+        }
+
+        let point = Rect::new(x, y, 1, 1);
+        let Some(index) = self.windows.iter().rposition(|w| w.area.intersects(point)) else {
+            return Ok(());
+        };
+        let grab = Self::grab_at(&self.windows[index], x, y);
+        if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+            let window = self.windows.remove(index);
+            let id = window.id;
+            self.windows.push(window);
+            cx.focus(self.id);
+            cx.request_redraw();
+            self.grab = grab.map(|grab| (id, grab));
+        }
+
+        let index = self.windows.iter().rposition(|w| w.area.intersects(point));
+        match grab {
+            Some(Grab::Move(..)) => cx.set_pointer_shape(PointerShape::Grab),
+            Some(Grab::Resize { right: true, .. }) => cx.set_pointer_shape(PointerShape::EwResize),
+            Some(Grab::Resize { .. }) => cx.set_pointer_shape(PointerShape::NsResize),
+            None if self.windows[index.unwrap()].inner().intersects(point) => {
+                self.forward(index.unwrap(), event, cx)?;
+            }
+            None => {}
+        }
+        // Windows are opaque to the mouse.
+        if !event.is_consumed() {
+            _ = event.consume();
+        }
+        Ok(())
+    }
+}
+
+impl<S: 'static, E: 'static> Component<S, E> for WindowManager<S, E> {
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn focusable(&self) -> bool {
+        !self.windows.is_empty()
+    }
+
+    fn on_mount(&mut self, cx: &mut Context<S, E>) {
+        self.mounted = true;
+        for window in &mut self.windows {
+            window.child.on_mount(cx);
+        }
+    }
+
+    fn on_unmount(&mut self, cx: &mut Context<S, E>) {
+        self.mounted = false;
+        for window in &mut self.windows {
+            window.child.on_unmount(cx);
+        }
+    }
+
+    fn view(&self, area: Rect, buf: &mut Buffer, state: &S) {
+        self.area.set(area);
+        for (i, window) in self.windows.iter().enumerate() {
+            let window_area = window.area.intersection(area);
+            let style = match i + 1 == self.windows.len() {
+                true => self.focused_border_style,
+                false => self.border_style,
+            };
+            Clear.render(window_area, buf);
+            Block::new()
+                .borders(Borders::ALL)
+                .border_style(style)
+                .title(window.title.as_str())
+                .render(window_area, buf);
+            window
+                .child
+                .view(window.inner().intersection(area), buf, state);
+        }
+    }
+
+    fn cursor(&self, _area: Rect, state: &S) -> Option<(u16, u16)> {
+        let window = self.windows.last()?;
+        window
+            .child
+            .cursor(window.inner().intersection(self.area.get()), state)
+    }
+
+    fn cursor_style(&self, state: &S) -> Option<CursorStyle> {
+        self.windows.last()?.child.cursor_style(state)
+    }
+
+    fn title(&self) -> Option<&str> {
+        self.windows.last()?.child.title()
+    }
+
+    fn actions(&self) -> &[Action] {
+        self.windows.last().map_or(&[], |w| w.child.actions())
+    }
+
+    fn handle_action(&mut self, action: &Action, cx: &mut Context<S, E>) -> bool {
+        let inner = self.windows.last().map(|w| w.child.id());
+        let Some(window) = self.windows.last_mut() else {
+            return false;
+        };
+        let handled =
+            cx.with_inner_focus(self.id, inner, |cx| window.child.handle_action(action, cx));
+        self.redirect_focus(cx);
+        handled
+    }
+
+    fn handle_message(&mut self, message: Box<dyn Any>, cx: &mut Context<S, E>) {
+        if let Ok(window) = message.downcast::<Window<S, E>>() {
+            self.open(*window, cx);
+        }
+    }
+
+    fn handle_event(&mut self, event: &mut Event<E>, cx: &mut Context<S, E>) {
+        // Errors of children can only be reported through `try_handle_event`.
+        _ = self.try_handle_event(event, cx);
+    }
+
+    fn try_handle_event(
+        &mut self,
+        event: &mut Event<E>,
+        cx: &mut Context<S, E>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Event::Terminal(TerminalEvent::Mouse(_)) = event {
+            return self.handle_mouse(event, cx);
+        }
+        // From the top window down.
+        for index in (0..self.windows.len()).rev() {
+            self.forward(index, event, cx)?;
+            if event.is_consumed() || index >= self.windows.len() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}